@@ -0,0 +1,202 @@
+//! Access log of proxied requests
+//!
+//! The middleware logs one event (target `access`) per proxied request once it has
+//! been served, tagged with the request's ID. Handlers fill in what only they know — the
+//! JSON-RPC methods or beacon API route, the node that served the request and how many
+//! attempts it took — through the [`AccessRecord`] in the request extensions.
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::body::HttpBody;
+use axum::extract::{ConnectInfo, Request};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::info;
+
+use crate::auth::ProxyRoute;
+use crate::request_id::RequestId;
+
+/// Target access log events are logged under
+pub const TARGET: &str = "access";
+
+/// Details of a request filled in while it is served
+#[derive(Debug, Default)]
+struct Entry {
+    api_key: Option<String>,
+    route: Option<&'static str>,
+    methods: Option<String>,
+    node: Option<String>,
+    attempts: usize,
+}
+
+/// Shared access log entry of a request, found in its extensions when the access
+/// log is enabled
+#[derive(Debug, Clone, Default)]
+pub struct AccessRecord(Arc<Mutex<Entry>>);
+
+impl AccessRecord {
+    /// Set the name of the API key the request was made with
+    pub fn set_api_key(&self, name: &str) {
+        self.entry().api_key = Some(name.to_string());
+    }
+
+    /// Set the beacon API route template of a CL request
+    pub fn set_route(&self, route: &'static str) {
+        self.entry().route = Some(route);
+    }
+
+    /// Set the JSON-RPC method(s) of an EL request
+    pub fn set_methods(&self, methods: String) {
+        self.entry().methods = Some(methods);
+    }
+
+    /// Set the node that served the request and the number of attempts made
+    pub fn set_upstream(&self, node: &str, attempts: usize) {
+        let mut entry = self.entry();
+        entry.node = Some(node.to_string());
+        entry.attempts = attempts;
+    }
+
+    fn entry(&self) -> std::sync::MutexGuard<'_, Entry> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Middleware logging every request it serves to the access log
+pub async fn log_request(mut request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let record = AccessRecord::default();
+    request.extensions_mut().insert(record.clone());
+
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let forwarded_for = request
+        .headers()
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let bytes_in = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    let request_id = request.extensions().get::<RequestId>().cloned();
+    let http_method = request.method().clone();
+    let proxy_route = ProxyRoute::from_path(request.uri().path()).map_or("other", |r| r.label());
+
+    let response = next.run(request).await;
+
+    let entry = record.entry();
+    info!(
+        target: TARGET,
+        request_id = request_id.as_ref().map_or("-", RequestId::as_str),
+        client_ip = client_ip.as_deref().unwrap_or("-"),
+        forwarded_for = forwarded_for.as_deref().unwrap_or("-"),
+        api_key = entry.api_key.as_deref().unwrap_or("-"),
+        http_method = %http_method,
+        route = entry.route.unwrap_or(proxy_route),
+        methods = entry.methods.as_deref().unwrap_or("-"),
+        node = entry.node.as_deref().unwrap_or("-"),
+        attempts = entry.attempts,
+        status = response.status().as_u16(),
+        bytes_in,
+        // Unknown for streamed responses
+        bytes_out = response.body().size_hint().exact(),
+        latency_ms = start.elapsed().as_secs_f64() * 1000.0,
+        "request"
+    );
+    drop(entry);
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::extract::Extension;
+    use std::io::Write;
+    use tower::ServiceExt;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    /// Log writer capturing everything written to it
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_log_records_request() {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_writer(capture.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route(
+                "/el",
+                axum::routing::post(|Extension(record): Extension<AccessRecord>| async move {
+                    record.set_api_key("indexer");
+                    record.set_methods("eth_call".to_string());
+                    record.set_upstream("geth-1", 2);
+                    "0123456789"
+                }),
+            )
+            .layer(axum::middleware::from_fn(log_request));
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .header("content-length", "4")
+            .header("x-forwarded-for", "203.0.113.7")
+            .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))))
+            .body(Body::from("null"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["target"], "access");
+        assert_eq!(line["client_ip"], "10.0.0.1");
+        assert_eq!(line["forwarded_for"], "203.0.113.7");
+        assert_eq!(line["api_key"], "indexer");
+        assert_eq!(line["http_method"], "POST");
+        assert_eq!(line["route"], "el");
+        assert_eq!(line["methods"], "eth_call");
+        assert_eq!(line["node"], "geth-1");
+        assert_eq!(line["attempts"], 2);
+        assert_eq!(line["status"], 200);
+        assert_eq!(line["bytes_in"], 4);
+        assert_eq!(line["bytes_out"], 10);
+        assert!(line["latency_ms"].is_number());
+        assert!(line["timestamp"].is_string());
+    }
+}
@@ -0,0 +1,682 @@
+//! Client authentication with API keys
+//!
+//! Keys are accepted from a request header, a query parameter or a path segment
+//! (`/el/<key>`, `/el/ws/<key>`, `/cl/<key>/...`). Each key has a token bucket refilled
+//! in compute units per second, where every JSON-RPC call costs its method's weight,
+//! and an optional cap on concurrent WebSocket connections.
+
+use axum::Json;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, StatusCode, Uri, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::access_log::AccessRecord;
+use crate::metrics::VixyMetrics;
+use crate::proxy::jsonrpc;
+use crate::state::AppState;
+
+/// Proxy endpoint a request is made to, used for error shapes and metric labels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyRoute {
+    El,
+    ElWs,
+    Cl,
+}
+
+impl ProxyRoute {
+    /// Classify a request path
+    pub fn from_path(path: &str) -> Option<Self> {
+        let is_under = |prefix: &str| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        };
+        if is_under("/el/ws") {
+            Some(Self::ElWs)
+        } else if is_under("/el") {
+            Some(Self::El)
+        } else if is_under("/cl") {
+            Some(Self::Cl)
+        } else {
+            None
+        }
+    }
+
+    /// Metric label for the route
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::El => "el",
+            Self::ElWs => "el_ws",
+            Self::Cl => "cl",
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::El => "/el",
+            Self::ElWs => "/el/ws",
+            Self::Cl => "/cl",
+        }
+    }
+
+    /// Build an error response in the shape clients of this route expect
+    ///
+    /// EL clients get a JSON-RPC error object, CL clients a beacon API error.
+    pub fn error_response(self, status: StatusCode, id: Value, message: &str) -> Response {
+        match self {
+            Self::El | Self::ElWs => {
+                let code = if status == StatusCode::TOO_MANY_REQUESTS {
+                    jsonrpc::LIMIT_EXCEEDED
+                } else {
+                    jsonrpc::UNAUTHORIZED
+                };
+                (status, Json(jsonrpc::error_response(id, code, message))).into_response()
+            }
+            Self::Cl => (
+                status,
+                Json(json!({"code": status.as_u16(), "message": message})),
+            )
+                .into_response(),
+        }
+    }
+
+    /// Build a 429 response, telling the client when to retry if known
+    pub fn rate_limited_response(self, id: Value, retry_after: Option<Duration>) -> Response {
+        let mut response =
+            self.error_response(StatusCode::TOO_MANY_REQUESTS, id, "rate limit exceeded");
+        if let Some(retry_after) = retry_after {
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            if let Ok(value) = HeaderValue::from_str(&seconds.to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+        }
+        response
+    }
+}
+
+/// Compute-unit weights of JSON-RPC methods
+#[derive(Debug)]
+struct MethodWeights {
+    default: u32,
+    methods: HashMap<String, u32>,
+}
+
+impl MethodWeights {
+    fn weight(&self, method: Option<&str>) -> u32 {
+        method
+            .and_then(|m| self.methods.get(m))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Compute units charged for a request payload (a batch costs the sum of its calls)
+    fn cost(&self, payload: Option<&Value>) -> u32 {
+        match payload {
+            Some(Value::Array(calls)) => calls
+                .iter()
+                .map(|call| self.weight(jsonrpc::method_of(call)))
+                .sum(),
+            Some(call) => self.weight(jsonrpc::method_of(call)),
+            None => self.default,
+        }
+    }
+}
+
+/// Token bucket refilled continuously at a fixed rate
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, capacity: u32) -> Self {
+        Self {
+            capacity: f64::from(capacity),
+            rate: f64::from(rate),
+            tokens: f64::from(capacity),
+            updated: Instant::now(),
+        }
+    }
+
+    /// Take `cost` tokens, or return how long until enough tokens are available
+    ///
+    /// A cost above the bucket size is capped to it, so expensive calls are still
+    /// possible with a full bucket.
+    fn try_take(&mut self, cost: f64, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+
+        let cost = cost.min(self.capacity);
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((cost - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// Runtime state of one API key
+#[derive(Debug)]
+pub struct ApiKeyState {
+    /// Name used in logs and metrics
+    pub name: String,
+    weights: Arc<MethodWeights>,
+    /// None when the key is not rate limited
+    bucket: Option<Mutex<TokenBucket>>,
+    max_ws_connections: usize,
+    ws_connections: AtomicUsize,
+    /// JSON-RPC methods this key may call
+    pub method_filter: crate::config::MethodFilter,
+}
+
+impl ApiKeyState {
+    /// Charge a request against the key's rate limit
+    ///
+    /// Returns how long to wait before retrying if the key is over its limit.
+    pub fn charge(&self, payload: Option<&Value>) -> Result<(), Duration> {
+        let Some(bucket) = &self.bucket else {
+            return Ok(());
+        };
+        let cost = f64::from(self.weights.cost(payload));
+        bucket
+            .lock()
+            .expect("token bucket lock poisoned")
+            .try_take(cost, Instant::now())
+    }
+
+    /// Reserve a WebSocket connection slot, or None if the key is at its cap
+    pub fn try_acquire_ws(self: &Arc<Self>) -> Option<WsSlot> {
+        let acquired =
+            self.ws_connections
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                    (self.max_ws_connections == 0 || current < self.max_ws_connections)
+                        .then_some(current + 1)
+                });
+        let count = acquired.ok()? + 1;
+        VixyMetrics::set_api_key_ws_connections(&self.name, count as u64);
+        Some(WsSlot { key: self.clone() })
+    }
+}
+
+/// A WebSocket connection slot, released when dropped
+#[derive(Debug)]
+pub struct WsSlot {
+    key: Arc<ApiKeyState>,
+}
+
+impl Drop for WsSlot {
+    fn drop(&mut self) {
+        let count = self.key.ws_connections.fetch_sub(1, Ordering::SeqCst) - 1;
+        VixyMetrics::set_api_key_ws_connections(&self.key.name, count as u64);
+    }
+}
+
+/// The API key a request was authenticated with, stored as a request extension
+#[derive(Debug, Clone)]
+pub struct AuthorizedKey(pub Arc<ApiKeyState>);
+
+/// Looks up API keys and where clients may send them
+#[derive(Debug)]
+pub struct Authenticator {
+    header: String,
+    query_param: String,
+    path_segment: bool,
+    keys: HashMap<String, Arc<ApiKeyState>>,
+}
+
+impl Authenticator {
+    /// Create an authenticator from the auth configuration
+    pub fn new(config: &crate::config::Auth) -> Self {
+        let weights = Arc::new(MethodWeights {
+            default: config.default_weight,
+            methods: config.method_weights.clone(),
+        });
+
+        let keys = config
+            .keys
+            .iter()
+            .map(|key| {
+                let bucket = (key.requests_per_second > 0).then(|| {
+                    let burst = key.burst.unwrap_or(key.requests_per_second).max(1);
+                    Mutex::new(TokenBucket::new(key.requests_per_second, burst))
+                });
+                let state = ApiKeyState {
+                    name: key.name.clone(),
+                    weights: weights.clone(),
+                    bucket,
+                    max_ws_connections: key.max_ws_connections,
+                    ws_connections: AtomicUsize::new(0),
+                    method_filter: key.method_filter.clone(),
+                };
+                (key.key.clone(), Arc::new(state))
+            })
+            .collect();
+
+        Self {
+            header: config.header.clone(),
+            query_param: config.query_param.clone(),
+            path_segment: config.path_segment,
+            keys,
+        }
+    }
+
+    /// Find the API key of a request and remove it from the URI
+    ///
+    /// The key is looked up in the header, then the query parameter, then the first
+    /// path segment after the route prefix. A key in the query or path is stripped
+    /// so it is never forwarded to upstream nodes.
+    fn authenticate(&self, request: &mut Request, route: ProxyRoute) -> Option<Arc<ApiKeyState>> {
+        let uri = request.uri().clone();
+        let mut found = request
+            .headers()
+            .get(&self.header)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|k| self.keys.get(k));
+
+        let query = uri.query().unwrap_or_default();
+        if found.is_none() {
+            found = url::form_urlencoded::parse(query.as_bytes())
+                .find(|(name, _)| *name == self.query_param)
+                .and_then(|(_, key)| self.keys.get(key.as_ref()));
+        }
+
+        let mut path = uri.path().to_string();
+        if self.path_segment
+            && let Some((key, stripped)) = split_path_key(&path, route)
+            && let Some(state) = self.keys.get(key)
+        {
+            found = found.or(Some(state));
+            path = stripped;
+        }
+
+        let query = strip_query_param(query, &self.query_param);
+        let path_and_query = if query.is_empty() {
+            path
+        } else {
+            format!("{path}?{query}")
+        };
+        if path_and_query != uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/") {
+            let mut parts = uri.into_parts();
+            parts.path_and_query = path_and_query.parse().ok();
+            if let Ok(rewritten) = Uri::from_parts(parts) {
+                *request.uri_mut() = rewritten;
+            }
+        }
+
+        found.cloned()
+    }
+}
+
+/// Split an API key segment off a proxy path: `/cl/<key>/eth/v1/...` → (`<key>`, `/cl/eth/v1/...`)
+fn split_path_key(path: &str, route: ProxyRoute) -> Option<(&str, String)> {
+    let prefix = route.prefix();
+    let rest = path.strip_prefix(prefix)?.strip_prefix('/')?;
+    let (segment, tail) = match rest.split_once('/') {
+        Some((segment, tail)) => (segment, Some(tail)),
+        None => (rest, None),
+    };
+    if segment.is_empty() {
+        return None;
+    }
+    let stripped = match tail {
+        Some(tail) => format!("{prefix}/{tail}"),
+        None => prefix.to_string(),
+    };
+    Some((segment, stripped))
+}
+
+/// Remove a parameter from a query string, keeping the other pairs untouched
+fn strip_query_param(query: &str, param: &str) -> String {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(param))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Middleware enforcing API keys and rate limits on the proxy endpoints
+///
+/// A no-op when auth is disabled.
+pub async fn require_api_key(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(auth) = &state.auth else {
+        return next.run(request).await;
+    };
+    let Some(route) = ProxyRoute::from_path(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let Some(key) = auth.authenticate(&mut request, route) else {
+        debug!(
+            route = route.label(),
+            "Rejecting request without a valid API key"
+        );
+        VixyMetrics::inc_api_key_request("anonymous", route.label(), "unauthorized");
+        return route.error_response(
+            StatusCode::UNAUTHORIZED,
+            Value::Null,
+            "missing or invalid API key",
+        );
+    };
+
+    // JSON-RPC bodies are charged by method weight
+    let (request, payload) = if route == ProxyRoute::El {
+        let (parts, body) = request.into_parts();
+        let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!(error = %e, "Failed to read request body");
+                return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+            }
+        };
+        let payload = serde_json::from_slice::<Value>(&bytes).ok();
+        (Request::from_parts(parts, Body::from(bytes)), payload)
+    } else {
+        (request, None)
+    };
+
+    if let Err(retry_after) = key.charge(payload.as_ref()) {
+        debug!(key = %key.name, route = route.label(), "API key over its rate limit");
+        VixyMetrics::inc_api_key_request(&key.name, route.label(), "rate_limited");
+        let id = payload
+            .as_ref()
+            .and_then(jsonrpc::id_of)
+            .cloned()
+            .unwrap_or(Value::Null);
+        return route.rate_limited_response(id, Some(retry_after));
+    }
+
+    VixyMetrics::inc_api_key_request(&key.name, route.label(), "allowed");
+    let mut request = request;
+    if let Some(record) = request.extensions().get::<AccessRecord>() {
+        record.set_api_key(&key.name);
+    }
+    request.extensions_mut().insert(AuthorizedKey(key));
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ApiKey, Auth};
+
+    fn make_auth(requests_per_second: u32, max_ws_connections: usize) -> Authenticator {
+        Authenticator::new(&Auth {
+            enabled: true,
+            method_weights: HashMap::from([("eth_getLogs".to_string(), 10)]),
+            keys: vec![ApiKey {
+                name: "indexer".to_string(),
+                key: "secret".to_string(),
+                requests_per_second,
+                burst: None,
+                max_ws_connections,
+                method_filter: Default::default(),
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn authenticate(
+        auth: &Authenticator,
+        uri: &str,
+        header: Option<&str>,
+    ) -> (Option<String>, String) {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(key) = header {
+            builder = builder.header("x-api-key", key);
+        }
+        let mut request = builder.body(Body::empty()).unwrap();
+        let route = ProxyRoute::from_path(request.uri().path()).unwrap();
+        let key = auth.authenticate(&mut request, route);
+        (key.map(|k| k.name.clone()), request.uri().to_string())
+    }
+
+    #[test]
+    fn test_route_from_path() {
+        assert_eq!(ProxyRoute::from_path("/el"), Some(ProxyRoute::El));
+        assert_eq!(ProxyRoute::from_path("/el/secret"), Some(ProxyRoute::El));
+        assert_eq!(ProxyRoute::from_path("/el/ws/"), Some(ProxyRoute::ElWs));
+        assert_eq!(
+            ProxyRoute::from_path("/cl/eth/v1/node/health"),
+            Some(ProxyRoute::Cl)
+        );
+        assert_eq!(ProxyRoute::from_path("/status"), None);
+        assert_eq!(ProxyRoute::from_path("/clx"), None);
+    }
+
+    #[test]
+    fn test_key_from_header() {
+        let auth = make_auth(0, 0);
+
+        let (key, uri) = authenticate(&auth, "/el", Some("secret"));
+        assert_eq!(key.as_deref(), Some("indexer"));
+        assert_eq!(uri, "/el");
+
+        let (key, _) = authenticate(&auth, "/el", Some("wrong"));
+        assert_eq!(key, None);
+    }
+
+    #[test]
+    fn test_key_from_query_is_stripped() {
+        let auth = make_auth(0, 0);
+
+        let (key, uri) = authenticate(
+            &auth,
+            "/cl/eth/v1/node/peers?state=connected&api_key=secret",
+            None,
+        );
+
+        assert_eq!(key.as_deref(), Some("indexer"));
+        assert_eq!(uri, "/cl/eth/v1/node/peers?state=connected");
+    }
+
+    #[test]
+    fn test_key_from_path_is_stripped() {
+        let auth = make_auth(0, 0);
+
+        let (key, uri) = authenticate(&auth, "/cl/secret/eth/v1/node/health", None);
+        assert_eq!(key.as_deref(), Some("indexer"));
+        assert_eq!(uri, "/cl/eth/v1/node/health");
+
+        let (key, uri) = authenticate(&auth, "/el/ws/secret", None);
+        assert_eq!(key.as_deref(), Some("indexer"));
+        assert_eq!(uri, "/el/ws");
+
+        // Regular beacon paths are left alone
+        let (key, uri) = authenticate(&auth, "/cl/eth/v1/node/health", None);
+        assert_eq!(key, None);
+        assert_eq!(uri, "/cl/eth/v1/node/health");
+    }
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10, 10);
+
+        assert!(bucket.try_take(10.0, start).is_ok());
+        let wait = bucket.try_take(5.0, start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        assert!(
+            bucket
+                .try_take(5.0, start + Duration::from_millis(500))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_charge_uses_method_weights() {
+        let auth = make_auth(10, 0);
+        let key = auth.keys.get("secret").unwrap();
+
+        let logs = json!({"jsonrpc": "2.0", "id": 1, "method": "eth_getLogs", "params": []});
+        assert!(key.charge(Some(&logs)).is_ok());
+
+        // The bucket of 10 units is drained by one eth_getLogs call
+        let block_number = json!({"jsonrpc": "2.0", "id": 2, "method": "eth_blockNumber"});
+        assert!(key.charge(Some(&block_number)).is_err());
+    }
+
+    #[test]
+    fn test_unlimited_key_is_never_rate_limited() {
+        let auth = make_auth(0, 0);
+        let key = auth.keys.get("secret").unwrap();
+
+        for _ in 0..1000 {
+            assert!(key.charge(None).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_ws_connection_cap() {
+        let auth = make_auth(0, 1);
+        let key = auth.keys.get("secret").unwrap();
+
+        let slot = key.try_acquire_ws().expect("First connection should fit");
+        assert!(key.try_acquire_ws().is_none());
+
+        drop(slot);
+        assert!(key.try_acquire_ws().is_some());
+    }
+
+    // =========================================================================
+    // Middleware tests
+    // =========================================================================
+
+    async fn make_app() -> (axum::Router, wiremock::MockServer) {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x1"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = crate::config::Config::parse(&format!(
+            r#"
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "{uri}"
+ws_url = "ws://localhost:8546"
+
+[[cl]]
+name = "lighthouse-1"
+url = "{uri}"
+
+[auth]
+enabled = true
+
+[[auth.keys]]
+name = "indexer"
+key = "secret"
+requests_per_second = 1
+"#,
+            uri = mock_server.uri()
+        ))
+        .unwrap();
+        let state = Arc::new(AppState::new(&config));
+        for node in state.el_nodes.write().await.iter_mut() {
+            node.is_healthy = true;
+        }
+
+        let app = axum::Router::new()
+            .route(
+                "/el",
+                axum::routing::post(crate::proxy::http::el_proxy_handler),
+            )
+            .route(
+                "/cl/{*path}",
+                axum::routing::any(crate::proxy::http::cl_proxy_handler),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                require_api_key,
+            ))
+            .with_state(state);
+        (app, mock_server)
+    }
+
+    async fn send(
+        app: &axum::Router,
+        request: Request,
+    ) -> (StatusCode, axum::http::HeaderMap, Value) {
+        use tower::util::ServiceExt;
+
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            headers,
+            serde_json::from_slice(&body).unwrap_or_default(),
+        )
+    }
+
+    fn el_request(key: Option<&str>) -> Request {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .header("content-type", "application/json");
+        if let Some(key) = key {
+            builder = builder.header("x-api-key", key);
+        }
+        builder
+            .body(Body::from(
+                r#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":7}"#,
+            ))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_missing_key_rejected_in_route_shape() {
+        let (app, _mock) = make_app().await;
+
+        let (status, _, body) = send(&app, el_request(None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"]["code"], jsonrpc::UNAUTHORIZED);
+
+        let request = Request::builder()
+            .uri("/cl/eth/v1/node/health")
+            .body(Body::empty())
+            .unwrap();
+        let (status, _, body) = send(&app, request).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["code"], 401);
+    }
+
+    #[tokio::test]
+    async fn test_key_over_limit_gets_429() {
+        let (app, _mock) = make_app().await;
+
+        let (status, _, _) = send(&app, el_request(Some("secret"))).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, headers, body) = send(&app, el_request(Some("secret"))).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["error"]["code"], jsonrpc::LIMIT_EXCEEDED);
+        assert!(headers.contains_key(header::RETRY_AFTER));
+    }
+}
@@ -4,6 +4,7 @@
 
 use eyre::{Result, WrapErr, eyre};
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 
 /// Configuration error type
 #[derive(Debug, thiserror::Error)]
@@ -28,12 +29,29 @@ pub struct Global {
     pub max_cl_lag_slots: u64,
     /// Health check interval in milliseconds
     pub health_check_interval_ms: u64,
+    /// Timeout for a single health check request in milliseconds
+    /// (defaults to the health check interval, at most one second)
+    pub health_check_timeout_ms: Option<u64>,
     /// Proxy request timeout in milliseconds
     pub proxy_timeout_ms: u64,
     /// Maximum number of retry attempts for failed proxy requests
     pub max_retries: u32,
     /// Number of consecutive health check failures before marking node as unhealthy
     pub health_check_max_failures: u32,
+    /// Number of consecutive health check successes before marking node as healthy again
+    pub health_check_recovery_successes: u32,
+    /// Minimum time failover stays active before traffic returns to primary nodes
+    pub failover_min_dwell_ms: u64,
+    /// Maximum number of calls accepted in a single JSON-RPC batch (0 = unlimited)
+    pub max_batch_size: usize,
+    /// Whether to split JSON-RPC batches across all healthy EL nodes
+    pub split_batches: bool,
+    /// Whether identical concurrent read requests share a single upstream call
+    pub coalesce_requests: bool,
+    /// Number of recent health events kept for `/status/events`
+    pub event_log_size: usize,
+    /// Time without a new chain head before the chain is reported stalled (0 = never)
+    pub chain_stall_timeout_ms: u64,
 }
 
 /// Metrics configuration settings
@@ -45,6 +63,8 @@ pub struct Metrics {
     /// Port to serve metrics on (if separate from main server)
     /// If None, metrics are served on the main server at /metrics
     pub port: Option<u16>,
+    /// JSON-RPC methods EL metrics are labelled with; other methods are labelled `other`
+    pub el_methods: Vec<String>,
 }
 
 impl Default for Metrics {
@@ -52,6 +72,35 @@ impl Default for Metrics {
         Self {
             enabled: true,
             port: None,
+            el_methods: [
+                "eth_blockNumber",
+                "eth_call",
+                "eth_chainId",
+                "eth_estimateGas",
+                "eth_feeHistory",
+                "eth_gasPrice",
+                "eth_getBalance",
+                "eth_getBlockByHash",
+                "eth_getBlockByNumber",
+                "eth_getBlockReceipts",
+                "eth_getCode",
+                "eth_getLogs",
+                "eth_getStorageAt",
+                "eth_getTransactionByHash",
+                "eth_getTransactionCount",
+                "eth_getTransactionReceipt",
+                "eth_maxPriorityFeePerGas",
+                "eth_sendRawTransaction",
+                "eth_syncing",
+                "net_version",
+                "web3_clientVersion",
+                "debug_traceTransaction",
+                "debug_traceCall",
+                "trace_block",
+                "trace_transaction",
+            ]
+            .map(String::from)
+            .to_vec(),
         }
     }
 }
@@ -62,13 +111,51 @@ impl Default for Global {
             max_el_lag_blocks: 5,
             max_cl_lag_slots: 3,
             health_check_interval_ms: 1000,
+            health_check_timeout_ms: None,
             proxy_timeout_ms: 30000,
             max_retries: 2,
             health_check_max_failures: 3,
+            health_check_recovery_successes: 1,
+            failover_min_dwell_ms: 0,
+            max_batch_size: 1000,
+            split_batches: false,
+            coalesce_requests: false,
+            event_log_size: 1000,
+            chain_stall_timeout_ms: 60000,
         }
     }
 }
 
+impl Global {
+    /// Timeout for a single health check request in milliseconds
+    pub fn health_check_timeout(&self) -> u64 {
+        self.health_check_timeout_ms
+            .unwrap_or(self.health_check_interval_ms.min(1000))
+    }
+
+    /// Validate the global settings
+    fn validate(&self) -> Result<()> {
+        let Some(timeout_ms) = self.health_check_timeout_ms else {
+            return Ok(());
+        };
+        if timeout_ms == 0 {
+            return Err(ConfigError::ValidationFailed(
+                "health_check_timeout_ms must be greater than 0".to_string(),
+            )
+            .into());
+        }
+        if timeout_ms > self.health_check_interval_ms {
+            return Err(ConfigError::ValidationFailed(format!(
+                "health_check_timeout_ms ({timeout_ms}) must not exceed health_check_interval_ms ({})",
+                self.health_check_interval_ms
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
 /// EL (Execution Layer) node configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct ElNode {
@@ -78,6 +165,9 @@ pub struct ElNode {
     pub http_url: String,
     /// WebSocket URL for subscriptions
     pub ws_url: String,
+    /// Tags used by method routing rules (e.g. "archive")
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl ElNode {
@@ -97,6 +187,263 @@ pub struct El {
     /// Backup EL nodes - only used when ALL primary nodes are unavailable
     #[serde(default)]
     pub backup: Vec<ElNode>,
+    /// Method routing rules, evaluated in order
+    #[serde(default)]
+    pub routes: Vec<MethodRoute>,
+    /// Transaction broadcast settings
+    #[serde(default)]
+    pub broadcast: Broadcast,
+    /// Response cache settings
+    #[serde(default)]
+    pub cache: Cache,
+    /// Methods clients may call on /el and /el/ws
+    #[serde(default)]
+    pub method_filter: MethodFilter,
+    /// Hedged request settings
+    #[serde(default)]
+    pub hedging: Hedging,
+    /// Retries of calls answered with a retryable JSON-RPC response
+    #[serde(default)]
+    pub retry: Retry,
+}
+
+/// Retrying EL calls on another node when the response suggests the node was behind
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Retry {
+    /// Whether retryable responses are retried (up to `global.max_retries` times)
+    pub enabled: bool,
+    /// Responses that trigger a retry; a response is retried if any rule matches
+    pub rules: Vec<RetryRule>,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rules: vec![
+                RetryRule {
+                    error_codes: vec![-32000],
+                    message_contains: Some("header not found".to_string()),
+                    ..Default::default()
+                },
+                RetryRule {
+                    message_contains: Some("unknown block".to_string()),
+                    ..Default::default()
+                },
+                RetryRule {
+                    methods: vec![
+                        "eth_getBlockByNumber".to_string(),
+                        "eth_getBlockReceipts".to_string(),
+                    ],
+                    null_result: true,
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+}
+
+/// A JSON-RPC response that is retried on another node
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RetryRule {
+    /// Methods the rule applies to; a trailing `*` matches by prefix, empty matches any
+    pub methods: Vec<String>,
+    /// Error codes that are retried (empty = any code)
+    pub error_codes: Vec<i64>,
+    /// Text the error message must contain (case-insensitive)
+    pub message_contains: Option<String>,
+    /// Whether a null result is retried
+    pub null_result: bool,
+}
+
+impl RetryRule {
+    /// Check whether a response to a call of `method` should be retried
+    ///
+    /// Returns the retry reason ("error" or "null_result") if the rule matches.
+    pub fn retry_reason(&self, method: &str, response: &serde_json::Value) -> Option<&'static str> {
+        if !self.methods.is_empty() && !self.methods.iter().any(|p| method_matches(p, method)) {
+            return None;
+        }
+
+        let Some(error) = response.get("error").filter(|e| !e.is_null()) else {
+            let null_result = response.get("result").is_some_and(|r| r.is_null());
+            return (self.null_result && null_result).then_some("null_result");
+        };
+
+        if !self.matches_errors() {
+            return None;
+        }
+        let code_matches = self.error_codes.is_empty()
+            || error
+                .get("code")
+                .and_then(|c| c.as_i64())
+                .is_some_and(|c| self.error_codes.contains(&c));
+        let message_matches = self.message_contains.as_ref().is_none_or(|text| {
+            error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .is_some_and(|m| m.to_lowercase().contains(&text.to_lowercase()))
+        });
+        (code_matches && message_matches).then_some("error")
+    }
+
+    /// Whether the rule matches error responses at all
+    fn matches_errors(&self) -> bool {
+        !self.error_codes.is_empty() || self.message_contains.is_some()
+    }
+}
+
+/// Hedging of slow EL reads by sending them to a second node
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Hedging {
+    /// Whether hedged requests are enabled
+    pub enabled: bool,
+    /// Methods that may be hedged; a trailing `*` matches by prefix. Non-idempotent
+    /// methods are never hedged, even if they match.
+    pub methods: Vec<String>,
+    /// Latency percentile of recent requests after which a hedge is sent (e.g. 95)
+    pub percentile: f64,
+    /// Lower bound of the hedge delay (in milliseconds)
+    pub min_delay_ms: u64,
+    /// Upper bound of the hedge delay, also used until enough latencies are known
+    /// (in milliseconds)
+    pub max_delay_ms: u64,
+}
+
+impl Default for Hedging {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            methods: vec![
+                "eth_call".to_string(),
+                "eth_getBalance".to_string(),
+                "eth_getBlockByHash".to_string(),
+                "eth_getBlockByNumber".to_string(),
+                "eth_getLogs".to_string(),
+                "eth_getTransactionByHash".to_string(),
+                "eth_getTransactionReceipt".to_string(),
+            ],
+            percentile: 95.0,
+            min_delay_ms: 10,
+            max_delay_ms: 500,
+        }
+    }
+}
+
+impl Hedging {
+    /// Check whether calls to a JSON-RPC method may be hedged
+    pub fn matches(&self, method: &str) -> bool {
+        crate::proxy::jsonrpc::is_idempotent(method)
+            && self.methods.iter().any(|p| method_matches(p, method))
+    }
+}
+
+/// In-memory caching of EL JSON-RPC responses
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Cache {
+    /// Whether response caching is enabled
+    pub enabled: bool,
+    /// Maximum memory used by cached responses, in megabytes
+    pub max_memory_mb: usize,
+    /// Number of blocks behind the chain head after which block data is treated as final
+    pub finality_depth: u64,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_memory_mb: 64,
+            finality_depth: 64,
+        }
+    }
+}
+
+/// Fan-out of transaction submission methods to every healthy EL node
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Broadcast {
+    /// Whether broadcasting is enabled
+    pub enabled: bool,
+    /// JSON-RPC methods to broadcast
+    pub methods: Vec<String>,
+    /// Also send to healthy backup nodes, even when failover is not active
+    pub include_backups: bool,
+    /// Extra write-only endpoints that only receive broadcasts
+    pub extra_endpoints: Vec<BroadcastEndpoint>,
+    /// Timeout for each broadcast request (in milliseconds)
+    pub timeout_ms: u64,
+}
+
+impl Default for Broadcast {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            methods: vec!["eth_sendRawTransaction".to_string()],
+            include_backups: false,
+            extra_endpoints: Vec::new(),
+            timeout_ms: 30000,
+        }
+    }
+}
+
+/// A write-only endpoint that receives broadcast transactions
+#[derive(Debug, Clone, Deserialize)]
+pub struct BroadcastEndpoint {
+    /// Human-readable name for the endpoint
+    pub name: String,
+    /// HTTP URL for JSON-RPC requests
+    pub http_url: String,
+}
+
+/// Routes JSON-RPC methods to EL nodes carrying a given tag
+#[derive(Debug, Clone, Deserialize)]
+pub struct MethodRoute {
+    /// Method names to match; a trailing `*` matches by prefix (e.g. "debug_*")
+    pub methods: Vec<String>,
+    /// Tag of the EL nodes that should serve these methods
+    pub tag: String,
+}
+
+impl MethodRoute {
+    /// Check whether a JSON-RPC method matches this route
+    pub fn matches(&self, method: &str) -> bool {
+        self.methods.iter().any(|p| method_matches(p, method))
+    }
+}
+
+/// Match a method against a pattern where a trailing `*` matches by prefix
+fn method_matches(pattern: &str, method: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => method.starts_with(prefix),
+        None => method == pattern,
+    }
+}
+
+/// Allowlist/denylist of JSON-RPC methods
+///
+/// Patterns may end in `*` to match by prefix (e.g. "admin_*").
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MethodFilter {
+    /// Methods that may be called; empty allows every method not denied
+    pub allow: Vec<String>,
+    /// Methods that may never be called
+    pub deny: Vec<String>,
+}
+
+impl MethodFilter {
+    /// Check whether a JSON-RPC method passes the filter
+    pub fn permits(&self, method: &str) -> bool {
+        if self.deny.iter().any(|p| method_matches(p, method)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|p| method_matches(p, method))
+    }
 }
 
 impl El {
@@ -119,6 +466,58 @@ impl El {
                 .wrap_err_with(|| format!("invalid backup EL node '{}'", node.name))?;
         }
 
+        for endpoint in &self.broadcast.extra_endpoints {
+            validate_url(&endpoint.http_url, "http_url")
+                .wrap_err_with(|| format!("invalid broadcast endpoint '{}'", endpoint.name))?;
+        }
+
+        if self.broadcast.timeout_ms == 0 {
+            return Err(ConfigError::ValidationFailed(
+                "broadcast timeout_ms must be greater than 0".to_string(),
+            )
+            .into());
+        }
+
+        if self.hedging.enabled {
+            if !(self.hedging.percentile > 0.0 && self.hedging.percentile <= 100.0) {
+                return Err(ConfigError::ValidationFailed(format!(
+                    "hedging percentile must be between 0 and 100, got {}",
+                    self.hedging.percentile
+                ))
+                .into());
+            }
+            if self.hedging.min_delay_ms > self.hedging.max_delay_ms {
+                return Err(ConfigError::ValidationFailed(
+                    "hedging min_delay_ms must not exceed max_delay_ms".to_string(),
+                )
+                .into());
+            }
+        }
+
+        for rule in &self.retry.rules {
+            if !rule.matches_errors() && !rule.null_result {
+                return Err(ConfigError::ValidationFailed(
+                    "retry rule must set error_codes, message_contains or null_result".to_string(),
+                )
+                .into());
+            }
+        }
+
+        for route in &self.routes {
+            let tagged = self
+                .primary
+                .iter()
+                .chain(&self.backup)
+                .any(|n| n.tags.contains(&route.tag));
+            if !tagged {
+                return Err(ConfigError::ValidationFailed(format!(
+                    "route tag '{}' is not assigned to any EL node",
+                    route.tag
+                ))
+                .into());
+            }
+        }
+
         Ok(())
     }
 }
@@ -130,6 +529,9 @@ pub struct Cl {
     pub name: String,
     /// Base URL for beacon API requests
     pub url: String,
+    /// Tags used by path routing rules (e.g. "debug")
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl Cl {
@@ -140,200 +542,1137 @@ impl Cl {
     }
 }
 
-/// Main configuration struct
+/// How the responses of a broadcast CL request are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BroadcastPolicy {
+    /// Return as soon as any node accepts the request
+    FirstSuccess,
+    /// Succeed only if every node accepts the request
+    AllSuccess,
+}
+
+/// CL node configuration with primary and backup nodes
+///
+/// Accepts `[[cl.primary]]`/`[[cl.backup]]` tables or, for backward compatibility, a flat
+/// `[[cl]]` list whose nodes are all primary.
 #[derive(Debug, Clone, Deserialize)]
-pub struct Config {
-    /// Global settings
-    #[serde(default)]
-    pub global: Global,
-    /// Metrics settings
-    #[serde(default)]
-    pub metrics: Metrics,
-    /// EL node configuration
-    pub el: El,
-    /// CL nodes configuration
-    pub cl: Vec<Cl>,
+#[serde(from = "ClNodesConfig")]
+pub struct ClNodes {
+    /// Primary CL nodes - used first
+    pub primary: Vec<Cl>,
+    /// Backup CL nodes - only used when ALL primary nodes are unavailable
+    pub backup: Vec<Cl>,
 }
 
-impl Config {
-    /// Load configuration from a file path
-    pub fn load(path: &str) -> Result<Self> {
-        let content =
-            std::fs::read_to_string(path).wrap_err_with(|| format!("failed to read {path}"))?;
-        Self::parse(&content)
+/// Accepted shapes of the `cl` configuration
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ClNodesConfig {
+    Tiered {
+        primary: Vec<Cl>,
+        #[serde(default)]
+        backup: Vec<Cl>,
+    },
+    Flat(Vec<Cl>),
+}
+
+impl From<ClNodesConfig> for ClNodes {
+    fn from(config: ClNodesConfig) -> Self {
+        match config {
+            ClNodesConfig::Tiered { primary, backup } => Self { primary, backup },
+            ClNodesConfig::Flat(primary) => Self {
+                primary,
+                backup: Vec::new(),
+            },
+        }
     }
+}
 
-    /// Parse configuration from a TOML string
-    pub fn parse(s: &str) -> Result<Self> {
-        let config: Config = toml::from_str(s).wrap_err("failed to parse TOML configuration")?;
-        config.validate()?;
-        Ok(config)
+impl ClNodes {
+    /// Iterate over all CL nodes, primaries first
+    pub fn iter(&self) -> impl Iterator<Item = &Cl> {
+        self.primary.iter().chain(&self.backup)
     }
 
-    /// Validate the entire configuration
-    fn validate(&self) -> Result<()> {
-        self.el.validate().wrap_err("invalid EL configuration")?;
+    /// Total number of CL nodes
+    pub fn len(&self) -> usize {
+        self.primary.len() + self.backup.len()
+    }
 
-        if self.cl.is_empty() {
+    /// Whether no CL nodes are configured
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Validate the CL node configuration
+    fn validate(&self) -> Result<()> {
+        if self.primary.is_empty() {
             return Err(ConfigError::ValidationFailed(
-                "at least one CL node is required".to_string(),
+                "at least one primary CL node is required".to_string(),
             )
             .into());
         }
 
-        for node in &self.cl {
+        for node in &self.primary {
+            node.validate()
+                .wrap_err_with(|| format!("invalid primary CL node '{}'", node.name))?;
+        }
+
+        for node in &self.backup {
             node.validate()
-                .wrap_err_with(|| format!("invalid CL node '{}'", node.name))?;
+                .wrap_err_with(|| format!("invalid backup CL node '{}'", node.name))?;
         }
 
         Ok(())
     }
 }
 
-/// Validate that a string is a valid URL
-fn validate_url(url: &str, field_name: &str) -> Result<()> {
-    // Check for basic URL structure
-    if !url.starts_with("http://")
-        && !url.starts_with("https://")
-        && !url.starts_with("ws://")
-        && !url.starts_with("wss://")
-    {
-        return Err(ConfigError::InvalidUrl {
-            url: url.to_string(),
-            reason: format!("{field_name} must start with http://, https://, ws://, or wss://"),
-        }
-        .into());
-    }
-
-    // Try to parse as URL to validate further
-    url::Url::parse(url).map_err(|e| {
-        eyre!(ConfigError::InvalidUrl {
-            url: url.to_string(),
-            reason: e.to_string(),
-        })
-    })?;
+/// What happens to a CL request matching a path rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClRouteAction {
+    /// Forward the request as usual
+    Allow,
+    /// Reject the request with 403 Forbidden
+    Deny,
+    /// Forward the request to a CL node carrying the rule's tag
+    Route,
+}
 
-    Ok(())
+/// Beacon API path rule; rules are evaluated in order and the first match applies
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClRoute {
+    /// Path patterns to match (e.g. "/eth/v1/node/*", "/eth/v*/debug/**")
+    pub paths: Vec<String>,
+    /// HTTP methods to match (e.g. "GET"); empty matches every method
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// What to do with matching requests
+    pub action: ClRouteAction,
+    /// Tag of the CL nodes that should serve matching requests (for action = "route")
+    #[serde(default)]
+    pub tag: Option<String>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl ClRoute {
+    /// Check whether the HTTP method of a request matches this rule
+    pub fn matches_method(&self, method: &str) -> bool {
+        self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method))
+    }
 
-    const VALID_CONFIG: &str = r#"
-[global]
-max_el_lag_blocks = 5
-max_cl_lag_slots = 3
-health_check_interval_ms = 1000
+    /// Validate the rule against the configured CL nodes
+    fn validate(&self, nodes: &ClNodes) -> Result<()> {
+        if self.paths.is_empty() {
+            return Err(ConfigError::ValidationFailed("CL route has no paths".to_string()).into());
+        }
 
-[el]
-[[el.primary]]
-name = "geth-1"
-http_url = "http://localhost:8545"
-ws_url = "ws://localhost:8546"
+        match (self.action, &self.tag) {
+            (ClRouteAction::Route, None) => Err(ConfigError::ValidationFailed(
+                "CL route with action 'route' requires a tag".to_string(),
+            )
+            .into()),
+            (ClRouteAction::Route, Some(tag)) if !nodes.iter().any(|n| n.tags.contains(tag)) => {
+                Err(ConfigError::ValidationFailed(format!(
+                    "route tag '{tag}' is not assigned to any CL node"
+                ))
+                .into())
+            }
+            (ClRouteAction::Allow | ClRouteAction::Deny, Some(_)) => {
+                Err(ConfigError::ValidationFailed(
+                    "a tag is only allowed on CL routes with action 'route'".to_string(),
+                )
+                .into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
 
-[[el.primary]]
-name = "geth-2"
-http_url = "http://localhost:8547"
-ws_url = "ws://localhost:8548"
+/// Fan-out of beacon API publish requests to every healthy CL node
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClBroadcast {
+    /// Whether broadcasting is enabled
+    pub enabled: bool,
+    /// Beacon API path patterns whose POST requests are broadcast
+    pub paths: Vec<String>,
+    /// How node responses are combined into the client response
+    pub policy: BroadcastPolicy,
+    /// Timeout for each broadcast request (in milliseconds)
+    pub timeout_ms: u64,
+}
 
-[[el.backup]]
-name = "alchemy-1"
-http_url = "https://eth-mainnet.g.alchemy.com/v2/xxx"
-ws_url = "wss://eth-mainnet.g.alchemy.com/v2/xxx"
+impl Default for ClBroadcast {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            paths: vec![
+                "/eth/v*/beacon/blocks".to_string(),
+                "/eth/v*/beacon/blinded_blocks".to_string(),
+                "/eth/v*/beacon/pool/*".to_string(),
+            ],
+            policy: BroadcastPolicy::FirstSuccess,
+            timeout_ms: 30000,
+        }
+    }
+}
 
-[[cl]]
-name = "lighthouse-1"
-url = "http://localhost:5052"
+impl ClBroadcast {
+    /// Validate the CL broadcast settings
+    fn validate(&self) -> Result<()> {
+        if self.timeout_ms == 0 {
+            return Err(ConfigError::ValidationFailed(
+                "broadcast timeout_ms must be greater than 0".to_string(),
+            )
+            .into());
+        }
 
-[[cl]]
-name = "prysm-1"
-url = "http://localhost:5053"
-"#;
+        Ok(())
+    }
+}
 
-    #[test]
-    fn test_parse_valid_config() {
-        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+/// Passive outlier detection from proxied traffic
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OutlierDetection {
+    /// Whether nodes are ejected based on the outcome of proxied requests
+    pub enabled: bool,
+    /// Length of the sliding window request outcomes are counted over (in milliseconds)
+    pub window_ms: u64,
+    /// Minimum number of requests in the window before a node can be ejected
+    pub min_requests: u32,
+    /// Error rate (0.0 - 1.0) above which a node is ejected
+    pub max_error_rate: f64,
+    /// How long an ejected node is kept out of rotation (in milliseconds)
+    pub ejection_ms: u64,
+    /// JSON-RPC error codes counted as node errors (HTTP 5xx and timeouts always are)
+    pub error_codes: Vec<i64>,
+}
 
-        assert_eq!(config.global.max_el_lag_blocks, 5);
-        assert_eq!(config.global.max_cl_lag_slots, 3);
-        assert_eq!(config.global.health_check_interval_ms, 1000);
+impl Default for OutlierDetection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_ms: 10000,
+            min_requests: 20,
+            max_error_rate: 0.5,
+            ejection_ms: 30000,
+            error_codes: vec![-32603],
+        }
+    }
+}
 
-        assert_eq!(config.el.primary.len(), 2);
-        assert_eq!(config.el.backup.len(), 1);
-        assert_eq!(config.cl.len(), 2);
+impl OutlierDetection {
+    /// Validate the outlier detection settings
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if self.window_ms == 0 {
+            return Err(ConfigError::ValidationFailed(
+                "window_ms must be greater than 0".to_string(),
+            )
+            .into());
+        }
+        if !(self.max_error_rate > 0.0 && self.max_error_rate <= 1.0) {
+            return Err(ConfigError::ValidationFailed(format!(
+                "max_error_rate must be between 0 and 1, got {}",
+                self.max_error_rate
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Log output settings
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Logging {
+    /// Format of log lines
+    pub format: LogFormat,
+    /// Access log of proxied requests
+    pub access_log: AccessLog,
+}
+
+/// Format of log lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Access log settings
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AccessLog {
+    /// Whether a line is logged for every proxied request
+    pub enabled: bool,
+    /// File the access log is written to; stdout if unset
+    pub file: Option<String>,
+    /// How often the access log file is rotated
+    pub rotation: LogRotation,
+    /// Number of rotated access log files kept (0 keeps all)
+    pub max_files: usize,
+}
+
+impl Default for AccessLog {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: None,
+            rotation: LogRotation::Daily,
+            max_files: 7,
+        }
+    }
+}
+
+impl AccessLog {
+    /// Validate the access log settings
+    fn validate(&self) -> Result<()> {
+        if let Some(file) = &self.file
+            && std::path::Path::new(file).file_name().is_none()
+        {
+            return Err(ConfigError::ValidationFailed(format!(
+                "access log file '{file}' must name a file"
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// How often a log file is rotated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    Never,
+}
+
+/// Distributed tracing settings
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Tracing {
+    /// Whether spans are exported to an OTLP collector
+    pub enabled: bool,
+    /// OTLP/HTTP traces endpoint of the collector
+    pub endpoint: String,
+    /// Service name spans are reported under
+    pub service_name: String,
+    /// Fraction (0.0 - 1.0) of new traces that are sampled; traces started by a
+    /// client follow the client's sampling decision
+    pub sample_ratio: f64,
+}
+
+impl Default for Tracing {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318/v1/traces".to_string(),
+            service_name: "vixy".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+impl Tracing {
+    /// Validate the tracing settings
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        validate_url(&self.endpoint, "tracing endpoint")?;
+        if !(0.0..=1.0).contains(&self.sample_ratio) {
+            return Err(ConfigError::ValidationFailed(format!(
+                "sample_ratio must be between 0 and 1, got {}",
+                self.sample_ratio
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Persistence of node health state across restarts
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Persistence {
+    /// Whether node state is saved and restored at startup
+    pub enabled: bool,
+    /// File node state is saved to
+    pub file: String,
+    /// How often node state is saved, besides on shutdown (0 = only on shutdown)
+    pub save_interval_ms: u64,
+    /// Saved state older than this is not restored
+    pub max_age_ms: u64,
+}
+
+impl Default for Persistence {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: "vixy-state.json".to_string(),
+            save_interval_ms: 30000,
+            max_age_ms: 300000,
+        }
+    }
+}
+
+impl Persistence {
+    /// Validate the persistence settings
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if std::path::Path::new(&self.file).file_name().is_none() {
+            return Err(ConfigError::ValidationFailed(format!(
+                "state file '{}' must name a file",
+                self.file
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Notifications sent on health events
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Notifications {
+    /// Whether notifications are sent
+    pub enabled: bool,
+    /// Events notifications are sent for
+    pub events: Vec<NotificationEvent>,
+    /// Window in which a repeat of the same notification is not sent again
+    pub dedup_window_ms: u64,
+    /// Maximum notifications sent per minute (0 = unlimited)
+    pub max_per_minute: u32,
+    /// Delivery retries after a failed attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further retry
+    pub retry_backoff_ms: u64,
+    /// Timeout of a single delivery attempt
+    pub timeout_ms: u64,
+    /// Where notifications are sent
+    pub sinks: Vec<NotificationSink>,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            events: vec![
+                NotificationEvent::Failover,
+                NotificationEvent::AllNodesDown,
+                NotificationEvent::NodeHealth,
+                NotificationEvent::ChainStall,
+            ],
+            dedup_window_ms: 300000,
+            max_per_minute: 30,
+            max_retries: 3,
+            retry_backoff_ms: 1000,
+            timeout_ms: 5000,
+            sinks: Vec::new(),
+        }
+    }
+}
+
+/// Health event a notification can be sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// Failover to backup nodes was activated or deactivated
+    Failover,
+    /// No node of a layer is healthy, or one recovered
+    AllNodesDown,
+    /// A node became unhealthy or healthy
+    NodeHealth,
+    /// The chain head stopped advancing, or advances again
+    ChainStall,
+}
+
+/// A destination for notifications
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationSink {
+    /// Name used in logs and metrics
+    pub name: String,
+    /// How notifications are delivered
+    pub kind: SinkKind,
+    /// Webhook URL, or base URL of the Alertmanager
+    pub url: String,
+    /// JSON body of webhook notifications with `{{field}}` placeholders; the event as
+    /// JSON if unset
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Events sent to this sink (default: all notified events)
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+}
+
+/// How notifications are delivered to a sink
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkKind {
+    /// JSON POST to a URL
+    Webhook,
+    /// Slack incoming webhook (or a compatible one)
+    Slack,
+    /// Alertmanager v2 alerts API
+    Alertmanager,
+}
+
+impl Notifications {
+    /// Validate the notification settings
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if self.sinks.is_empty() {
+            return Err(ConfigError::ValidationFailed(
+                "notifications are enabled but no sinks are configured".to_string(),
+            )
+            .into());
+        }
+
+        let mut names = HashSet::new();
+        for sink in &self.sinks {
+            if !names.insert(sink.name.as_str()) {
+                return Err(ConfigError::ValidationFailed(format!(
+                    "duplicate notification sink name '{}'",
+                    sink.name
+                ))
+                .into());
+            }
+            validate_url(&sink.url, &format!("notification sink '{}' url", sink.name))?;
+            if let Some(template) = &sink.template {
+                if sink.kind != SinkKind::Webhook {
+                    return Err(ConfigError::ValidationFailed(format!(
+                        "notification sink '{}': templates are only supported by webhook sinks",
+                        sink.name
+                    ))
+                    .into());
+                }
+                crate::notify::check_template(template).map_err(|reason| {
+                    ConfigError::ValidationFailed(format!(
+                        "notification sink '{}': invalid template: {reason}",
+                        sink.name
+                    ))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Client authentication with API keys
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Auth {
+    /// Whether API keys are required on the proxy endpoints
+    pub enabled: bool,
+    /// Request header carrying the API key
+    pub header: String,
+    /// Query parameter carrying the API key
+    pub query_param: String,
+    /// Whether the API key may be given as a path segment (e.g. /el/<key>)
+    pub path_segment: bool,
+    /// Compute units charged for a call whose method has no explicit weight
+    pub default_weight: u32,
+    /// Compute units charged per JSON-RPC method (e.g. eth_getLogs = 10)
+    pub method_weights: HashMap<String, u32>,
+    /// Accepted API keys
+    pub keys: Vec<ApiKey>,
+}
+
+impl Default for Auth {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            header: "x-api-key".to_string(),
+            query_param: "api_key".to_string(),
+            path_segment: true,
+            default_weight: 1,
+            method_weights: HashMap::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+/// A client API key with its limits
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiKey {
+    /// Name used in logs and metrics (the key itself is never exposed)
+    pub name: String,
+    /// The secret key clients send
+    pub key: String,
+    /// Compute units per second refilled into the key's token bucket (0 = unlimited)
+    #[serde(default)]
+    pub requests_per_second: u32,
+    /// Token bucket size; defaults to one second worth of requests
+    #[serde(default)]
+    pub burst: Option<u32>,
+    /// Maximum concurrent WebSocket connections (0 = unlimited)
+    #[serde(default)]
+    pub max_ws_connections: usize,
+    /// Methods this key may call, on top of the global EL method filter
+    #[serde(default)]
+    pub method_filter: MethodFilter,
+}
+
+impl Auth {
+    /// Validate the auth configuration
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if self.keys.is_empty() {
+            return Err(ConfigError::ValidationFailed(
+                "auth is enabled but no API keys are configured".to_string(),
+            )
+            .into());
+        }
+
+        let mut names = HashSet::new();
+        let mut keys = HashSet::new();
+        for key in &self.keys {
+            if key.key.is_empty() {
+                return Err(
+                    ConfigError::MissingField(format!("key for API key '{}'", key.name)).into(),
+                );
+            }
+            if !names.insert(key.name.as_str()) {
+                return Err(ConfigError::ValidationFailed(format!(
+                    "duplicate API key name '{}'",
+                    key.name
+                ))
+                .into());
+            }
+            if !keys.insert(key.key.as_str()) {
+                return Err(ConfigError::ValidationFailed(format!(
+                    "API key '{}' reuses the key of another entry",
+                    key.name
+                ))
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Main configuration struct
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    /// Global settings
+    #[serde(default)]
+    pub global: Global,
+    /// Metrics settings
+    #[serde(default)]
+    pub metrics: Metrics,
+    /// EL node configuration
+    pub el: El,
+    /// CL nodes configuration
+    pub cl: ClNodes,
+    /// CL publish broadcast settings
+    #[serde(default)]
+    pub cl_broadcast: ClBroadcast,
+    /// Beacon API path rules, evaluated in order
+    #[serde(default)]
+    pub cl_routes: Vec<ClRoute>,
+    /// Client API key settings
+    #[serde(default)]
+    pub auth: Auth,
+    /// Passive outlier detection settings
+    #[serde(default)]
+    pub outlier_detection: OutlierDetection,
+    /// Distributed tracing settings
+    #[serde(default)]
+    pub tracing: Tracing,
+    /// Log output settings
+    #[serde(default)]
+    pub logging: Logging,
+    /// Health event notification settings
+    #[serde(default)]
+    pub notifications: Notifications,
+    /// Node state persistence settings
+    #[serde(default)]
+    pub persistence: Persistence,
+}
+
+impl Config {
+    /// Load configuration from a file path
+    pub fn load(path: &str) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).wrap_err_with(|| format!("failed to read {path}"))?;
+        Self::parse(&content)
+    }
+
+    /// Parse configuration from a TOML string
+    pub fn parse(s: &str) -> Result<Self> {
+        let config: Config = toml::from_str(s).wrap_err("failed to parse TOML configuration")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Validate the entire configuration
+    fn validate(&self) -> Result<()> {
+        self.global
+            .validate()
+            .wrap_err("invalid global configuration")?;
+
+        self.el.validate().wrap_err("invalid EL configuration")?;
+
+        self.cl.validate().wrap_err("invalid CL configuration")?;
+
+        self.cl_broadcast
+            .validate()
+            .wrap_err("invalid CL broadcast configuration")?;
+
+        for route in &self.cl_routes {
+            route
+                .validate(&self.cl)
+                .wrap_err("invalid CL route configuration")?;
+        }
+
+        self.auth
+            .validate()
+            .wrap_err("invalid auth configuration")?;
+
+        self.outlier_detection
+            .validate()
+            .wrap_err("invalid outlier detection configuration")?;
+
+        self.tracing
+            .validate()
+            .wrap_err("invalid tracing configuration")?;
+
+        self.logging
+            .access_log
+            .validate()
+            .wrap_err("invalid access log configuration")?;
+
+        self.notifications
+            .validate()
+            .wrap_err("invalid notifications configuration")?;
+
+        self.persistence
+            .validate()
+            .wrap_err("invalid persistence configuration")?;
+
+        Ok(())
+    }
+}
+
+/// Validate that a string is a valid URL
+fn validate_url(url: &str, field_name: &str) -> Result<()> {
+    // Check for basic URL structure
+    if !url.starts_with("http://")
+        && !url.starts_with("https://")
+        && !url.starts_with("ws://")
+        && !url.starts_with("wss://")
+    {
+        return Err(ConfigError::InvalidUrl {
+            url: url.to_string(),
+            reason: format!("{field_name} must start with http://, https://, ws://, or wss://"),
+        }
+        .into());
+    }
+
+    // Try to parse as URL to validate further
+    url::Url::parse(url).map_err(|e| {
+        eyre!(ConfigError::InvalidUrl {
+            url: url.to_string(),
+            reason: e.to_string(),
+        })
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_CONFIG: &str = r#"
+[global]
+max_el_lag_blocks = 5
+max_cl_lag_slots = 3
+health_check_interval_ms = 1000
+
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[[el.primary]]
+name = "geth-2"
+http_url = "http://localhost:8547"
+ws_url = "ws://localhost:8548"
+
+[[el.backup]]
+name = "alchemy-1"
+http_url = "https://eth-mainnet.g.alchemy.com/v2/xxx"
+ws_url = "wss://eth-mainnet.g.alchemy.com/v2/xxx"
+
+[[cl]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
+
+[[cl]]
+name = "prysm-1"
+url = "http://localhost:5053"
+"#;
+
+    #[test]
+    fn test_parse_valid_config() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+
+        assert_eq!(config.global.max_el_lag_blocks, 5);
+        assert_eq!(config.global.max_cl_lag_slots, 3);
+        assert_eq!(config.global.health_check_interval_ms, 1000);
+
+        assert_eq!(config.el.primary.len(), 2);
+        assert_eq!(config.el.backup.len(), 1);
+        assert_eq!(config.cl.len(), 2);
 
         assert_eq!(config.el.primary[0].name, "geth-1");
         assert_eq!(config.el.primary[0].http_url, "http://localhost:8545");
         assert_eq!(config.el.primary[0].ws_url, "ws://localhost:8546");
 
-        assert_eq!(config.cl[0].name, "lighthouse-1");
-        assert_eq!(config.cl[0].url, "http://localhost:5052");
+        assert_eq!(config.cl.primary[0].name, "lighthouse-1");
+        assert_eq!(config.cl.primary[0].url, "http://localhost:5052");
+    }
+
+    #[test]
+    fn test_parse_config_missing_el_fails() {
+        let config_str = r#"
+[global]
+max_el_lag_blocks = 5
+max_cl_lag_slots = 3
+health_check_interval_ms = 1000
+
+[[cl]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
+"#;
+
+        let result = Config::parse(config_str);
+        assert!(result.is_err(), "Should fail when EL config is missing");
+    }
+
+    #[test]
+    fn test_parse_config_missing_cl_fails() {
+        let config_str = r#"
+[global]
+max_el_lag_blocks = 5
+max_cl_lag_slots = 3
+health_check_interval_ms = 1000
+
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+"#;
+
+        let result = Config::parse(config_str);
+        assert!(result.is_err(), "Should fail when CL config is missing");
+    }
+
+    #[test]
+    fn test_parse_config_invalid_url_fails() {
+        let config_str = r#"
+[global]
+max_el_lag_blocks = 5
+max_cl_lag_slots = 3
+health_check_interval_ms = 1000
+
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "not-a-valid-url"
+ws_url = "ws://localhost:8546"
+
+[[cl]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
+"#;
+
+        let result = Config::parse(config_str);
+        assert!(result.is_err(), "Should fail when URL is invalid");
+    }
+
+    #[test]
+    fn test_default_values_applied() {
+        // Config without explicit global values - should use defaults
+        let config_str = r#"
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[[cl]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
+"#;
+
+        let config = Config::parse(config_str).expect("Should parse with defaults");
+
+        // These are the default values specified in AGENT.md
+        assert_eq!(config.global.max_el_lag_blocks, 5);
+        assert_eq!(config.global.max_cl_lag_slots, 3);
+        assert_eq!(config.global.health_check_interval_ms, 1000);
+    }
+
+    #[test]
+    fn test_coalesce_setting() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.global.coalesce_requests);
+
+        let config_str = VALID_CONFIG.replace(
+            "health_check_interval_ms = 1000",
+            "health_check_interval_ms = 1000\ncoalesce_requests = true",
+        );
+        let config = Config::parse(&config_str).expect("Should parse coalesce setting");
+        assert!(config.global.coalesce_requests);
     }
 
     #[test]
-    fn test_parse_config_missing_el_fails() {
+    fn test_health_check_timeout_setting() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert_eq!(config.global.health_check_timeout(), 1000);
+
+        // Without a timeout, a short interval bounds the default
+        let config_str = VALID_CONFIG.replace(
+            "health_check_interval_ms = 1000",
+            "health_check_interval_ms = 100",
+        );
+        let config = Config::parse(&config_str).expect("Should parse short interval");
+        assert_eq!(config.global.health_check_timeout(), 100);
+
+        let config_str = VALID_CONFIG.replace(
+            "health_check_interval_ms = 1000",
+            "health_check_interval_ms = 1000\nhealth_check_timeout_ms = 250",
+        );
+        let config = Config::parse(&config_str).expect("Should parse health check timeout");
+        assert_eq!(config.global.health_check_timeout(), 250);
+
+        // A check must finish before the next one is due
+        let config_str = VALID_CONFIG.replace(
+            "health_check_interval_ms = 1000",
+            "health_check_interval_ms = 1000\nhealth_check_timeout_ms = 5000",
+        );
+        let error = Config::parse(&config_str).unwrap_err();
+        assert!(format!("{error:#}").contains("health_check_timeout_ms"));
+    }
+
+    #[test]
+    fn test_event_log_size_setting() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert_eq!(config.global.event_log_size, 1000);
+
+        let config_str = VALID_CONFIG.replace(
+            "health_check_interval_ms = 1000",
+            "health_check_interval_ms = 1000\nevent_log_size = 50",
+        );
+        let config = Config::parse(&config_str).expect("Should parse event log size");
+        assert_eq!(config.global.event_log_size, 50);
+    }
+
+    #[test]
+    fn test_batch_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert_eq!(config.global.max_batch_size, 1000);
+        assert!(!config.global.split_batches);
+
+        let config_str = VALID_CONFIG.replace(
+            "health_check_interval_ms = 1000",
+            "health_check_interval_ms = 1000\nmax_batch_size = 50\nsplit_batches = true",
+        );
+        let config = Config::parse(&config_str).expect("Should parse batch settings");
+        assert_eq!(config.global.max_batch_size, 50);
+        assert!(config.global.split_batches);
+    }
+
+    #[test]
+    fn test_parse_method_routes() {
         let config_str = r#"
-[global]
-max_el_lag_blocks = 5
-max_cl_lag_slots = 3
-health_check_interval_ms = 1000
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[[el.primary]]
+name = "erigon-archive"
+http_url = "http://localhost:8547"
+ws_url = "ws://localhost:8548"
+tags = ["archive"]
+
+[[el.routes]]
+methods = ["debug_*", "trace_*", "eth_getLogs"]
+tag = "archive"
 
 [[cl]]
 name = "lighthouse-1"
 url = "http://localhost:5052"
 "#;
 
-        let result = Config::parse(config_str);
-        assert!(result.is_err(), "Should fail when EL config is missing");
+        let config = Config::parse(config_str).expect("Should parse method routes");
+
+        assert!(config.el.primary[0].tags.is_empty());
+        assert_eq!(config.el.primary[1].tags, vec!["archive"]);
+        assert_eq!(config.el.routes.len(), 1);
+        assert!(config.el.routes[0].matches("debug_traceCall"));
+        assert!(config.el.routes[0].matches("eth_getLogs"));
+        assert!(!config.el.routes[0].matches("eth_call"));
     }
 
     #[test]
-    fn test_parse_config_missing_cl_fails() {
+    fn test_route_with_unknown_tag_fails() {
         let config_str = r#"
-[global]
-max_el_lag_blocks = 5
-max_cl_lag_slots = 3
-health_check_interval_ms = 1000
-
 [el]
 [[el.primary]]
 name = "geth-1"
 http_url = "http://localhost:8545"
 ws_url = "ws://localhost:8546"
+
+[[el.routes]]
+methods = ["trace_*"]
+tag = "archive"
+
+[[cl]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
 "#;
 
         let result = Config::parse(config_str);
-        assert!(result.is_err(), "Should fail when CL config is missing");
+        assert!(
+            result.is_err(),
+            "Should fail when no EL node carries the route tag"
+        );
     }
 
     #[test]
-    fn test_parse_config_invalid_url_fails() {
+    fn test_parse_cl_tiers() {
         let config_str = r#"
-[global]
-max_el_lag_blocks = 5
-max_cl_lag_slots = 3
-health_check_interval_ms = 1000
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[cl]
+[[cl.primary]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
+
+[[cl.backup]]
+name = "hosted-1"
+url = "https://beacon.example.com"
+"#;
+
+        let config = Config::parse(config_str).expect("Should parse CL tiers");
+
+        assert_eq!(config.cl.primary.len(), 1);
+        assert_eq!(config.cl.backup.len(), 1);
+        assert_eq!(config.cl.backup[0].name, "hosted-1");
+        assert_eq!(config.cl.len(), 2);
+    }
 
+    #[test]
+    fn test_flat_cl_list_is_primary() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+
+        assert_eq!(config.cl.primary.len(), 2);
+        assert!(config.cl.backup.is_empty());
+    }
+
+    #[test]
+    fn test_cl_backup_only_fails() {
+        let config_str = r#"
 [el]
 [[el.primary]]
 name = "geth-1"
-http_url = "not-a-valid-url"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[cl]
+primary = []
+
+[[cl.backup]]
+name = "hosted-1"
+url = "https://beacon.example.com"
+"#;
+
+        assert!(
+            Config::parse(config_str).is_err(),
+            "Should fail without a primary CL node"
+        );
+    }
+
+    #[test]
+    fn test_parse_cl_routes() {
+        let config_str = r#"
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
 ws_url = "ws://localhost:8546"
 
 [[cl]]
 name = "lighthouse-1"
 url = "http://localhost:5052"
+
+[[cl]]
+name = "lighthouse-debug"
+url = "http://localhost:5053"
+tags = ["debug"]
+
+[[cl_routes]]
+paths = ["/eth/v*/debug/**"]
+action = "route"
+tag = "debug"
+
+[[cl_routes]]
+paths = ["/eth/v1/node/peers", "/eth/v1/node/identity"]
+methods = ["GET"]
+action = "deny"
 "#;
 
-        let result = Config::parse(config_str);
-        assert!(result.is_err(), "Should fail when URL is invalid");
+        let config = Config::parse(config_str).expect("Should parse CL routes");
+
+        assert_eq!(config.cl.primary[1].tags, vec!["debug"]);
+        assert_eq!(config.cl_routes.len(), 2);
+        assert_eq!(config.cl_routes[0].action, ClRouteAction::Route);
+        assert_eq!(config.cl_routes[0].tag.as_deref(), Some("debug"));
+        assert!(config.cl_routes[0].matches_method("POST"));
+        assert!(config.cl_routes[1].matches_method("get"));
+        assert!(!config.cl_routes[1].matches_method("POST"));
     }
 
     #[test]
-    fn test_default_values_applied() {
-        // Config without explicit global values - should use defaults
-        let config_str = r#"
+    fn test_cl_route_validation() {
+        let base = r#"
 [el]
 [[el.primary]]
 name = "geth-1"
@@ -345,12 +1684,453 @@ name = "lighthouse-1"
 url = "http://localhost:5052"
 "#;
 
-        let config = Config::parse(config_str).expect("Should parse with defaults");
+        let parse = |rule: &str| Config::parse(&format!("cl_routes = [{rule}]\n{base}"));
 
-        // These are the default values specified in AGENT.md
-        assert_eq!(config.global.max_el_lag_blocks, 5);
-        assert_eq!(config.global.max_cl_lag_slots, 3);
-        assert_eq!(config.global.health_check_interval_ms, 1000);
+        // Tag not carried by any CL node
+        assert!(
+            parse(r#"{ paths = ["/eth/v*/debug/**"], action = "route", tag = "debug" }"#).is_err()
+        );
+        // Route without a tag
+        assert!(parse(r#"{ paths = ["/**"], action = "route" }"#).is_err());
+        // Tag on a non-route rule
+        assert!(parse(r#"{ paths = ["/**"], action = "deny", tag = "debug" }"#).is_err());
+        // No paths
+        assert!(parse(r#"{ paths = [], action = "deny" }"#).is_err());
+        assert!(parse(r#"{ paths = ["/eth/v1/node/*"], action = "deny" }"#).is_ok());
+    }
+
+    #[test]
+    fn test_parse_broadcast_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.el.broadcast.enabled);
+        assert_eq!(config.el.broadcast.methods, vec!["eth_sendRawTransaction"]);
+
+        let config_str = VALID_CONFIG.replace(
+            "[[cl]]\nname = \"lighthouse-1\"",
+            r#"[el.broadcast]
+enabled = true
+methods = ["eth_sendRawTransaction", "eth_sendBundle"]
+include_backups = true
+timeout_ms = 5000
+
+[[el.broadcast.extra_endpoints]]
+name = "flashbots"
+http_url = "https://rpc.flashbots.net"
+
+[[cl]]
+name = "lighthouse-1""#,
+        );
+        let config = Config::parse(&config_str).expect("Should parse broadcast settings");
+
+        assert!(config.el.broadcast.enabled);
+        assert!(config.el.broadcast.include_backups);
+        assert_eq!(config.el.broadcast.methods.len(), 2);
+        assert_eq!(config.el.broadcast.timeout_ms, 5000);
+        assert_eq!(config.el.broadcast.extra_endpoints[0].name, "flashbots");
+    }
+
+    #[test]
+    fn test_method_filter() {
+        let filter = MethodFilter {
+            allow: vec![],
+            deny: vec!["admin_*".to_string(), "debug_setHead".to_string()],
+        };
+        assert!(filter.permits("eth_call"));
+        assert!(filter.permits("debug_traceTransaction"));
+        assert!(!filter.permits("admin_peers"));
+        assert!(!filter.permits("debug_setHead"));
+
+        let filter = MethodFilter {
+            allow: vec!["eth_*".to_string()],
+            deny: vec!["eth_sendRawTransaction".to_string()],
+        };
+        assert!(filter.permits("eth_blockNumber"));
+        assert!(!filter.permits("eth_sendRawTransaction"));
+        assert!(!filter.permits("net_version"));
+    }
+
+    #[test]
+    fn test_parse_method_filters() {
+        let config_str = format!(
+            r#"{}
+[auth]
+enabled = true
+
+[[auth.keys]]
+name = "reader"
+key = "secret"
+method_filter = {{ allow = ["eth_*"] }}
+"#,
+            VALID_CONFIG.replace(
+                "[[cl]]\nname = \"lighthouse-1\"",
+                "[el.method_filter]\ndeny = [\"admin_*\", \"personal_*\"]\n\n[[cl]]\nname = \"lighthouse-1\"",
+            )
+        );
+        let config = Config::parse(&config_str).expect("Should parse method filters");
+
+        assert_eq!(config.el.method_filter.deny, vec!["admin_*", "personal_*"]);
+        assert_eq!(config.auth.keys[0].method_filter.allow, vec!["eth_*"]);
+    }
+
+    #[test]
+    fn test_parse_cache_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.el.cache.enabled);
+        assert_eq!(config.el.cache.max_memory_mb, 64);
+
+        let config_str = VALID_CONFIG.replace(
+            "[[cl]]\nname = \"lighthouse-1\"",
+            r#"[el.cache]
+enabled = true
+max_memory_mb = 16
+finality_depth = 12
+
+[[cl]]
+name = "lighthouse-1""#,
+        );
+        let config = Config::parse(&config_str).expect("Should parse cache settings");
+
+        assert!(config.el.cache.enabled);
+        assert_eq!(config.el.cache.max_memory_mb, 16);
+        assert_eq!(config.el.cache.finality_depth, 12);
+    }
+
+    #[test]
+    fn test_parse_hedging_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.el.hedging.enabled);
+        assert!(config.el.hedging.matches("eth_call"));
+
+        let config_str = VALID_CONFIG.replace(
+            "[[cl]]\nname = \"lighthouse-1\"",
+            r#"[el.hedging]
+enabled = true
+methods = ["eth_get*"]
+percentile = 99
+max_delay_ms = 200
+
+[[cl]]
+name = "lighthouse-1""#,
+        );
+        let config = Config::parse(&config_str).expect("Should parse hedging settings");
+
+        assert!(config.el.hedging.enabled);
+        assert_eq!(config.el.hedging.percentile, 99.0);
+        assert_eq!(config.el.hedging.max_delay_ms, 200);
+        assert!(config.el.hedging.matches("eth_getLogs"));
+        assert!(!config.el.hedging.matches("eth_call"));
+
+        let invalid = config_str.replace("percentile = 99", "percentile = 150");
+        assert!(Config::parse(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_retry_rules() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.el.retry.enabled);
+
+        let header_not_found = serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "Header not found"}
+        });
+        let null_result = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": null});
+        let reason = |method: &str, response: &serde_json::Value| {
+            config
+                .el
+                .retry
+                .rules
+                .iter()
+                .find_map(|r| r.retry_reason(method, response))
+        };
+
+        assert_eq!(reason("eth_call", &header_not_found), Some("error"));
+        assert_eq!(
+            reason("eth_getBlockByNumber", &null_result),
+            Some("null_result")
+        );
+        // A null receipt just means the transaction isn't mined yet
+        assert_eq!(reason("eth_getTransactionReceipt", &null_result), None);
+
+        let config_str = VALID_CONFIG.replace(
+            "[[cl]]\nname = \"lighthouse-1\"",
+            r#"[el.retry]
+enabled = true
+
+[[el.retry.rules]]
+methods = ["eth_getLogs"]
+error_codes = [-32005]
+
+[[cl]]
+name = "lighthouse-1""#,
+        );
+        let config = Config::parse(&config_str).expect("Should parse retry rules");
+        assert!(config.el.retry.enabled);
+        let rule = &config.el.retry.rules[0];
+        let limit = serde_json::json!({"error": {"code": -32005, "message": "limit exceeded"}});
+        assert_eq!(config.el.retry.rules.len(), 1);
+        assert_eq!(rule.retry_reason("eth_getLogs", &limit), Some("error"));
+        assert_eq!(rule.retry_reason("eth_call", &limit), None);
+        assert_eq!(rule.retry_reason("eth_getLogs", &header_not_found), None);
+
+        let empty_rule = config_str.replace("error_codes = [-32005]", "");
+        assert!(Config::parse(&empty_rule).is_err());
+    }
+
+    #[test]
+    fn test_parse_auth_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.auth.enabled);
+        assert_eq!(config.auth.header, "x-api-key");
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[auth]
+enabled = true
+
+[auth.method_weights]
+eth_getLogs = 10
+
+[[auth.keys]]
+name = "indexer"
+key = "secret-1"
+requests_per_second = 100
+max_ws_connections = 2
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse auth settings");
+
+        assert!(config.auth.enabled);
+        assert_eq!(config.auth.method_weights["eth_getLogs"], 10);
+        assert_eq!(config.auth.keys[0].name, "indexer");
+        assert_eq!(config.auth.keys[0].requests_per_second, 100);
+        assert_eq!(config.auth.keys[0].burst, None);
+        assert_eq!(config.auth.keys[0].max_ws_connections, 2);
+    }
+
+    #[test]
+    fn test_auth_requires_unique_keys() {
+        let enabled_without_keys = format!("{VALID_CONFIG}\n[auth]\nenabled = true\n");
+        assert!(Config::parse(&enabled_without_keys).is_err());
+
+        let duplicate = format!(
+            r#"{VALID_CONFIG}
+[auth]
+enabled = true
+
+[[auth.keys]]
+name = "a"
+key = "same"
+
+[[auth.keys]]
+name = "b"
+key = "same"
+"#
+        );
+        assert!(Config::parse(&duplicate).is_err());
+    }
+
+    #[test]
+    fn test_parse_cl_broadcast_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.cl_broadcast.enabled);
+        assert_eq!(config.cl_broadcast.policy, BroadcastPolicy::FirstSuccess);
+        assert_eq!(config.cl_broadcast.timeout_ms, 30000);
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[cl_broadcast]
+enabled = true
+paths = ["/eth/v*/beacon/blocks"]
+policy = "all_success"
+timeout_ms = 2000
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse CL broadcast settings");
+
+        assert!(config.cl_broadcast.enabled);
+        assert_eq!(config.cl_broadcast.paths, vec!["/eth/v*/beacon/blocks"]);
+        assert_eq!(config.cl_broadcast.policy, BroadcastPolicy::AllSuccess);
+        assert_eq!(config.cl_broadcast.timeout_ms, 2000);
+
+        let zero_timeout = format!("{VALID_CONFIG}\n[cl_broadcast]\ntimeout_ms = 0\n");
+        assert!(Config::parse(&zero_timeout).is_err());
+    }
+
+    #[test]
+    fn test_parse_outlier_detection_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.outlier_detection.enabled);
+        assert_eq!(config.outlier_detection.error_codes, vec![-32603]);
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[outlier_detection]
+enabled = true
+min_requests = 10
+max_error_rate = 0.25
+error_codes = [-32603, -32000]
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse outlier detection settings");
+
+        assert!(config.outlier_detection.enabled);
+        assert_eq!(config.outlier_detection.min_requests, 10);
+        assert_eq!(config.outlier_detection.max_error_rate, 0.25);
+        assert_eq!(config.outlier_detection.window_ms, 10000);
+        assert_eq!(config.outlier_detection.error_codes, vec![-32603, -32000]);
+
+        let invalid_rate =
+            format!("{VALID_CONFIG}\n[outlier_detection]\nenabled = true\nmax_error_rate = 1.5\n");
+        assert!(Config::parse(&invalid_rate).is_err());
+    }
+
+    #[test]
+    fn test_parse_tracing_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.tracing.enabled);
+        assert_eq!(config.tracing.service_name, "vixy");
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[tracing]
+enabled = true
+endpoint = "http://otel-collector:4318/v1/traces"
+sample_ratio = 0.1
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse tracing settings");
+
+        assert!(config.tracing.enabled);
+        assert_eq!(
+            config.tracing.endpoint,
+            "http://otel-collector:4318/v1/traces"
+        );
+        assert_eq!(config.tracing.sample_ratio, 0.1);
+
+        let invalid_ratio =
+            format!("{VALID_CONFIG}\n[tracing]\nenabled = true\nsample_ratio = 2.0\n");
+        assert!(Config::parse(&invalid_ratio).is_err());
+
+        let invalid_endpoint =
+            format!("{VALID_CONFIG}\n[tracing]\nenabled = true\nendpoint = \"otel:4318\"\n");
+        assert!(Config::parse(&invalid_endpoint).is_err());
+    }
+
+    #[test]
+    fn test_parse_logging_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert_eq!(config.logging.format, LogFormat::Text);
+        assert!(!config.logging.access_log.enabled);
+        assert_eq!(config.logging.access_log.rotation, LogRotation::Daily);
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[logging]
+format = "json"
+
+[logging.access_log]
+enabled = true
+file = "/var/log/vixy/access.log"
+rotation = "hourly"
+max_files = 24
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse logging settings");
+
+        assert_eq!(config.logging.format, LogFormat::Json);
+        let access_log = &config.logging.access_log;
+        assert!(access_log.enabled);
+        assert_eq!(access_log.file.as_deref(), Some("/var/log/vixy/access.log"));
+        assert_eq!(access_log.rotation, LogRotation::Hourly);
+        assert_eq!(access_log.max_files, 24);
+
+        let invalid_format = format!("{VALID_CONFIG}\n[logging]\nformat = \"xml\"\n");
+        assert!(Config::parse(&invalid_format).is_err());
+    }
+
+    #[test]
+    fn test_parse_notification_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.notifications.enabled);
+        assert_eq!(config.notifications.events.len(), 4);
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[notifications]
+enabled = true
+events = ["failover", "chain_stall"]
+max_per_minute = 10
+
+[[notifications.sinks]]
+name = "ops"
+kind = "slack"
+url = "https://hooks.slack.com/services/T000/B000/XXX"
+
+[[notifications.sinks]]
+name = "pager"
+kind = "webhook"
+url = "http://pager.internal/hook"
+template = '{{"text": "{{{{summary}}}}", "layer": "{{{{layer}}}}", "firing": {{{{firing}}}}}}'
+events = ["failover"]
+
+[[notifications.sinks]]
+name = "alertmanager"
+kind = "alertmanager"
+url = "http://alertmanager:9093"
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse notification settings");
+
+        let notifications = &config.notifications;
+        assert!(notifications.enabled);
+        assert_eq!(
+            notifications.events,
+            vec![NotificationEvent::Failover, NotificationEvent::ChainStall]
+        );
+        assert_eq!(notifications.max_per_minute, 10);
+        assert_eq!(notifications.sinks.len(), 3);
+        assert_eq!(notifications.sinks[0].kind, SinkKind::Slack);
+        assert_eq!(
+            notifications.sinks[1].events,
+            vec![NotificationEvent::Failover]
+        );
+        assert_eq!(notifications.sinks[2].kind, SinkKind::Alertmanager);
+
+        // Templates must render valid JSON from known fields
+        for template in [r#"{"text": "{{summary}"#, r#"{"text": "{{nope}}"}"#] {
+            let config_str = format!(
+                "{VALID_CONFIG}\n[notifications]\nenabled = true\n\n[[notifications.sinks]]\n\
+                 name = \"hook\"\nkind = \"webhook\"\nurl = \"http://hook\"\ntemplate = '{template}'\n"
+            );
+            assert!(Config::parse(&config_str).is_err(), "{template}");
+        }
+
+        let no_sinks = format!("{VALID_CONFIG}\n[notifications]\nenabled = true\n");
+        assert!(Config::parse(&no_sinks).is_err());
+    }
+
+    #[test]
+    fn test_parse_persistence_settings() {
+        let config = Config::parse(VALID_CONFIG).expect("Should parse valid config");
+        assert!(!config.persistence.enabled);
+        assert_eq!(config.persistence.file, "vixy-state.json");
+
+        let config_str = format!(
+            r#"{VALID_CONFIG}
+[persistence]
+enabled = true
+file = "/var/lib/vixy/state.json"
+save_interval_ms = 10000
+max_age_ms = 60000
+"#
+        );
+        let config = Config::parse(&config_str).expect("Should parse persistence settings");
+        assert!(config.persistence.enabled);
+        assert_eq!(config.persistence.file, "/var/lib/vixy/state.json");
+        assert_eq!(config.persistence.save_interval_ms, 10000);
+        assert_eq!(config.persistence.max_age_ms, 60000);
+
+        let no_file = format!("{VALID_CONFIG}\n[persistence]\nenabled = true\nfile = \"/\"\n");
+        assert!(Config::parse(&no_file).is_err());
     }
 
     #[test]
@@ -0,0 +1,244 @@
+//! Log of health events
+//!
+//! Node health transitions, failover activations, layers without a healthy node, chain
+//! head stalls and WebSocket upstream switches are kept in a bounded in-memory ring
+//! buffer and broadcast to live subscribers, so `/status/events` can show what happened
+//! when, not just the current state. Notifications are sent from the same stream.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures_util::{Stream, StreamExt, stream};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+/// Number of events buffered for each live subscriber
+const SUBSCRIBER_BUFFER: usize = 256;
+
+/// Layer an event is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    El,
+    Cl,
+}
+
+impl Layer {
+    /// Name of the layer in messages
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::El => "EL",
+            Self::Cl => "CL",
+        }
+    }
+}
+
+/// Health state of a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    Healthy,
+    Unhealthy,
+}
+
+impl From<bool> for HealthState {
+    fn from(is_healthy: bool) -> Self {
+        if is_healthy {
+            Self::Healthy
+        } else {
+            Self::Unhealthy
+        }
+    }
+}
+
+/// What happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// A node became healthy or unhealthy
+    NodeHealth {
+        layer: Layer,
+        node: String,
+        tier: &'static str,
+        old_state: HealthState,
+        new_state: HealthState,
+        /// Health check failure reason (`timeout`, `connect`, ...), `lag`, `ejected`,
+        /// `failover_dwell` or `recovered`
+        reason: &'static str,
+        lag: u64,
+        /// Block number (EL) or slot (CL) the node reported
+        block: u64,
+    },
+    /// Failover to backup nodes was activated or deactivated
+    Failover { layer: Layer, active: bool },
+    /// No node of a layer is healthy any more, or one became healthy again
+    AllNodesDown { layer: Layer, active: bool },
+    /// The chain head stopped advancing for the stall timeout, or advanced again
+    ChainStall {
+        layer: Layer,
+        /// Block number (EL) or slot (CL) the chain head is at
+        head: u64,
+        /// How long the chain head has not advanced
+        stalled_ms: u64,
+        active: bool,
+    },
+    /// A WebSocket connection switched to another upstream node
+    WsReconnect { from_node: String, to_node: String },
+}
+
+/// A recorded event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Event {
+    /// Sequence number, increasing by one per event
+    pub id: u64,
+    /// When the event happened, in milliseconds since the Unix epoch
+    pub timestamp_ms: u64,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Debug)]
+struct Buffer {
+    events: VecDeque<Event>,
+    next_id: u64,
+}
+
+/// Bounded log of recent events with live subscribers
+#[derive(Debug)]
+pub struct EventLog {
+    capacity: usize,
+    buffer: Mutex<Buffer>,
+    live: broadcast::Sender<Event>,
+}
+
+impl EventLog {
+    /// Create a log keeping the last `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            buffer: Mutex::new(Buffer {
+                events: VecDeque::with_capacity(capacity),
+                next_id: 1,
+            }),
+            live: broadcast::channel(SUBSCRIBER_BUFFER).0,
+        }
+    }
+
+    /// Record an event, dropping the oldest one when the log is full
+    pub fn record(&self, kind: EventKind) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+
+        let mut buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        let event = Event {
+            id: buffer.next_id,
+            timestamp_ms,
+            kind,
+        };
+        buffer.next_id += 1;
+        if self.capacity > 0 {
+            if buffer.events.len() == self.capacity {
+                buffer.events.pop_front();
+            }
+            buffer.events.push_back(event.clone());
+        }
+        // Sent under the lock so subscribers see events in order; fails only
+        // without subscribers
+        let _ = self.live.send(event);
+    }
+
+    /// Recent events, oldest first
+    pub fn recent(&self) -> Vec<Event> {
+        let buffer = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.events.iter().cloned().collect()
+    }
+
+    /// Stream events as they are recorded, starting with the buffered events after
+    /// `last_id` when given
+    pub fn stream(&self, last_id: Option<u64>) -> impl Stream<Item = Event> + use<> {
+        // Subscribe before reading the buffer so no event falls in between
+        let receiver = self.live.subscribe();
+        let backlog = match last_id {
+            Some(last_id) => self
+                .recent()
+                .into_iter()
+                .filter(|e| e.id > last_id)
+                .collect(),
+            None => Vec::new(),
+        };
+        let mut last_sent = backlog.last().map_or(last_id.unwrap_or(0), |e| e.id);
+
+        let live = stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!(skipped, "Event subscriber fell behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        // Skip events already sent from the backlog
+        .filter(move |event| {
+            let new = event.id > last_sent;
+            if new {
+                last_sent = event.id;
+            }
+            std::future::ready(new)
+        });
+
+        stream::iter(backlog).chain(live)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failover(active: bool) -> EventKind {
+        EventKind::Failover {
+            layer: Layer::El,
+            active,
+        }
+    }
+
+    #[test]
+    fn test_event_log_keeps_most_recent_events() {
+        let log = EventLog::new(2);
+        log.record(failover(true));
+        log.record(failover(false));
+        log.record(EventKind::WsReconnect {
+            from_node: "geth-1".to_string(),
+            to_node: "geth-2".to_string(),
+        });
+
+        let events = log.recent();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].id, 2);
+        assert_eq!(events[0].kind, failover(false));
+        assert_eq!(events[1].id, 3);
+
+        let json = serde_json::to_value(&events[1]).unwrap();
+        assert_eq!(json["type"], "ws_reconnect");
+        assert_eq!(json["from_node"], "geth-1");
+        assert_eq!(json["to_node"], "geth-2");
+        assert!(json["timestamp_ms"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_resumes_after_last_id() {
+        let log = EventLog::new(10);
+        log.record(failover(true));
+        log.record(failover(false));
+
+        let stream = log.stream(Some(1));
+        log.record(failover(true));
+
+        let ids: Vec<u64> = stream.take(2).map(|e| e.id).collect().await;
+        assert_eq!(ids, vec![2, 3]);
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! Checks CL node health via /eth/v1/node/health and /eth/v1/beacon/headers/head.
 
+use super::CheckError;
 use crate::state::ClNodeState;
 use eyre::{Result, WrapErr};
 use serde::Deserialize;
+use std::time::{Duration, Instant};
 
 /// Response structure for /eth/v1/beacon/headers/head
 #[derive(Debug, Deserialize)]
@@ -28,8 +30,8 @@ struct BeaconHeaderMessage {
 }
 
 /// Check if the CL node's health endpoint returns 200
-pub async fn check_cl_health(url: &str) -> Result<bool> {
-    let client = reqwest::Client::new();
+pub async fn check_cl_health(url: &str, timeout: Duration) -> Result<bool> {
+    let client = super::check_client(timeout);
 
     let health_url = format!("{}/eth/v1/node/health", url.trim_end_matches('/'));
 
@@ -40,8 +42,8 @@ pub async fn check_cl_health(url: &str) -> Result<bool> {
 }
 
 /// Get the current slot from the CL node's beacon headers endpoint
-pub async fn check_cl_slot(url: &str) -> Result<u64> {
-    let client = reqwest::Client::new();
+pub async fn check_cl_slot(url: &str, timeout: Duration) -> Result<u64> {
+    let client = super::check_client(timeout);
 
     let headers_url = format!("{}/eth/v1/beacon/headers/head", url.trim_end_matches('/'));
 
@@ -51,6 +53,10 @@ pub async fn check_cl_slot(url: &str) -> Result<u64> {
         .await
         .wrap_err("failed to send request to CL node")?;
 
+    if !response.status().is_success() {
+        return Err(CheckError::HttpStatus(response.status().as_u16()).into());
+    }
+
     let body: BeaconHeaderResponse = response
         .json()
         .await
@@ -64,13 +70,13 @@ pub async fn check_cl_slot(url: &str) -> Result<u64> {
         .wrap_err("failed to parse slot number")
 }
 
-/// Check both health and slot for a CL node
-pub async fn check_cl_node(url: &str) -> Result<(bool, u64)> {
+/// Check both health and slot for a CL node, each request bounded by `timeout`
+pub async fn check_cl_node(url: &str, timeout: Duration) -> Result<(bool, u64)> {
     // Check health endpoint
-    let health_ok = check_cl_health(url).await?;
+    let health_ok = check_cl_health(url, timeout).await?;
 
     // Get current slot
-    let slot = check_cl_slot(url).await?;
+    let slot = check_cl_slot(url, timeout).await?;
 
     Ok((health_ok, slot))
 }
@@ -81,11 +87,16 @@ pub fn update_cl_chain_head(nodes: &[ClNodeState]) -> u64 {
 }
 
 /// Calculate health status for a CL node based on chain head and max lag
+///
+/// A node is marked unhealthy after `max_failures` failed checks in a row and only
+/// becomes healthy again after `recovery_successes` passed checks in a row. A node
+/// ejected by outlier detection stays unhealthy until its ejection ends.
 pub fn calculate_cl_health(
     node: &mut ClNodeState,
     chain_head: u64,
     max_lag: u64,
     max_failures: u32,
+    recovery_successes: u32,
 ) {
     // Calculate lag (how far behind the node is from chain head)
     node.lag = chain_head.saturating_sub(node.slot);
@@ -96,9 +107,15 @@ pub fn calculate_cl_health(
     if check_passed {
         // Reset consecutive failures on success
         node.consecutive_failures = 0;
-        node.is_healthy = true;
+        node.consecutive_successes = node.consecutive_successes.saturating_add(1);
+
+        // Only mark as healthy once enough checks in a row have passed
+        if node.consecutive_successes >= recovery_successes {
+            node.is_healthy = true;
+        }
     } else {
         // Increment consecutive failures
+        node.consecutive_successes = 0;
         node.consecutive_failures += 1;
 
         // Only mark as unhealthy if we've exceeded the threshold
@@ -107,6 +124,15 @@ pub fn calculate_cl_health(
         }
         // Otherwise, keep the current health status (might still be healthy from before)
     }
+
+    // Keep a node ejected by outlier detection out of rotation until its cool-down ends
+    if let Some(until) = node.ejected_until {
+        if Instant::now() < until {
+            node.is_healthy = false;
+        } else {
+            node.ejected_until = None;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -129,7 +155,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = check_cl_health(&mock_server.uri())
+        let result = check_cl_health(&mock_server.uri(), Duration::from_secs(1))
             .await
             .expect("Should check health");
 
@@ -146,7 +172,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = check_cl_health(&mock_server.uri())
+        let result = check_cl_health(&mock_server.uri(), Duration::from_secs(1))
             .await
             .expect("Should check health");
 
@@ -156,7 +182,7 @@ mod tests {
     #[tokio::test]
     async fn test_check_cl_health_returns_false_on_connection_failure() {
         // Use an invalid URL that will fail to connect
-        let result = check_cl_health("http://localhost:99999")
+        let result = check_cl_health("http://localhost:99999", Duration::from_secs(1))
             .await
             .expect("Should handle connection failure");
 
@@ -192,7 +218,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let slot = check_cl_slot(&mock_server.uri())
+        let slot = check_cl_slot(&mock_server.uri(), Duration::from_secs(1))
             .await
             .expect("Should parse slot");
 
@@ -209,10 +235,45 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = check_cl_slot(&mock_server.uri()).await;
+        let result = check_cl_slot(&mock_server.uri(), Duration::from_secs(1)).await;
         assert!(result.is_err(), "Should fail on invalid JSON");
     }
 
+    #[tokio::test]
+    async fn test_check_cl_slot_failure_reasons() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/head"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        let error = check_cl_slot(&mock_server.uri(), Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert_eq!(crate::health::failure_reason(&error), "http_status");
+
+        let error = check_cl_slot("http://127.0.0.1:1", Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert_eq!(crate::health::failure_reason(&error), "connect");
+    }
+
+    #[tokio::test]
+    async fn test_check_cl_slot_invalid_json_is_parse_failure() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/head"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .mount(&mock_server)
+            .await;
+
+        let error = check_cl_slot(&mock_server.uri(), Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert_eq!(crate::health::failure_reason(&error), "parse");
+    }
+
     // =========================================================================
     // calculate_cl_health tests
     // =========================================================================
@@ -221,11 +282,17 @@ mod tests {
         ClNodeState {
             name: name.to_string(),
             url: "http://localhost:5052".to_string(),
+            is_primary: true,
+            tags: vec![],
             slot,
             health_ok,
             is_healthy: false,
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
@@ -234,7 +301,7 @@ mod tests {
         let mut node = make_cl_node("test", 1000, true);
         let chain_head = 1005;
 
-        calculate_cl_health(&mut node, chain_head, 10, 3);
+        calculate_cl_health(&mut node, chain_head, 10, 3, 1);
 
         assert_eq!(node.lag, 5, "Lag should be chain_head - slot");
     }
@@ -247,7 +314,7 @@ mod tests {
         let max_lag = 3;
 
         // First failure
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 1);
         assert!(
             node.is_healthy,
@@ -255,7 +322,7 @@ mod tests {
         );
 
         // Second failure
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 2);
         assert!(
             node.is_healthy,
@@ -263,7 +330,7 @@ mod tests {
         );
 
         // Third failure
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 3);
         assert!(
             !node.is_healthy,
@@ -280,7 +347,7 @@ mod tests {
         let max_lag = 3;
 
         // First failure - still healthy
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 1);
         assert!(
             node.is_healthy,
@@ -288,7 +355,7 @@ mod tests {
         );
 
         // Second failure - still healthy
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 2);
         assert!(
             node.is_healthy,
@@ -296,7 +363,7 @@ mod tests {
         );
 
         // Third failure - now unhealthy
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 3);
         assert!(
             !node.is_healthy,
@@ -311,7 +378,7 @@ mod tests {
         let chain_head = 1000;
         let max_lag = 3;
 
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
 
         assert!(
             node.is_healthy,
@@ -326,7 +393,7 @@ mod tests {
         let chain_head = 1000;
         let max_lag = 3;
 
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
 
         assert!(
             node.is_healthy,
@@ -343,7 +410,7 @@ mod tests {
         let max_lag = 3;
 
         // First failure - still healthy
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 1);
         assert!(
             node.is_healthy,
@@ -351,7 +418,7 @@ mod tests {
         );
 
         // Second failure - still healthy
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 2);
         assert!(
             node.is_healthy,
@@ -360,7 +427,7 @@ mod tests {
 
         // Recovery - node catches up
         node.slot = 1000;
-        calculate_cl_health(&mut node, chain_head, max_lag, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(
             node.consecutive_failures, 0,
             "Consecutive failures should reset on success"
@@ -368,6 +435,53 @@ mod tests {
         assert!(node.is_healthy, "Node should be healthy after recovery");
     }
 
+    #[test]
+    fn test_cl_ejected_node_stays_unhealthy_until_ejection_ends() {
+        let mut node = make_cl_node("test", 1000, true);
+        node.ejected_until = Some(Instant::now() + std::time::Duration::from_secs(60));
+
+        calculate_cl_health(&mut node, 1000, 3, 3, 1);
+        assert!(!node.is_healthy, "Ejected node should stay unhealthy");
+
+        // Once the ejection has ended, the health check decides again
+        node.ejected_until = Some(Instant::now());
+        calculate_cl_health(&mut node, 1000, 3, 3, 1);
+        assert!(node.is_healthy);
+        assert!(node.ejected_until.is_none());
+    }
+
+    #[test]
+    fn test_cl_node_needs_consecutive_successes_to_recover() {
+        let mut node = make_cl_node("test", 1000, true);
+        let chain_head = 1000;
+        let max_lag = 3;
+
+        // Two passing checks are not enough
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 3);
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 3);
+        assert!(
+            !node.is_healthy,
+            "Node should not recover after 2 successes"
+        );
+
+        // A failure in between restarts the count
+        node.health_ok = false;
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 3);
+        assert_eq!(node.consecutive_successes, 0);
+
+        node.health_ok = true;
+        for _ in 0..2 {
+            calculate_cl_health(&mut node, chain_head, max_lag, 3, 3);
+        }
+        assert!(!node.is_healthy);
+
+        calculate_cl_health(&mut node, chain_head, max_lag, 3, 3);
+        assert!(
+            node.is_healthy,
+            "Node should recover after 3 successes in a row"
+        );
+    }
+
     // =========================================================================
     // update_cl_chain_head tests
     // =========================================================================
@@ -2,9 +2,11 @@
 //!
 //! Checks EL node health by calling eth_getBlockNumber and tracking chain head.
 
+use super::CheckError;
 use crate::state::ElNodeState;
 use eyre::{Result, WrapErr, eyre};
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
 /// JSON-RPC request structure
 #[derive(Debug, Serialize)]
@@ -45,9 +47,9 @@ pub fn parse_hex_block_number(hex: &str) -> Result<u64> {
     u64::from_str_radix(hex_str, 16).wrap_err_with(|| format!("invalid hex number: {hex}"))
 }
 
-/// Check an EL node's current block number via JSON-RPC
-pub async fn check_el_node(url: &str) -> Result<u64> {
-    let client = reqwest::Client::new();
+/// Check an EL node's current block number via JSON-RPC, giving up after `timeout`
+pub async fn check_el_node(url: &str, timeout: Duration) -> Result<u64> {
+    let client = super::check_client(timeout);
 
     let request = JsonRpcRequest {
         jsonrpc: "2.0",
@@ -63,13 +65,21 @@ pub async fn check_el_node(url: &str) -> Result<u64> {
         .await
         .wrap_err("failed to send request to EL node")?;
 
+    if !response.status().is_success() {
+        return Err(CheckError::HttpStatus(response.status().as_u16()).into());
+    }
+
     let rpc_response: JsonRpcResponse = response
         .json()
         .await
         .wrap_err("failed to parse JSON-RPC response")?;
 
     if let Some(error) = rpc_response.error {
-        return Err(eyre!("JSON-RPC error {}: {}", error.code, error.message));
+        return Err(CheckError::Rpc {
+            code: error.code,
+            message: error.message,
+        }
+        .into());
     }
 
     let result = rpc_response
@@ -85,11 +95,16 @@ pub fn update_el_chain_head(nodes: &[ElNodeState]) -> u64 {
 }
 
 /// Calculate health status for an EL node based on chain head and max lag
+///
+/// A node is marked unhealthy after `max_failures` failed checks in a row and only
+/// becomes healthy again after `recovery_successes` passed checks in a row. A node
+/// ejected by outlier detection stays unhealthy until its ejection ends.
 pub fn calculate_el_health(
     node: &mut ElNodeState,
     chain_head: u64,
     max_lag: u64,
     max_failures: u32,
+    recovery_successes: u32,
 ) {
     // Calculate lag (how far behind the node is from chain head)
     node.lag = chain_head.saturating_sub(node.block_number);
@@ -100,9 +115,15 @@ pub fn calculate_el_health(
     if check_passed {
         // Reset consecutive failures on success
         node.consecutive_failures = 0;
-        node.is_healthy = true;
+        node.consecutive_successes = node.consecutive_successes.saturating_add(1);
+
+        // Only mark as healthy once enough checks in a row have passed
+        if node.consecutive_successes >= recovery_successes {
+            node.is_healthy = true;
+        }
     } else {
         // Increment consecutive failures
+        node.consecutive_successes = 0;
         node.consecutive_failures += 1;
 
         // Only mark as unhealthy if we've exceeded the threshold
@@ -111,6 +132,15 @@ pub fn calculate_el_health(
         }
         // Otherwise, keep the current health status (might still be healthy from before)
     }
+
+    // Keep a node ejected by outlier detection out of rotation until its cool-down ends
+    if let Some(until) = node.ejected_until {
+        if Instant::now() < until {
+            node.is_healthy = false;
+        } else {
+            node.ejected_until = None;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -183,7 +213,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let block_number = check_el_node(&mock_server.uri())
+        let block_number = check_el_node(&mock_server.uri(), Duration::from_secs(1))
             .await
             .expect("Should get block number");
 
@@ -196,7 +226,7 @@ mod tests {
 
         // Don't mount any mock - request will fail
 
-        let result = check_el_node(&mock_server.uri()).await;
+        let result = check_el_node(&mock_server.uri(), Duration::from_secs(1)).await;
         assert!(result.is_err(), "Should fail on timeout/no response");
     }
 
@@ -213,10 +243,63 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let result = check_el_node(&mock_server.uri()).await;
+        let result = check_el_node(&mock_server.uri(), Duration::from_secs(1)).await;
         assert!(result.is_err(), "Should fail on invalid hex in response");
     }
 
+    #[tokio::test]
+    async fn test_check_el_node_failure_reasons() {
+        let rpc_error_mock = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "error": {"code": -32000, "message": "syncing"},
+                "id": 1
+            })))
+            .mount(&rpc_error_mock)
+            .await;
+
+        let status_mock = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(502))
+            .mount(&status_mock)
+            .await;
+
+        let error = check_el_node(&rpc_error_mock.uri(), Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert_eq!(crate::health::failure_reason(&error), "rpc_error");
+
+        let error = check_el_node(&status_mock.uri(), Duration::from_secs(1))
+            .await
+            .unwrap_err();
+        assert_eq!(crate::health::failure_reason(&error), "http_status");
+    }
+
+    #[tokio::test]
+    async fn test_check_el_node_gives_up_after_timeout() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "result": "0x10",
+                        "id": 1
+                    }))
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let start = Instant::now();
+        let error = check_el_node(&mock_server.uri(), Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(crate::health::failure_reason(&error), "timeout");
+    }
+
     // =========================================================================
     // calculate_el_lag / calculate_el_health tests
     // =========================================================================
@@ -227,11 +310,16 @@ mod tests {
             http_url: "http://localhost:8545".to_string(),
             ws_url: "ws://localhost:8546".to_string(),
             is_primary: true,
+            tags: vec![],
             block_number,
             check_ok,
             is_healthy: false,
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
@@ -240,7 +328,7 @@ mod tests {
         let mut node = make_el_node("test", 1000, true);
         let chain_head = 1005;
 
-        calculate_el_health(&mut node, chain_head, 10, 3);
+        calculate_el_health(&mut node, chain_head, 10, 3, 1);
 
         assert_eq!(node.lag, 5, "Lag should be chain_head - block_number");
     }
@@ -251,7 +339,7 @@ mod tests {
         let chain_head = 1002;
         let max_lag = 5;
 
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
 
         assert!(
             node.is_healthy,
@@ -268,7 +356,7 @@ mod tests {
         let max_lag = 5;
 
         // First failure - still healthy (threshold is 3)
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 1);
         assert!(
             node.is_healthy,
@@ -276,7 +364,7 @@ mod tests {
         );
 
         // Second failure - still healthy
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 2);
         assert!(
             node.is_healthy,
@@ -284,7 +372,7 @@ mod tests {
         );
 
         // Third failure - now unhealthy
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 3);
         assert!(
             !node.is_healthy,
@@ -299,7 +387,7 @@ mod tests {
         let chain_head = 1000;
         let max_lag = 5;
 
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
 
         assert!(
             node.is_healthy,
@@ -316,7 +404,7 @@ mod tests {
         let max_lag = 5;
 
         // First failure
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 1);
         assert!(
             node.is_healthy,
@@ -324,7 +412,7 @@ mod tests {
         );
 
         // Second failure
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 2);
         assert!(
             node.is_healthy,
@@ -332,7 +420,7 @@ mod tests {
         );
 
         // Third failure
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 3);
         assert!(
             !node.is_healthy,
@@ -349,7 +437,7 @@ mod tests {
         let max_lag = 5;
 
         // First failure - still healthy
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 1);
         assert!(
             node.is_healthy,
@@ -357,7 +445,7 @@ mod tests {
         );
 
         // Second failure - still healthy
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(node.consecutive_failures, 2);
         assert!(
             node.is_healthy,
@@ -366,7 +454,7 @@ mod tests {
 
         // Recovery - node catches up
         node.block_number = 1000;
-        calculate_el_health(&mut node, chain_head, max_lag, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 1);
         assert_eq!(
             node.consecutive_failures, 0,
             "Consecutive failures should reset on success"
@@ -374,6 +462,53 @@ mod tests {
         assert!(node.is_healthy, "Node should be healthy after recovery");
     }
 
+    #[test]
+    fn test_el_ejected_node_stays_unhealthy_until_ejection_ends() {
+        let mut node = make_el_node("test", 1000, true);
+        node.ejected_until = Some(Instant::now() + std::time::Duration::from_secs(60));
+
+        calculate_el_health(&mut node, 1000, 3, 3, 1);
+        assert!(!node.is_healthy, "Ejected node should stay unhealthy");
+
+        // Once the ejection has ended, the health check decides again
+        node.ejected_until = Some(Instant::now());
+        calculate_el_health(&mut node, 1000, 3, 3, 1);
+        assert!(node.is_healthy);
+        assert!(node.ejected_until.is_none());
+    }
+
+    #[test]
+    fn test_el_node_needs_consecutive_successes_to_recover() {
+        let mut node = make_el_node("test", 1000, true);
+        let chain_head = 1000;
+        let max_lag = 5;
+
+        // Two passing checks are not enough
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 3);
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 3);
+        assert!(
+            !node.is_healthy,
+            "Node should not recover after 2 successes"
+        );
+
+        // A failure in between restarts the count
+        node.check_ok = false;
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 3);
+        assert_eq!(node.consecutive_successes, 0);
+
+        node.check_ok = true;
+        for _ in 0..2 {
+            calculate_el_health(&mut node, chain_head, max_lag, 3, 3);
+        }
+        assert!(!node.is_healthy);
+
+        calculate_el_health(&mut node, chain_head, max_lag, 3, 3);
+        assert!(
+            node.is_healthy,
+            "Node should recover after 3 successes in a row"
+        );
+    }
+
     // =========================================================================
     // update_el_chain_head tests
     // =========================================================================
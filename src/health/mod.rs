@@ -2,3 +2,84 @@
 
 pub mod cl;
 pub mod el;
+
+use std::time::Duration;
+
+/// A health check failure that isn't a transport or decoding error
+#[derive(Debug, thiserror::Error)]
+pub enum CheckError {
+    #[error("unexpected HTTP status {0}")]
+    HttpStatus(u16),
+
+    #[error("JSON-RPC error {code}: {message}")]
+    Rpc { code: i32, message: String },
+}
+
+/// Build the HTTP client used for health checks, giving up on a request after `timeout`
+fn check_client(timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+/// Classify why a health check failed, for metrics
+///
+/// Returns one of `connect`, `timeout`, `http_status`, `parse` or `rpc_error`.
+pub fn failure_reason(error: &eyre::Report) -> &'static str {
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<CheckError>() {
+            return match e {
+                CheckError::HttpStatus(_) => "http_status",
+                CheckError::Rpc { .. } => "rpc_error",
+            };
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return if e.is_timeout() {
+                "timeout"
+            } else if e.is_decode() {
+                "parse"
+            } else if e.is_status() {
+                "http_status"
+            } else {
+                "connect"
+            };
+        }
+    }
+
+    // Anything else is a response that didn't contain what we expected
+    "parse"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::WrapErr;
+
+    #[test]
+    fn test_failure_reason() {
+        let rpc = eyre::Report::new(CheckError::Rpc {
+            code: -32000,
+            message: "syncing".to_string(),
+        });
+        assert_eq!(failure_reason(&rpc), "rpc_error");
+
+        let status: eyre::Result<()> =
+            Err(CheckError::HttpStatus(503)).wrap_err("failed to check node");
+        assert_eq!(failure_reason(&status.unwrap_err()), "http_status");
+
+        let parse = "abc".parse::<u64>().wrap_err("failed to parse slot number");
+        assert_eq!(failure_reason(&parse.unwrap_err()), "parse");
+    }
+
+    #[tokio::test]
+    async fn test_failure_reason_connect() {
+        let error = check_client(Duration::from_secs(1))
+            .get("http://127.0.0.1:1")
+            .send()
+            .await
+            .wrap_err("failed to send request")
+            .unwrap_err();
+        assert_eq!(failure_reason(&error), "connect");
+    }
+}
@@ -3,9 +3,16 @@
 //! A Rust proxy that monitors Ethereum Execution Layer (EL) and Consensus Layer (CL) nodes,
 //! tracks their health, and routes requests to healthy nodes.
 
+pub mod access_log;
+pub mod auth;
 pub mod config;
+pub mod events;
 pub mod health;
 pub mod metrics;
 pub mod monitor;
+pub mod notify;
+pub mod persistence;
 pub mod proxy;
+pub mod request_id;
 pub mod state;
+pub mod telemetry;
@@ -3,18 +3,26 @@
 //! Entry point for the Vixy proxy server.
 
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::Router;
 use clap::Parser;
 use tokio::signal;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use vixy::config::Config;
+use vixy::access_log;
+use vixy::auth;
+use vixy::config::{Config, LogFormat};
 use vixy::metrics::VixyMetrics;
 use vixy::monitor;
+use vixy::notify::Notifier;
+use vixy::persistence;
 use vixy::proxy::{http, ws};
+use vixy::request_id;
 use vixy::state::AppState;
+use vixy::telemetry;
 
 /// Vixy - Ethereum EL and CL Proxy
 #[derive(Parser, Debug)]
@@ -30,6 +38,10 @@ struct Args {
     /// Address to listen on
     #[arg(short, long, default_value = "0.0.0.0:8080")]
     listen: String,
+
+    /// Log JSON lines (overrides `logging.format`)
+    #[arg(long)]
+    json_logs: bool,
 }
 
 #[tokio::main]
@@ -40,26 +52,28 @@ async fn main() -> eyre::Result<()> {
         .install_default()
         .map_err(|_| eyre::eyre!("Failed to install rustls crypto provider"))?;
 
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
-
     // Parse CLI arguments
     let args = Args::parse();
 
-    info!(config = %args.config, listen = %args.listen, "Starting Vixy");
-
     // Load configuration
-    let config = Config::load(&args.config)?;
+    let mut config = Config::load(&args.config)?;
+    if args.json_logs {
+        config.logging.format = LogFormat::Json;
+    }
+
+    // Initialize logging and, if enabled, span export
+    let telemetry = telemetry::init(&config.logging, &config.tracing)?;
+
+    info!(config = %args.config, listen = %args.listen, "Starting Vixy");
+    if config.tracing.enabled {
+        info!(endpoint = %config.tracing.endpoint, "Exporting traces");
+    }
 
     info!(
         el_primary_count = config.el.primary.len(),
         el_backup_count = config.el.backup.len(),
-        cl_count = config.cl.len(),
+        cl_primary_count = config.cl.primary.len(),
+        cl_backup_count = config.cl.backup.len(),
         max_el_lag = config.global.max_el_lag_blocks,
         max_cl_lag = config.global.max_cl_lag_slots,
         "Configuration loaded"
@@ -68,22 +82,59 @@ async fn main() -> eyre::Result<()> {
     // Initialize application state
     let state = Arc::new(AppState::new(&config));
 
-    // Spawn the health monitor background task
+    // Restore the last known node state from before the restart
+    let state_file = PathBuf::from(&config.persistence.file);
+    if config.persistence.enabled {
+        let max_age = Duration::from_millis(config.persistence.max_age_ms);
+        match persistence::restore(&state, &state_file, max_age).await {
+            Ok(restored) => info!(restored, file = %state_file.display(), "Restored node state"),
+            Err(e) => warn!(error = format!("{e:#}"), "Failed to restore node state"),
+        }
+    }
+
+    // Send notifications for health events, subscribing before the initial health check
+    // so layers that are down at startup are reported. Nodes becoming healthy in that
+    // check resolve no alert, so they send nothing.
+    if config.notifications.enabled {
+        let notifier = Notifier::new(&config.notifications)?;
+        tokio::spawn(notifier.run(state.events.stream(None)));
+        info!(
+            sinks = config.notifications.sinks.len(),
+            "Notifications enabled"
+        );
+    }
+
+    // Check all nodes once so the first requests are routed on fresh health state
+    monitor::run_health_check_cycle(&state).await;
+    info!("Initial health check complete");
+
+    // Spawn the health monitor background task, starting after the initial check
     let monitor_state = state.clone();
     let monitor_interval = config.global.health_check_interval_ms;
     tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(monitor_interval)).await;
         monitor::run_health_monitor(monitor_state, monitor_interval).await;
     });
 
     info!(interval_ms = monitor_interval, "Health monitor started");
 
+    // Save node state periodically; it is also saved on shutdown
+    if config.persistence.enabled && config.persistence.save_interval_ms > 0 {
+        tokio::spawn(persistence::run_periodic_save(
+            state.clone(),
+            state_file.clone(),
+            Duration::from_millis(config.persistence.save_interval_ms),
+        ));
+    }
+    let saved_state = state.clone();
+
     // Initialize metrics if enabled (triggers lazy static initialization)
     if config.metrics.enabled {
         let _ = &*vixy::metrics::METRICS;
     }
 
-    // Build the main router
-    let mut app = Router::new()
+    // Proxy routes, guarded by API keys when auth is enabled
+    let mut proxy_routes = Router::new()
         // EL HTTP proxy (with and without trailing slash)
         .route("/el", axum::routing::post(http::el_proxy_handler))
         .route("/el/", axum::routing::post(http::el_proxy_handler))
@@ -93,11 +144,38 @@ async fn main() -> eyre::Result<()> {
         // CL HTTP proxy (all paths under /cl/, including bare /cl and /cl/)
         .route("/cl", axum::routing::any(http::cl_proxy_handler))
         .route("/cl/", axum::routing::any(http::cl_proxy_handler))
-        .route("/cl/{*path}", axum::routing::any(http::cl_proxy_handler))
+        .route("/cl/{*path}", axum::routing::any(http::cl_proxy_handler));
+
+    // API keys given as a path segment (/el/<key>, /el/ws/<key>; /cl/<key>/... is
+    // already covered by the CL wildcard route)
+    if config.auth.enabled && config.auth.path_segment {
+        proxy_routes = proxy_routes
+            .route("/el/{key}", axum::routing::post(http::el_proxy_handler))
+            .route("/el/ws/{key}", axum::routing::get(ws::el_ws_handler));
+    }
+
+    let mut proxy_routes = proxy_routes.route_layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        auth::require_api_key,
+    ));
+
+    // Access log, outside auth so rejected requests are logged too
+    if config.logging.access_log.enabled {
+        proxy_routes = proxy_routes.route_layer(axum::middleware::from_fn(access_log::log_request));
+    }
+
+    // Request IDs, outermost so every response carries one
+    let proxy_routes = proxy_routes.route_layer(axum::middleware::from_fn(request_id::propagate));
+
+    // Build the main router
+    let mut app = Router::new()
+        .merge(proxy_routes)
         // Health endpoint for the proxy itself
         .route("/health", axum::routing::get(|| async { "OK" }))
         // Status endpoint - JSON view of all node states
         .route("/status", axum::routing::get(http::status_handler))
+        // Health event log, as JSON or a live event stream
+        .route("/status/events", axum::routing::get(http::events_handler))
         .with_state(state);
 
     // Handle metrics based on configuration
@@ -151,9 +229,24 @@ async fn main() -> eyre::Result<()> {
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
     // Serve with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
+
+    if config.persistence.enabled {
+        match persistence::save(&saved_state, &state_file).await {
+            Ok(()) => info!(file = %state_file.display(), "Saved node state"),
+            Err(e) => error!(error = format!("{e:#}"), "Failed to save node state"),
+        }
+    }
+
+    // Flush buffered spans and access log lines
+    if let Err(e) = telemetry.shutdown() {
+        error!(error = %e, "Failed to flush telemetry");
+    }
 
     info!("Vixy shut down gracefully");
 
@@ -19,6 +19,14 @@ pub struct VixyMetrics {
     #[metric(rename = "el_request_duration_seconds", labels = ["node", "tier"])]
     el_request_duration: Histogram,
 
+    /// EL HTTP requests by JSON-RPC method, outcome and HTTP status
+    #[metric(rename = "el_method_requests_total", labels = ["method", "outcome", "status"])]
+    el_method_requests: Counter,
+
+    /// EL HTTP request duration in seconds by JSON-RPC method and outcome
+    #[metric(rename = "el_method_request_duration_seconds", labels = ["method", "outcome"])]
+    el_method_request_duration: Histogram,
+
     /// Current block number for EL node
     #[metric(rename = "el_node_block_number", labels = ["node", "tier"])]
     el_block_number: Gauge,
@@ -39,6 +47,22 @@ pub struct VixyMetrics {
     #[metric(rename = "el_failovers_total")]
     el_failovers: Counter,
 
+    /// Times an EL node went from healthy to unhealthy
+    #[metric(rename = "el_node_flaps_total", labels = ["node", "tier"])]
+    el_node_flaps: Counter,
+
+    /// EL health check duration in seconds
+    #[metric(rename = "el_health_check_duration_seconds", labels = ["node", "tier"])]
+    el_health_check_duration: Histogram,
+
+    /// Failed EL health checks (reason = connect, timeout, http_status, parse, rpc_error)
+    #[metric(rename = "el_health_check_failures_total", labels = ["node", "tier", "reason"])]
+    el_health_check_failures: Counter,
+
+    /// Times an EL node was ejected by outlier detection
+    #[metric(rename = "el_node_ejections_total", labels = ["node", "tier"])]
+    el_node_ejections: Counter,
+
     /// Current EL chain head block number
     #[metric(rename = "el_chain_head")]
     el_chain_head: Gauge,
@@ -47,27 +71,91 @@ pub struct VixyMetrics {
     #[metric(rename = "el_healthy_nodes")]
     el_healthy_nodes: Gauge,
 
+    /// Broadcast transaction results per node (accepted, rejected, failed)
+    #[metric(rename = "el_broadcast_results_total", labels = ["node", "result"])]
+    el_broadcast_results: Counter,
+
+    /// EL calls rejected by the method filter (route = el, el_ws)
+    #[metric(rename = "el_method_rejections_total", labels = ["key", "route"])]
+    el_method_rejections: Counter,
+
+    /// EL calls retried on another node (reason = error, null_result)
+    #[metric(rename = "el_retries_total", labels = ["node", "reason"])]
+    el_retries: Counter,
+
+    /// Hedgeable EL calls by outcome (not_hedged, first_won, hedge_won)
+    #[metric(rename = "el_hedge_requests_total", labels = ["outcome"])]
+    el_hedge_requests: Counter,
+
+    /// EL response cache lookups (hit, miss, bypass)
+    #[metric(rename = "el_cache_requests_total", labels = ["result"])]
+    el_cache_requests: Counter,
+
+    /// Requests answered by sharing an identical in-flight upstream call (layer = el, cl)
+    #[metric(rename = "coalesced_requests_total", labels = ["layer"])]
+    coalesced_requests: Counter,
+
+    /// Number of entries in the EL response cache
+    #[metric(rename = "el_cache_entries")]
+    el_cache_entries: Gauge,
+
+    /// Approximate memory used by the EL response cache in bytes
+    #[metric(rename = "el_cache_size_bytes")]
+    el_cache_size_bytes: Gauge,
+
     // CL metrics
     /// Total CL requests proxied
-    #[metric(rename = "cl_requests_total", labels = ["node"])]
+    #[metric(rename = "cl_requests_total", labels = ["node", "tier"])]
     cl_requests: Counter,
 
     /// CL request duration in seconds
-    #[metric(rename = "cl_request_duration_seconds", labels = ["node"])]
+    #[metric(rename = "cl_request_duration_seconds", labels = ["node", "tier"])]
     cl_request_duration: Histogram,
 
+    /// CL requests by beacon API route template and HTTP status
+    #[metric(rename = "cl_endpoint_requests_total", labels = ["route", "status"])]
+    cl_endpoint_requests: Counter,
+
+    /// CL request duration in seconds by beacon API route template
+    #[metric(rename = "cl_endpoint_request_duration_seconds", labels = ["route"])]
+    cl_endpoint_request_duration: Histogram,
+
     /// Current slot for CL node
-    #[metric(rename = "cl_node_slot", labels = ["node"])]
+    #[metric(rename = "cl_node_slot", labels = ["node", "tier"])]
     cl_slot: Gauge,
 
     /// Slot lag for CL node
-    #[metric(rename = "cl_node_lag_slots", labels = ["node"])]
+    #[metric(rename = "cl_node_lag_slots", labels = ["node", "tier"])]
     cl_lag: Gauge,
 
     /// CL node health status (1=healthy, 0=unhealthy)
-    #[metric(rename = "cl_node_healthy", labels = ["node"])]
+    #[metric(rename = "cl_node_healthy", labels = ["node", "tier"])]
     cl_healthy: Gauge,
 
+    /// CL failover active status (1=active, 0=inactive)
+    #[metric(rename = "cl_failover_active")]
+    cl_failover_active: Gauge,
+
+    /// Total CL failovers triggered
+    #[metric(rename = "cl_failovers_total")]
+    cl_failovers: Counter,
+
+    /// Times a CL node went from healthy to unhealthy
+    #[metric(rename = "cl_node_flaps_total", labels = ["node", "tier"])]
+    cl_node_flaps: Counter,
+
+    /// CL health check duration in seconds
+    #[metric(rename = "cl_health_check_duration_seconds", labels = ["node", "tier"])]
+    cl_health_check_duration: Histogram,
+
+    /// Failed CL health checks (reason = connect, timeout, http_status, parse, rpc_error)
+    #[metric(rename = "cl_health_check_failures_total", labels = ["node", "tier", "reason"])]
+    cl_health_check_failures: Counter,
+
+    /// Times an CL node was ejected by outlier detection
+    #[metric(rename = "cl_node_ejections_total", labels = ["node", "tier"])]
+    cl_node_ejections: Counter,
+
     /// Current CL chain head slot
     #[metric(rename = "cl_chain_head")]
     cl_chain_head: Gauge,
@@ -76,6 +164,26 @@ pub struct VixyMetrics {
     #[metric(rename = "cl_healthy_nodes")]
     cl_healthy_nodes: Gauge,
 
+    /// Broadcast publish results per node (accepted, rejected, failed)
+    #[metric(rename = "cl_broadcast_results_total", labels = ["node", "result"])]
+    cl_broadcast_results: Counter,
+
+    /// CL requests rejected by a path rule
+    #[metric(rename = "cl_route_rejections_total", labels = ["method"])]
+    cl_route_rejections: Counter,
+
+    /// Active beacon event streams
+    #[metric(rename = "cl_sse_streams_active")]
+    cl_sse_streams: Gauge,
+
+    /// Beacon event stream upstream switches (reason = closed, error, unhealthy)
+    #[metric(rename = "cl_sse_reconnections_total", labels = ["reason"])]
+    cl_sse_reconnections: Counter,
+
+    /// Beacon events received from upstream (result = forwarded, duplicate)
+    #[metric(rename = "cl_sse_events_total", labels = ["result"])]
+    cl_sse_events: Counter,
+
     // WebSocket metrics
     /// Active WebSocket connections
     #[metric(rename = "ws_connections_active")]
@@ -108,6 +216,27 @@ pub struct VixyMetrics {
     /// Current upstream node for WebSocket connections
     #[metric(rename = "ws_upstream_node", labels = ["node"])]
     ws_upstream_node: Gauge,
+
+    /// Requests per API key (route = el, el_ws, cl; result = allowed, unauthorized, rate_limited)
+    #[metric(rename = "api_key_requests_total", labels = ["key", "route", "result"])]
+    api_key_requests: Counter,
+
+    /// Active WebSocket connections per API key
+    #[metric(rename = "api_key_ws_connections", labels = ["key"])]
+    api_key_ws_connections: Gauge,
+
+    /// In-flight WebSocket requests recovered after an upstream switch
+    #[metric(rename = "ws_inflight_recovered_total", labels = ["action"])]
+    ws_inflight_recovered: Counter,
+
+    // Notification metrics
+    /// Notifications per sink (result = sent, failed, dropped)
+    #[metric(rename = "notifications_total", labels = ["sink", "result"])]
+    notifications: Counter,
+
+    /// Notifications not sent (reason = duplicate, rate_limited)
+    #[metric(rename = "notifications_suppressed_total", labels = ["reason"])]
+    notifications_suppressed: Counter,
 }
 
 /// Global metrics instance
@@ -137,6 +266,16 @@ impl VixyMetrics {
             .observe(duration_secs);
     }
 
+    /// Record a completed EL HTTP request by method, outcome and status
+    pub fn observe_el_method_request(method: &str, outcome: &str, status: u16, duration_secs: f64) {
+        METRICS
+            .el_method_requests(method, outcome, status.to_string())
+            .inc();
+        METRICS
+            .el_method_request_duration(method, outcome)
+            .observe(duration_secs);
+    }
+
     /// Set EL node block number
     pub fn set_el_block_number(node: &str, tier: &str, block: u64) {
         METRICS.el_block_number(node, tier).set(block);
@@ -166,6 +305,28 @@ impl VixyMetrics {
         METRICS.el_failovers().inc();
     }
 
+    /// Record an EL node going from healthy to unhealthy
+    pub fn inc_el_node_flaps(node: &str, tier: &str) {
+        METRICS.el_node_flaps(node, tier).inc();
+    }
+
+    /// Record the duration of an EL health check
+    pub fn observe_el_health_check(node: &str, tier: &str, duration_secs: f64) {
+        METRICS
+            .el_health_check_duration(node, tier)
+            .observe(duration_secs);
+    }
+
+    /// Record a failed EL health check
+    pub fn inc_el_health_check_failure(node: &str, tier: &str, reason: &str) {
+        METRICS.el_health_check_failures(node, tier, reason).inc();
+    }
+
+    /// Record an EL node being ejected by outlier detection
+    pub fn inc_el_node_ejections(node: &str, tier: &str) {
+        METRICS.el_node_ejections(node, tier).inc();
+    }
+
     /// Set EL chain head
     pub fn set_el_chain_head(block: u64) {
         METRICS.el_chain_head().set(block);
@@ -176,37 +337,119 @@ impl VixyMetrics {
         METRICS.el_healthy_nodes().set(count);
     }
 
+    /// Record the result of a broadcast transaction on one node
+    pub fn inc_el_broadcast_result(node: &str, result: &str) {
+        METRICS.el_broadcast_results(node, result).inc();
+    }
+
+    /// Record an EL call rejected by the method filter
+    pub fn inc_el_method_rejection(key: &str, route: &str) {
+        METRICS.el_method_rejections(key, route).inc();
+    }
+
+    /// Record an EL call retried on another node after `node` answered it
+    pub fn inc_el_retry(node: &str, reason: &str) {
+        METRICS.el_retries(node, reason).inc();
+    }
+
+    /// Record the outcome of a hedgeable EL call
+    pub fn inc_el_hedge_request(outcome: &str) {
+        METRICS.el_hedge_requests(outcome).inc();
+    }
+
+    /// Record an EL response cache lookup (result = "hit", "miss" or "bypass")
+    pub fn inc_el_cache_request(result: &str) {
+        METRICS.el_cache_requests(result).inc();
+    }
+
+    /// Record a request served by a coalesced upstream call
+    pub fn inc_coalesced_requests(layer: &str) {
+        METRICS.coalesced_requests(layer).inc();
+    }
+
+    /// Set the EL response cache usage
+    pub fn set_el_cache_usage(entries: u64, bytes: u64) {
+        METRICS.el_cache_entries().set(entries);
+        METRICS.el_cache_size_bytes().set(bytes);
+    }
+
     // =========================================================================
     // CL Metrics helpers
     // =========================================================================
 
     /// Increment CL request counter
-    pub fn inc_cl_requests(node: &str) {
-        METRICS.cl_requests(node).inc();
+    pub fn inc_cl_requests(node: &str, tier: &str) {
+        METRICS.cl_requests(node, tier).inc();
     }
 
     /// Record CL request duration
-    pub fn observe_cl_duration(node: &str, duration_secs: f64) {
-        METRICS.cl_request_duration(node).observe(duration_secs);
+    pub fn observe_cl_duration(node: &str, tier: &str, duration_secs: f64) {
+        METRICS
+            .cl_request_duration(node, tier)
+            .observe(duration_secs);
+    }
+
+    /// Record a completed CL request by route template and status
+    pub fn observe_cl_endpoint_request(route: &str, status: u16, duration_secs: f64) {
+        METRICS
+            .cl_endpoint_requests(route, status.to_string())
+            .inc();
+        METRICS
+            .cl_endpoint_request_duration(route)
+            .observe(duration_secs);
     }
 
     /// Set CL node slot
-    pub fn set_cl_slot(node: &str, slot: u64) {
-        METRICS.cl_slot(node).set(slot);
+    pub fn set_cl_slot(node: &str, tier: &str, slot: u64) {
+        METRICS.cl_slot(node, tier).set(slot);
     }
 
     /// Set CL node lag
-    pub fn set_cl_lag(node: &str, lag: u64) {
-        METRICS.cl_lag(node).set(lag);
+    pub fn set_cl_lag(node: &str, tier: &str, lag: u64) {
+        METRICS.cl_lag(node, tier).set(lag);
     }
 
     /// Set CL node health status (1 = healthy, 0 = unhealthy)
-    pub fn set_cl_healthy(node: &str, healthy: bool) {
+    pub fn set_cl_healthy(node: &str, tier: &str, healthy: bool) {
         METRICS
-            .cl_healthy(node)
+            .cl_healthy(node, tier)
             .set(if healthy { 1u64 } else { 0u64 });
     }
 
+    /// Set CL failover status (1 = active, 0 = inactive)
+    pub fn set_cl_failover_active(active: bool) {
+        METRICS
+            .cl_failover_active()
+            .set(if active { 1u64 } else { 0u64 });
+    }
+
+    /// Increment CL failover counter
+    pub fn inc_cl_failovers() {
+        METRICS.cl_failovers().inc();
+    }
+
+    /// Record a CL node going from healthy to unhealthy
+    pub fn inc_cl_node_flaps(node: &str, tier: &str) {
+        METRICS.cl_node_flaps(node, tier).inc();
+    }
+
+    /// Record the duration of an CL health check
+    pub fn observe_cl_health_check(node: &str, tier: &str, duration_secs: f64) {
+        METRICS
+            .cl_health_check_duration(node, tier)
+            .observe(duration_secs);
+    }
+
+    /// Record a failed CL health check
+    pub fn inc_cl_health_check_failure(node: &str, tier: &str, reason: &str) {
+        METRICS.cl_health_check_failures(node, tier, reason).inc();
+    }
+
+    /// Record an CL node being ejected by outlier detection
+    pub fn inc_cl_node_ejections(node: &str, tier: &str) {
+        METRICS.cl_node_ejections(node, tier).inc();
+    }
+
     /// Set CL chain head
     pub fn set_cl_chain_head(slot: u64) {
         METRICS.cl_chain_head().set(slot);
@@ -217,6 +460,36 @@ impl VixyMetrics {
         METRICS.cl_healthy_nodes().set(count);
     }
 
+    /// Record the result of a broadcast publish request on one node
+    pub fn inc_cl_broadcast_result(node: &str, result: &str) {
+        METRICS.cl_broadcast_results(node, result).inc();
+    }
+
+    /// Record a CL request rejected by a path rule
+    pub fn inc_cl_route_rejection(method: &str) {
+        METRICS.cl_route_rejections(method).inc();
+    }
+
+    /// Increment active beacon event streams
+    pub fn inc_cl_sse_streams() {
+        METRICS.cl_sse_streams().inc();
+    }
+
+    /// Decrement active beacon event streams
+    pub fn dec_cl_sse_streams() {
+        METRICS.cl_sse_streams().dec();
+    }
+
+    /// Record a beacon event stream switching upstream node
+    pub fn inc_cl_sse_reconnection(reason: &str) {
+        METRICS.cl_sse_reconnections(reason).inc();
+    }
+
+    /// Record a beacon event received from upstream (result = "forwarded" or "duplicate")
+    pub fn inc_cl_sse_event(result: &str) {
+        METRICS.cl_sse_events(result).inc();
+    }
+
     // =========================================================================
     // WebSocket Metrics helpers
     // =========================================================================
@@ -269,6 +542,36 @@ impl VixyMetrics {
             .ws_upstream_node(node)
             .set(if connected { 1u64 } else { 0u64 });
     }
+
+    /// Record a request made with an API key
+    pub fn inc_api_key_request(key: &str, route: &str, result: &str) {
+        METRICS.api_key_requests(key, route, result).inc();
+    }
+
+    /// Set the number of active WebSocket connections of an API key
+    pub fn set_api_key_ws_connections(key: &str, count: u64) {
+        METRICS.api_key_ws_connections(key).set(count);
+    }
+
+    /// Increment in-flight WebSocket requests recovered on reconnection
+    /// (action = "resent" or "failed")
+    pub fn inc_ws_inflight_recovered(action: &str) {
+        METRICS.ws_inflight_recovered(action).inc();
+    }
+
+    // =========================================================================
+    // Notification Metrics helpers
+    // =========================================================================
+
+    /// Record the result of a notification for a sink
+    pub fn inc_notifications(sink: &str, result: &str) {
+        METRICS.notifications(sink, result).inc();
+    }
+
+    /// Record a notification that was not sent
+    pub fn inc_notifications_suppressed(reason: &str) {
+        METRICS.notifications_suppressed(reason).inc();
+    }
 }
 
 #[cfg(test)]
@@ -314,9 +617,9 @@ mod tests {
         VixyMetrics::set_el_block_number("geth-1", "primary", 100);
         VixyMetrics::set_el_lag("geth-1", "primary", 5);
         VixyMetrics::set_el_healthy("geth-1", "primary", true);
-        VixyMetrics::set_cl_slot("lighthouse-1", 200);
-        VixyMetrics::set_cl_lag("lighthouse-1", 2);
-        VixyMetrics::set_cl_healthy("lighthouse-1", true);
+        VixyMetrics::set_cl_slot("lighthouse-1", "primary", 200);
+        VixyMetrics::set_cl_lag("lighthouse-1", "primary", 2);
+        VixyMetrics::set_cl_healthy("lighthouse-1", "primary", true);
 
         let output = VixyMetrics::render();
         assert!(output.contains("vixy_el_node_block_number"));
@@ -348,13 +651,93 @@ mod tests {
         VixyMetrics::set_ws_subscriptions(5);
         VixyMetrics::set_ws_upstream_node("geth-1", true);
         VixyMetrics::set_ws_upstream_node("geth-2", false);
+        VixyMetrics::inc_ws_inflight_recovered("resent");
+        VixyMetrics::inc_ws_inflight_recovered("failed");
         // If we get here without panic, subscription metrics are working
     }
 
+    #[test]
+    fn test_broadcast_metrics() {
+        VixyMetrics::inc_el_broadcast_result("geth-1", "accepted");
+        VixyMetrics::inc_el_broadcast_result("geth-2", "rejected");
+        VixyMetrics::inc_cl_broadcast_result("lighthouse-1", "accepted");
+        VixyMetrics::inc_cl_route_rejection("GET");
+        VixyMetrics::inc_cl_sse_streams();
+        VixyMetrics::dec_cl_sse_streams();
+        VixyMetrics::inc_cl_sse_reconnection("unhealthy");
+        VixyMetrics::inc_cl_sse_event("duplicate");
+
+        let output = VixyMetrics::render();
+        assert!(output.contains("vixy_el_broadcast_results_total"));
+        assert!(output.contains("vixy_cl_broadcast_results_total"));
+        assert!(output.contains("vixy_cl_route_rejections_total"));
+        assert!(output.contains("vixy_cl_sse_reconnections_total"));
+        assert!(output.contains("vixy_cl_sse_events_total"));
+    }
+
+    #[test]
+    fn test_method_and_route_metrics() {
+        VixyMetrics::observe_el_method_request("eth_call", "jsonrpc_error", 200, 0.01);
+        VixyMetrics::observe_cl_endpoint_request("/eth/v1/node/health", 503, 0.01);
+
+        let output = VixyMetrics::render();
+        assert!(output.contains("vixy_el_method_requests_total"));
+        assert!(output.contains(r#"outcome="jsonrpc_error""#));
+        assert!(output.contains("vixy_el_method_request_duration_seconds"));
+        assert!(output.contains(r#"route="/eth/v1/node/health""#));
+        assert!(output.contains("vixy_cl_endpoint_request_duration_seconds"));
+    }
+
+    #[test]
+    fn test_cache_metrics() {
+        VixyMetrics::inc_el_cache_request("hit");
+        VixyMetrics::inc_el_cache_request("miss");
+        VixyMetrics::set_el_cache_usage(3, 1024);
+        VixyMetrics::inc_coalesced_requests("el");
+        VixyMetrics::inc_el_method_rejection("anonymous", "el");
+        VixyMetrics::inc_el_hedge_request("hedge_won");
+        VixyMetrics::inc_el_retry("geth-1", "error");
+
+        let output = VixyMetrics::render();
+        assert!(output.contains("vixy_el_cache_requests_total"));
+        assert!(output.contains("vixy_el_hedge_requests_total"));
+        assert!(output.contains("vixy_el_cache_size_bytes"));
+        assert!(output.contains("vixy_coalesced_requests_total"));
+    }
+
+    #[test]
+    fn test_api_key_metrics() {
+        VixyMetrics::inc_api_key_request("indexer", "el", "allowed");
+        VixyMetrics::inc_api_key_request("anonymous", "cl", "unauthorized");
+        VixyMetrics::set_api_key_ws_connections("indexer", 2);
+
+        let output = VixyMetrics::render();
+        assert!(output.contains("vixy_api_key_requests_total"));
+        assert!(output.contains("vixy_api_key_ws_connections"));
+    }
+
+    #[test]
+    fn test_health_check_metrics() {
+        VixyMetrics::observe_el_health_check("geth-1", "primary", 0.02);
+        VixyMetrics::inc_el_health_check_failure("geth-1", "primary", "timeout");
+        VixyMetrics::observe_cl_health_check("lighthouse-1", "primary", 0.02);
+        VixyMetrics::inc_cl_health_check_failure("lighthouse-1", "primary", "http_status");
+
+        let output = VixyMetrics::render();
+        assert!(output.contains("vixy_el_health_check_duration_seconds"));
+        assert!(output.contains("vixy_el_health_check_failures_total"));
+        assert!(output.contains("vixy_cl_health_check_duration_seconds"));
+        assert!(output.contains(r#"reason="http_status""#));
+    }
+
     #[test]
     fn test_failover_counter() {
         VixyMetrics::inc_el_failovers();
         VixyMetrics::set_el_failover_active(true);
+        VixyMetrics::inc_el_node_flaps("geth-1", "primary");
+        VixyMetrics::inc_cl_node_flaps("lighthouse-1", "backup");
+        VixyMetrics::inc_el_node_ejections("geth-1", "primary");
+        VixyMetrics::inc_cl_node_ejections("lighthouse-1", "primary");
         // If we get here without panic, failover metrics are working
     }
 }
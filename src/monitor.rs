@@ -2,29 +2,32 @@
 //!
 //! Background task that periodically checks all EL and CL nodes and updates their health state.
 
-use std::sync::Arc;
-use std::sync::atomic::Ordering;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use tracing::{debug, info, warn};
+use tracing::{Instrument, debug, info, info_span, warn};
 
-use crate::health::{cl, el};
+use crate::events::{EventKind, HealthState, Layer};
+use crate::health::{self, cl, el};
 use crate::metrics::VixyMetrics;
-use crate::state::AppState;
+use crate::state::{AppState, HeadProgress};
 
 /// Run a single health check cycle for all nodes
 ///
 /// This function checks all EL and CL nodes once and updates their state.
 /// Returns true if at least one primary EL node is healthy.
+#[tracing::instrument(name = "health_check_cycle", skip_all)]
 pub async fn run_health_check_cycle(state: &Arc<AppState>) -> bool {
     // Check all EL nodes
     let any_primary_healthy = check_all_el_nodes(state).await;
 
     // Check all CL nodes
-    check_all_cl_nodes(state).await;
+    let any_cl_primary_healthy = check_all_cl_nodes(state).await;
 
-    // Update failover flag
+    // Update failover flags
     update_failover_flag(state, any_primary_healthy);
+    update_cl_failover_flag(state, any_cl_primary_healthy);
 
     any_primary_healthy
 }
@@ -34,11 +37,22 @@ pub async fn run_health_check_cycle(state: &Arc<AppState>) -> bool {
 /// Returns true if at least one primary EL node is healthy.
 pub async fn check_all_el_nodes(state: &Arc<AppState>) -> bool {
     // First pass: check each node and update block numbers
-    {
+    let failure_reasons = {
         let mut el_nodes = state.el_nodes.write().await;
+        let mut failure_reasons = Vec::with_capacity(el_nodes.len());
 
         for node in el_nodes.iter_mut() {
-            match el::check_el_node(&node.http_url).await {
+            let tier = if node.is_primary { "primary" } else { "backup" };
+            let check_start = Instant::now();
+            let result = el::check_el_node(&node.http_url, state.health_check_timeout)
+                .instrument(info_span!("el_health_check", node = %node.name, tier))
+                .await;
+            let check_duration = check_start.elapsed();
+            node.check_latency_ms = check_duration.as_millis() as u64;
+            VixyMetrics::observe_el_health_check(&node.name, tier, check_duration.as_secs_f64());
+
+            failure_reasons.push(result.as_ref().err().map(health::failure_reason));
+            match result {
                 Ok(block_number) => {
                     node.block_number = block_number;
                     node.check_ok = true;
@@ -50,8 +64,11 @@ pub async fn check_all_el_nodes(state: &Arc<AppState>) -> bool {
                 }
                 Err(e) => {
                     // On error, mark check as failed
+                    let reason = health::failure_reason(&e);
+                    VixyMetrics::inc_el_health_check_failure(&node.name, tier, reason);
                     warn!(
                         node = %node.name,
+                        reason,
                         error = %e,
                         "EL node check failed"
                     );
@@ -60,7 +77,9 @@ pub async fn check_all_el_nodes(state: &Arc<AppState>) -> bool {
                 }
             }
         }
-    }
+
+        failure_reasons
+    };
 
     // Calculate chain head (max block number across all nodes)
     let chain_head = {
@@ -70,23 +89,76 @@ pub async fn check_all_el_nodes(state: &Arc<AppState>) -> bool {
 
     // Store chain head
     state.el_chain_head.store(chain_head, Ordering::SeqCst);
+    update_chain_progress(state, Layer::El, &state.el_head_progress, chain_head);
 
     // Update chain head metric
     VixyMetrics::set_el_chain_head(chain_head);
 
     // Second pass: calculate health for each node
     let mut el_nodes = state.el_nodes.write().await;
-    let mut any_primary_healthy = false;
-    let mut healthy_count = 0u64;
+    let previous_health: Vec<bool> = el_nodes.iter().map(|n| n.is_healthy).collect();
 
     for node in el_nodes.iter_mut() {
+        let was_healthy = node.is_healthy;
         el::calculate_el_health(
             node,
             chain_head,
             state.max_el_lag,
             state.health_check_max_failures,
+            state.health_check_recovery_successes,
         );
 
+        if was_healthy && !node.is_healthy {
+            let tier = if node.is_primary { "primary" } else { "backup" };
+            node.flaps = node.flaps.saturating_add(1);
+            VixyMetrics::inc_el_node_flaps(&node.name, tier);
+            warn!(node = %node.name, tier, "EL node marked unhealthy");
+        }
+    }
+
+    // Keep recovered primaries out of rotation until failover has lasted the
+    // minimum dwell time, as long as a backup can serve traffic meanwhile
+    if failover_dwelling(
+        &state.el_failover_active,
+        &state.el_failover_since,
+        state.failover_min_dwell,
+    ) && el_nodes.iter().any(|n| !n.is_primary && n.is_healthy)
+    {
+        for node in el_nodes.iter_mut().filter(|n| n.is_primary && n.is_healthy) {
+            debug!(node = %node.name, "Holding recovered primary EL node until failover dwell time has passed");
+            node.is_healthy = false;
+        }
+    }
+
+    // Record health transitions
+    for ((node, was_healthy), failure) in el_nodes
+        .iter()
+        .zip(previous_health)
+        .zip(failure_reasons)
+        .filter(|((node, was_healthy), _)| node.is_healthy != *was_healthy)
+    {
+        state.events.record(EventKind::NodeHealth {
+            layer: Layer::El,
+            node: node.name.clone(),
+            tier: if node.is_primary { "primary" } else { "backup" },
+            old_state: HealthState::from(was_healthy),
+            new_state: HealthState::from(node.is_healthy),
+            reason: transition_reason(
+                node.is_healthy,
+                failure,
+                node.lag > state.max_el_lag,
+                node.is_ejected(),
+            ),
+            lag: node.lag,
+            block: node.block_number,
+        });
+    }
+
+    // Third pass: collect results and update metrics
+    let mut any_primary_healthy = false;
+    let mut healthy_count = 0u64;
+
+    for node in el_nodes.iter() {
         if node.is_primary && node.is_healthy {
             any_primary_healthy = true;
         }
@@ -115,19 +187,41 @@ pub async fn check_all_el_nodes(state: &Arc<AppState>) -> bool {
 
     // Update healthy nodes count metric
     VixyMetrics::set_el_healthy_nodes(healthy_count);
+    update_all_down_flag(state, Layer::El, &state.el_all_down, healthy_count == 0);
 
     any_primary_healthy
 }
 
 /// Check all CL nodes and update their state
-pub async fn check_all_cl_nodes(state: &Arc<AppState>) {
+///
+/// Returns true if at least one primary CL node is healthy.
+pub async fn check_all_cl_nodes(state: &Arc<AppState>) -> bool {
     // First pass: check each node and update slots
-    {
+    let failure_reasons = {
         let mut cl_nodes = state.cl_nodes.write().await;
+        let mut failure_reasons = Vec::with_capacity(cl_nodes.len());
 
         for node in cl_nodes.iter_mut() {
-            match cl::check_cl_node(&node.url).await {
+            let tier = if node.is_primary { "primary" } else { "backup" };
+            let check_start = Instant::now();
+            let result = cl::check_cl_node(&node.url, state.health_check_timeout)
+                .instrument(info_span!("cl_health_check", node = %node.name, tier))
+                .await;
+            let check_duration = check_start.elapsed();
+            node.check_latency_ms = check_duration.as_millis() as u64;
+            VixyMetrics::observe_cl_health_check(&node.name, tier, check_duration.as_secs_f64());
+
+            failure_reasons.push(match &result {
+                Ok((true, _)) => None,
+                Ok((false, _)) => Some("http_status"),
+                Err(e) => Some(health::failure_reason(e)),
+            });
+            match result {
                 Ok((health_ok, slot)) => {
+                    if !health_ok {
+                        // The health endpoint answered with a non-success status
+                        VixyMetrics::inc_cl_health_check_failure(&node.name, tier, "http_status");
+                    }
                     node.health_ok = health_ok;
                     node.slot = slot;
                     debug!(
@@ -139,8 +233,11 @@ pub async fn check_all_cl_nodes(state: &Arc<AppState>) {
                 }
                 Err(e) => {
                     // On error, mark as unhealthy
+                    let reason = health::failure_reason(&e);
+                    VixyMetrics::inc_cl_health_check_failure(&node.name, tier, reason);
                     warn!(
                         node = %node.name,
+                        reason,
                         error = %e,
                         "CL node check failed"
                     );
@@ -149,7 +246,9 @@ pub async fn check_all_cl_nodes(state: &Arc<AppState>) {
                 }
             }
         }
-    }
+
+        failure_reasons
+    };
 
     // Calculate chain head (max slot across all nodes)
     let chain_head = {
@@ -159,50 +258,110 @@ pub async fn check_all_cl_nodes(state: &Arc<AppState>) {
 
     // Store chain head
     state.cl_chain_head.store(chain_head, Ordering::SeqCst);
+    update_chain_progress(state, Layer::Cl, &state.cl_head_progress, chain_head);
 
     // Update chain head metric
     VixyMetrics::set_cl_chain_head(chain_head);
 
     // Second pass: calculate health for each node
+    let mut cl_nodes = state.cl_nodes.write().await;
+    let previous_health: Vec<bool> = cl_nodes.iter().map(|n| n.is_healthy).collect();
+
+    for node in cl_nodes.iter_mut() {
+        let was_healthy = node.is_healthy;
+        cl::calculate_cl_health(
+            node,
+            chain_head,
+            state.max_cl_lag,
+            state.health_check_max_failures,
+            state.health_check_recovery_successes,
+        );
+
+        if was_healthy && !node.is_healthy {
+            let tier = if node.is_primary { "primary" } else { "backup" };
+            node.flaps = node.flaps.saturating_add(1);
+            VixyMetrics::inc_cl_node_flaps(&node.name, tier);
+            warn!(node = %node.name, tier, "CL node marked unhealthy");
+        }
+    }
+
+    // Keep recovered primaries out of rotation until failover has lasted the
+    // minimum dwell time, as long as a backup can serve traffic meanwhile
+    if failover_dwelling(
+        &state.cl_failover_active,
+        &state.cl_failover_since,
+        state.failover_min_dwell,
+    ) && cl_nodes.iter().any(|n| !n.is_primary && n.is_healthy)
     {
-        let mut cl_nodes = state.cl_nodes.write().await;
-        let mut healthy_count = 0u64;
+        for node in cl_nodes.iter_mut().filter(|n| n.is_primary && n.is_healthy) {
+            debug!(node = %node.name, "Holding recovered primary CL node until failover dwell time has passed");
+            node.is_healthy = false;
+        }
+    }
 
-        for node in cl_nodes.iter_mut() {
-            cl::calculate_cl_health(
-                node,
-                chain_head,
-                state.max_cl_lag,
-                state.health_check_max_failures,
-            );
+    // Record health transitions
+    for ((node, was_healthy), failure) in cl_nodes
+        .iter()
+        .zip(previous_health)
+        .zip(failure_reasons)
+        .filter(|((node, was_healthy), _)| node.is_healthy != *was_healthy)
+    {
+        state.events.record(EventKind::NodeHealth {
+            layer: Layer::Cl,
+            node: node.name.clone(),
+            tier: if node.is_primary { "primary" } else { "backup" },
+            old_state: HealthState::from(was_healthy),
+            new_state: HealthState::from(node.is_healthy),
+            reason: transition_reason(
+                node.is_healthy,
+                failure,
+                node.lag > state.max_cl_lag,
+                node.is_ejected(),
+            ),
+            lag: node.lag,
+            block: node.slot,
+        });
+    }
 
-            if node.is_healthy {
-                healthy_count += 1;
-            }
+    // Third pass: collect results and update metrics
+    let mut any_primary_healthy = false;
+    let mut healthy_count = 0u64;
 
-            // Update per-node metrics
-            VixyMetrics::set_cl_slot(&node.name, node.slot);
-            VixyMetrics::set_cl_lag(&node.name, node.lag);
-            VixyMetrics::set_cl_healthy(&node.name, node.is_healthy);
-
-            debug!(
-                node = %node.name,
-                slot = node.slot,
-                health_ok = node.health_ok,
-                lag = node.lag,
-                consecutive_failures = node.consecutive_failures,
-                is_healthy = node.is_healthy,
-                "CL node health calculated"
-            );
+    for node in cl_nodes.iter() {
+        if node.is_primary && node.is_healthy {
+            any_primary_healthy = true;
+        }
+
+        if node.is_healthy {
+            healthy_count += 1;
         }
 
-        // Update healthy nodes count metric
-        VixyMetrics::set_cl_healthy_nodes(healthy_count);
+        // Update per-node metrics
+        let tier = if node.is_primary { "primary" } else { "backup" };
+        VixyMetrics::set_cl_slot(&node.name, tier, node.slot);
+        VixyMetrics::set_cl_lag(&node.name, tier, node.lag);
+        VixyMetrics::set_cl_healthy(&node.name, tier, node.is_healthy);
+
+        debug!(
+            node = %node.name,
+            slot = node.slot,
+            health_ok = node.health_ok,
+            lag = node.lag,
+            consecutive_failures = node.consecutive_failures,
+            is_healthy = node.is_healthy,
+            "CL node health calculated"
+        );
     }
+
+    // Update healthy nodes count metric
+    VixyMetrics::set_cl_healthy_nodes(healthy_count);
+    update_all_down_flag(state, Layer::Cl, &state.cl_all_down, healthy_count == 0);
+
+    any_primary_healthy
 }
 
 /// Update the failover flag based on primary EL node availability
-pub fn update_failover_flag(state: &Arc<AppState>, any_primary_healthy: bool) {
+pub fn update_failover_flag(state: &AppState, any_primary_healthy: bool) {
     let was_failover = state.el_failover_active.load(Ordering::SeqCst);
     let is_failover = !any_primary_healthy;
 
@@ -210,6 +369,15 @@ pub fn update_failover_flag(state: &Arc<AppState>, any_primary_healthy: bool) {
         state
             .el_failover_active
             .store(is_failover, Ordering::SeqCst);
+        *state
+            .el_failover_since
+            .lock()
+            .expect("failover lock poisoned") = is_failover.then(Instant::now);
+
+        state.events.record(EventKind::Failover {
+            layer: Layer::El,
+            active: is_failover,
+        });
 
         // Update metrics
         VixyMetrics::set_el_failover_active(is_failover);
@@ -222,6 +390,132 @@ pub fn update_failover_flag(state: &Arc<AppState>, any_primary_healthy: bool) {
     }
 }
 
+/// Update the CL failover flag based on primary CL node availability
+pub fn update_cl_failover_flag(state: &AppState, any_primary_healthy: bool) {
+    let was_failover = state.cl_failover_active.load(Ordering::SeqCst);
+    let is_failover = !any_primary_healthy;
+
+    if was_failover != is_failover {
+        state
+            .cl_failover_active
+            .store(is_failover, Ordering::SeqCst);
+        *state
+            .cl_failover_since
+            .lock()
+            .expect("failover lock poisoned") = is_failover.then(Instant::now);
+
+        state.events.record(EventKind::Failover {
+            layer: Layer::Cl,
+            active: is_failover,
+        });
+
+        // Update metrics
+        VixyMetrics::set_cl_failover_active(is_failover);
+        if is_failover {
+            VixyMetrics::inc_cl_failovers();
+            warn!("CL failover ACTIVATED - all primary nodes unhealthy, using backups");
+        } else {
+            info!("CL failover DEACTIVATED - primary node recovered");
+        }
+    }
+}
+
+/// Record when no node of a layer is healthy any more, or one became healthy again
+fn update_all_down_flag(state: &AppState, layer: Layer, flag: &AtomicBool, all_down: bool) {
+    if flag.swap(all_down, Ordering::SeqCst) == all_down {
+        return;
+    }
+
+    if all_down {
+        warn!(layer = layer.name(), "All nodes are down");
+    } else {
+        info!(layer = layer.name(), "Nodes are back up");
+    }
+    state.events.record(EventKind::AllNodesDown {
+        layer,
+        active: all_down,
+    });
+}
+
+/// Record when the chain head has not advanced for the stall timeout, or advances again
+fn update_chain_progress(
+    state: &AppState,
+    layer: Layer,
+    progress: &Mutex<HeadProgress>,
+    chain_head: u64,
+) {
+    // Nothing to watch before any node reported a chain head
+    if state.chain_stall_timeout.is_zero() || chain_head == 0 {
+        return;
+    }
+
+    let mut progress = progress.lock().expect("chain progress lock poisoned");
+    let stalled_ms = progress.since.elapsed().as_millis() as u64;
+    if chain_head > progress.head {
+        if progress.stalled {
+            info!(
+                layer = layer.name(),
+                chain_head, stalled_ms, "Chain head advancing again"
+            );
+            state.events.record(EventKind::ChainStall {
+                layer,
+                head: chain_head,
+                stalled_ms,
+                active: false,
+            });
+        }
+        *progress = HeadProgress::new(chain_head);
+    } else if !progress.stalled && progress.since.elapsed() >= state.chain_stall_timeout {
+        progress.stalled = true;
+        warn!(
+            layer = layer.name(),
+            chain_head = progress.head,
+            stalled_ms,
+            "Chain head stalled"
+        );
+        state.events.record(EventKind::ChainStall {
+            layer,
+            head: progress.head,
+            stalled_ms,
+            active: true,
+        });
+    }
+}
+
+/// Why a node's health changed, given the outcome of its last check
+fn transition_reason(
+    is_healthy: bool,
+    check_failure: Option<&'static str>,
+    lagging: bool,
+    ejected: bool,
+) -> &'static str {
+    if is_healthy {
+        "recovered"
+    } else if let Some(reason) = check_failure {
+        reason
+    } else if ejected {
+        "ejected"
+    } else if lagging {
+        "lag"
+    } else {
+        // Recovered, but held back until failover has lasted the minimum dwell time
+        "failover_dwell"
+    }
+}
+
+/// Check whether failover is active and has not yet lasted `min_dwell`
+fn failover_dwelling(
+    active: &AtomicBool,
+    since: &Mutex<Option<Instant>>,
+    min_dwell: Duration,
+) -> bool {
+    active.load(Ordering::SeqCst)
+        && since
+            .lock()
+            .expect("failover lock poisoned")
+            .is_some_and(|t| t.elapsed() < min_dwell)
+}
+
 /// Run the health monitoring loop
 ///
 /// This function runs forever, periodically checking all nodes and updating their health state.
@@ -558,6 +852,211 @@ health_check_interval_ms = 100
         );
     }
 
+    // =========================================================================
+    // test_monitor_records_health_events
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_monitor_records_health_events() {
+        let primary_mock = MockServer::start().await;
+        let backup_mock = MockServer::start().await;
+
+        // Primary answers the first check, then fails
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": "0x3e8",
+                "id": 1
+            })))
+            .up_to_n_times(1)
+            .mount(&primary_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&primary_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": "0x3e8",
+                "id": 1
+            })))
+            .mount(&backup_mock)
+            .await;
+
+        let config = create_config_with_backup(&[&primary_mock.uri()], &[&backup_mock.uri()], &[]);
+        let mut state = AppState::new(&config);
+        state.health_check_max_failures = 1;
+        let state = Arc::new(state);
+
+        let any_primary_healthy = check_all_el_nodes(&state).await;
+        update_failover_flag(&state, any_primary_healthy);
+        let any_primary_healthy = check_all_el_nodes(&state).await;
+        update_failover_flag(&state, any_primary_healthy);
+
+        let events: Vec<EventKind> = state.events.recent().into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            events,
+            vec![
+                EventKind::NodeHealth {
+                    layer: Layer::El,
+                    node: "primary-0".to_string(),
+                    tier: "primary",
+                    old_state: HealthState::Unhealthy,
+                    new_state: HealthState::Healthy,
+                    reason: "recovered",
+                    lag: 0,
+                    block: 1000,
+                },
+                EventKind::NodeHealth {
+                    layer: Layer::El,
+                    node: "backup-0".to_string(),
+                    tier: "backup",
+                    old_state: HealthState::Unhealthy,
+                    new_state: HealthState::Healthy,
+                    reason: "recovered",
+                    lag: 0,
+                    block: 1000,
+                },
+                EventKind::NodeHealth {
+                    layer: Layer::El,
+                    node: "primary-0".to_string(),
+                    tier: "primary",
+                    old_state: HealthState::Healthy,
+                    new_state: HealthState::Unhealthy,
+                    reason: "http_status",
+                    lag: 0,
+                    block: 1000,
+                },
+                EventKind::Failover {
+                    layer: Layer::El,
+                    active: true,
+                },
+            ]
+        );
+    }
+
+    // =========================================================================
+    // test_monitor_reports_chain_stall_and_all_nodes_down
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_monitor_reports_chain_stall_and_all_nodes_down() {
+        let config = create_test_config(&["http://localhost:8545"], &[]);
+        let mut state = AppState::new(&config);
+        state.chain_stall_timeout = Duration::from_millis(20);
+
+        update_chain_progress(&state, Layer::El, &state.el_head_progress, 100);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        update_chain_progress(&state, Layer::El, &state.el_head_progress, 100);
+        // Reported once while stalled
+        update_chain_progress(&state, Layer::El, &state.el_head_progress, 100);
+        update_chain_progress(&state, Layer::El, &state.el_head_progress, 101);
+
+        update_all_down_flag(&state, Layer::Cl, &state.cl_all_down, true);
+        update_all_down_flag(&state, Layer::Cl, &state.cl_all_down, true);
+        update_all_down_flag(&state, Layer::Cl, &state.cl_all_down, false);
+
+        let events: Vec<EventKind> = state.events.recent().into_iter().map(|e| e.kind).collect();
+        assert!(matches!(
+            events[0],
+            EventKind::ChainStall {
+                layer: Layer::El,
+                head: 100,
+                active: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[1],
+            EventKind::ChainStall {
+                layer: Layer::El,
+                head: 101,
+                active: false,
+                ..
+            }
+        ));
+        assert_eq!(
+            events[2..],
+            [
+                EventKind::AllNodesDown {
+                    layer: Layer::Cl,
+                    active: true
+                },
+                EventKind::AllNodesDown {
+                    layer: Layer::Cl,
+                    active: false
+                },
+            ]
+        );
+    }
+
+    // =========================================================================
+    // test_monitor_sets_cl_failover_flag
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_monitor_sets_cl_failover_flag() {
+        // Primary CL node has no mocks mounted, so its health check fails
+        let primary_mock = MockServer::start().await;
+        let backup_mock = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/node/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&backup_mock)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/head"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {"header": {"message": {"slot": "5000"}}}
+            })))
+            .mount(&backup_mock)
+            .await;
+
+        let config = Config::parse(&format!(
+            r#"[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[cl]
+[[cl.primary]]
+name = "lighthouse-1"
+url = "{}"
+
+[[cl.backup]]
+name = "hosted-1"
+url = "{}"
+"#,
+            primary_mock.uri(),
+            backup_mock.uri()
+        ))
+        .expect("Test config should parse");
+        let state = Arc::new(AppState::new(&config));
+
+        let any_primary_healthy = check_all_cl_nodes(&state).await;
+        assert!(!any_primary_healthy);
+
+        update_cl_failover_flag(&state, any_primary_healthy);
+        assert!(
+            state.cl_failover_active.load(Ordering::SeqCst),
+            "CL failover should be active when no primary nodes are healthy"
+        );
+
+        // The backup becomes eligible for traffic only now
+        let cl_nodes = state.cl_nodes.read().await;
+        assert!(crate::proxy::selection::select_cl_node(&cl_nodes, false).is_none());
+        let selected = crate::proxy::selection::select_cl_node(&cl_nodes, true).unwrap();
+        assert_eq!(selected.name, "hosted-1");
+        drop(cl_nodes);
+
+        // Failover clears once a primary is healthy again
+        update_cl_failover_flag(&state, true);
+        assert!(!state.cl_failover_active.load(Ordering::SeqCst));
+    }
+
     // =========================================================================
     // test_monitor_clears_failover_when_primary_recovers
     // =========================================================================
@@ -606,6 +1105,43 @@ health_check_interval_ms = 100
         );
     }
 
+    #[tokio::test]
+    async fn test_monitor_holds_primary_during_failover_dwell() {
+        let primary_mock = MockServer::start().await;
+        let backup_mock = MockServer::start().await;
+
+        for mock in [&primary_mock, &backup_mock] {
+            Mock::given(method("POST"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": "0x3e8",
+                    "id": 1
+                })))
+                .mount(mock)
+                .await;
+        }
+
+        let config = create_config_with_backup(&[&primary_mock.uri()], &[&backup_mock.uri()], &[]);
+        let mut state = Arc::new(AppState::new(&config));
+        Arc::get_mut(&mut state).unwrap().failover_min_dwell = Duration::from_secs(60);
+
+        // Failover was just activated
+        update_failover_flag(&state, false);
+
+        // The primary passes its check but stays out of rotation
+        let any_primary_healthy = check_all_el_nodes(&state).await;
+        update_failover_flag(&state, any_primary_healthy);
+        assert!(!any_primary_healthy);
+        assert!(state.el_failover_active.load(Ordering::SeqCst));
+
+        // Once the dwell time has passed, the primary takes over again
+        *state.el_failover_since.lock().unwrap() = Some(Instant::now() - Duration::from_secs(61));
+        let any_primary_healthy = check_all_el_nodes(&state).await;
+        update_failover_flag(&state, any_primary_healthy);
+        assert!(any_primary_healthy);
+        assert!(!state.el_failover_active.load(Ordering::SeqCst));
+    }
+
     // =========================================================================
     // test_monitor_runs_at_configured_interval
     // =========================================================================
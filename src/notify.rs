@@ -0,0 +1,783 @@
+//! Notifications of health events
+//!
+//! Failovers, layers without a healthy node, node health transitions and chain head
+//! stalls are sent to the configured sinks: webhooks (optionally with a JSON template),
+//! Slack-compatible webhooks and the Alertmanager API. Repeats within the de-duplication
+//! window are dropped, sending is rate limited and failed deliveries are retried with
+//! exponential backoff.
+
+use std::collections::{HashMap, VecDeque};
+use std::pin::pin;
+use std::time::{Duration, Instant};
+
+use axum::http::header;
+use eyre::{Result, WrapErr};
+use futures_util::{Stream, StreamExt};
+use serde_json::{Map, Value, json};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use crate::config::{self, NotificationEvent, NotificationSink, SinkKind};
+use crate::events::{Event, EventKind, HealthState};
+use crate::metrics::VixyMetrics;
+
+/// Notifications queued per sink before new ones are dropped
+const SINK_QUEUE_SIZE: usize = 64;
+
+/// How often firing alerts are re-sent to Alertmanager, which resolves alerts it has
+/// not heard about for its `resolve_timeout`
+const ALERTMANAGER_RESEND_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Window the rate limit applies to
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Template fields with numeric values
+const NUMBER_FIELDS: &[&str] = &["id", "timestamp_ms", "lag", "block", "head", "stalled_ms"];
+
+/// Template fields with boolean values
+const BOOL_FIELDS: &[&str] = &["firing", "active"];
+
+/// Template fields with string values
+const STRING_FIELDS: &[&str] = &[
+    "summary",
+    "status",
+    "type",
+    "layer",
+    "node",
+    "tier",
+    "old_state",
+    "new_state",
+    "reason",
+];
+
+/// A notification about an event
+#[derive(Debug)]
+struct Notification {
+    trigger: NotificationEvent,
+    /// Whether a problem started (true) or ended (false)
+    firing: bool,
+    /// Identifies the problem; shared by its firing and resolved notifications
+    alert: String,
+    summary: String,
+    /// Event fields plus `summary`, `status` and `firing`
+    fields: Map<String, Value>,
+}
+
+impl Notification {
+    /// Notification for an event, if the event is one notifications are sent for
+    fn from_event(event: &Event) -> Option<Self> {
+        let (trigger, firing, alert, summary) = match &event.kind {
+            EventKind::NodeHealth {
+                layer,
+                node,
+                tier,
+                new_state,
+                reason,
+                lag,
+                block,
+                ..
+            } => {
+                let firing = *new_state == HealthState::Unhealthy;
+                let summary = if firing {
+                    format!(
+                        "{} node {node} ({tier}) is unhealthy: {reason} (lag {lag}, block {block})",
+                        layer.name()
+                    )
+                } else {
+                    format!("{} node {node} ({tier}) is healthy again", layer.name())
+                };
+                let alert = format!("node_health/{}/{node}", layer.name());
+                (NotificationEvent::NodeHealth, firing, alert, summary)
+            }
+            EventKind::Failover { layer, active } => {
+                let summary = if *active {
+                    format!(
+                        "{} failover activated: no primary node is healthy, using backups",
+                        layer.name()
+                    )
+                } else {
+                    format!(
+                        "{} failover deactivated: a primary node recovered",
+                        layer.name()
+                    )
+                };
+                let alert = format!("failover/{}", layer.name());
+                (NotificationEvent::Failover, *active, alert, summary)
+            }
+            EventKind::AllNodesDown { layer, active } => {
+                let summary = if *active {
+                    format!("All {} nodes are down", layer.name())
+                } else {
+                    format!("{} nodes are back up", layer.name())
+                };
+                let alert = format!("all_nodes_down/{}", layer.name());
+                (NotificationEvent::AllNodesDown, *active, alert, summary)
+            }
+            EventKind::ChainStall {
+                layer,
+                head,
+                stalled_ms,
+                active,
+            } => {
+                let seconds = stalled_ms / 1000;
+                let summary = if *active {
+                    format!(
+                        "{} chain head stalled at {head} for {seconds}s",
+                        layer.name()
+                    )
+                } else {
+                    format!(
+                        "{} chain head advancing again at {head} after {seconds}s",
+                        layer.name()
+                    )
+                };
+                let alert = format!("chain_stall/{}", layer.name());
+                (NotificationEvent::ChainStall, *active, alert, summary)
+            }
+            EventKind::WsReconnect { .. } => return None,
+        };
+
+        let mut fields = match serde_json::to_value(event) {
+            Ok(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        fields.insert("summary".to_string(), summary.clone().into());
+        let status = if firing { "firing" } else { "resolved" };
+        fields.insert("status".to_string(), status.into());
+        fields.insert("firing".to_string(), firing.into());
+
+        Some(Self {
+            trigger,
+            firing,
+            alert,
+            summary,
+            fields,
+        })
+    }
+
+    /// Alertmanager alert for the notification
+    fn alert(&self) -> Value {
+        let alertname = match self.trigger {
+            NotificationEvent::Failover => "VixyFailover",
+            NotificationEvent::AllNodesDown => "VixyAllNodesDown",
+            NotificationEvent::NodeHealth => "VixyNodeUnhealthy",
+            NotificationEvent::ChainStall => "VixyChainStall",
+        };
+        let mut labels = json!({ "alertname": alertname, "service": "vixy" });
+        for label in ["layer", "node", "tier"] {
+            if let Some(value) = self.fields.get(label) {
+                labels[label] = value.clone();
+            }
+        }
+
+        let mut alert = json!({
+            "labels": labels,
+            "annotations": { "summary": self.summary },
+        });
+        if !self.firing {
+            let timestamp_ms = self.fields["timestamp_ms"].as_u64().unwrap_or_default();
+            alert["endsAt"] = rfc3339(timestamp_ms).into();
+        }
+        alert
+    }
+}
+
+/// Delivery queue of a sink
+#[derive(Debug)]
+struct SinkQueue {
+    sink: NotificationSink,
+    queue: mpsc::Sender<String>,
+}
+
+impl SinkQueue {
+    /// Whether notifications of `trigger` go to this sink
+    fn wants(&self, trigger: NotificationEvent) -> bool {
+        self.sink.events.is_empty() || self.sink.events.contains(&trigger)
+    }
+
+    /// Queue a request body for delivery
+    fn send(&self, body: String) {
+        if self.queue.try_send(body).is_err() {
+            VixyMetrics::inc_notifications(&self.sink.name, "dropped");
+            warn!(sink = %self.sink.name, "Notification queue full, dropping notification");
+        }
+    }
+}
+
+/// Sends notifications for health events to the configured sinks
+#[derive(Debug)]
+pub struct Notifier {
+    config: config::Notifications,
+    sinks: Vec<SinkQueue>,
+    /// When each notification (alert and whether it fired) was last sent
+    last_sent: HashMap<(String, bool), Instant>,
+    /// When notifications were sent within the rate limit window
+    sent: VecDeque<Instant>,
+    /// Alerts that fired and are not resolved yet; re-sent to Alertmanager until they are
+    firing_alerts: HashMap<String, (NotificationEvent, Value)>,
+}
+
+impl Notifier {
+    /// Start delivery workers for the configured sinks
+    pub fn new(config: &config::Notifications) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .wrap_err("failed to build notification HTTP client")?;
+
+        let sinks = config
+            .sinks
+            .iter()
+            .map(|sink| {
+                let (queue, bodies) = mpsc::channel(SINK_QUEUE_SIZE);
+                tokio::spawn(deliver(
+                    client.clone(),
+                    sink.clone(),
+                    bodies,
+                    config.max_retries,
+                    Duration::from_millis(config.retry_backoff_ms),
+                ));
+                SinkQueue {
+                    sink: sink.clone(),
+                    queue,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            config: config.clone(),
+            sinks,
+            last_sent: HashMap::new(),
+            sent: VecDeque::new(),
+            firing_alerts: HashMap::new(),
+        })
+    }
+
+    /// Send notifications for events until the stream ends
+    pub async fn run(mut self, events: impl Stream<Item = Event>) {
+        let mut events = pin!(events);
+        let mut resend = tokio::time::interval_at(
+            tokio::time::Instant::now() + ALERTMANAGER_RESEND_INTERVAL,
+            ALERTMANAGER_RESEND_INTERVAL,
+        );
+
+        loop {
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(event) => self.notify(&event),
+                    None => break,
+                },
+                _ = resend.tick() => self.resend_firing_alerts(),
+            }
+        }
+    }
+
+    /// Send the notification for an event, unless it is a repeat or over the rate limit
+    fn notify(&mut self, event: &Event) {
+        let Some(notification) = Notification::from_event(event) else {
+            return;
+        };
+        if !self.config.events.contains(&notification.trigger) {
+            return;
+        }
+        // Nodes start unhealthy, so the first health check "recovers" every node; only
+        // resolve problems a notification was sent for
+        if !notification.firing && !self.firing_alerts.contains_key(&notification.alert) {
+            debug!(alert = %notification.alert, "Dropping resolved notification for an alert that never fired");
+            return;
+        }
+
+        let now = Instant::now();
+        let key = (notification.alert.clone(), notification.firing);
+        let window = Duration::from_millis(self.config.dedup_window_ms);
+        if self
+            .last_sent
+            .get(&key)
+            .is_some_and(|sent| now.duration_since(*sent) < window)
+        {
+            VixyMetrics::inc_notifications_suppressed("duplicate");
+            debug!(alert = %notification.alert, "Dropping repeated notification");
+            return;
+        }
+
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_LIMIT_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.config.max_per_minute > 0 && self.sent.len() >= self.config.max_per_minute as usize
+        {
+            VixyMetrics::inc_notifications_suppressed("rate_limited");
+            warn!(summary = %notification.summary, "Notification rate limit reached, dropping notification");
+            return;
+        }
+        self.sent.push_back(now);
+        self.last_sent.insert(key, now);
+        self.last_sent
+            .retain(|_, sent| now.duration_since(*sent) < window);
+
+        let alert = notification.alert();
+        if notification.firing {
+            self.firing_alerts.insert(
+                notification.alert.clone(),
+                (notification.trigger, alert.clone()),
+            );
+        } else {
+            self.firing_alerts.remove(&notification.alert);
+        }
+
+        for sink in self.sinks.iter().filter(|s| s.wants(notification.trigger)) {
+            let body = match sink.sink.kind {
+                SinkKind::Webhook => match &sink.sink.template {
+                    Some(template) => render(template, |name| {
+                        Ok(field_text(notification.fields.get(name)))
+                    })
+                    .unwrap_or_default(),
+                    None => Value::Object(notification.fields.clone()).to_string(),
+                },
+                SinkKind::Slack => json!({ "text": notification.summary }).to_string(),
+                SinkKind::Alertmanager => json!([alert]).to_string(),
+            };
+            sink.send(body);
+        }
+    }
+
+    /// Re-send alerts that are still firing to Alertmanager sinks
+    fn resend_firing_alerts(&self) {
+        for sink in &self.sinks {
+            if sink.sink.kind != SinkKind::Alertmanager {
+                continue;
+            }
+            let alerts: Vec<&Value> = self
+                .firing_alerts
+                .values()
+                .filter(|(trigger, _)| sink.wants(*trigger))
+                .map(|(_, alert)| alert)
+                .collect();
+            if !alerts.is_empty() {
+                sink.send(json!(alerts).to_string());
+            }
+        }
+    }
+}
+
+/// Deliver request bodies queued for a sink, retrying failed deliveries
+async fn deliver(
+    client: reqwest::Client,
+    sink: NotificationSink,
+    mut bodies: mpsc::Receiver<String>,
+    max_retries: u32,
+    backoff: Duration,
+) {
+    let url = match sink.kind {
+        SinkKind::Alertmanager => format!("{}/api/v2/alerts", sink.url.trim_end_matches('/')),
+        SinkKind::Webhook | SinkKind::Slack => sink.url.clone(),
+    };
+
+    while let Some(body) = bodies.recv().await {
+        let mut attempt = 0;
+        let result = loop {
+            let response = client
+                .post(&url)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+                .await;
+            let error = match response {
+                Ok(response) if response.status().is_success() => break "sent",
+                Ok(response) => {
+                    let status = response.status();
+                    // Other client errors will not go away by retrying
+                    if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+                    {
+                        warn!(sink = %sink.name, %status, "Notification rejected");
+                        break "failed";
+                    }
+                    format!("status {status}")
+                }
+                Err(e) => e.to_string(),
+            };
+
+            if attempt == max_retries {
+                warn!(sink = %sink.name, error = %error, "Failed to deliver notification");
+                break "failed";
+            }
+            debug!(sink = %sink.name, error = %error, attempt, "Retrying notification");
+            tokio::time::sleep(backoff * 2u32.saturating_pow(attempt)).await;
+            attempt += 1;
+        };
+        VixyMetrics::inc_notifications(&sink.name, result);
+    }
+}
+
+/// Render a template, replacing each `{{field}}` with `lookup(field)`
+fn render(
+    template: &str,
+    lookup: impl Fn(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..];
+        let end = placeholder
+            .find("}}")
+            .ok_or_else(|| "unclosed placeholder".to_string())?;
+        rendered.push_str(&lookup(placeholder[..end].trim())?);
+        rest = &placeholder[end + 2..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Text a field is rendered as: strings JSON-escaped without quotes, other values as
+/// JSON and missing fields as nothing
+fn field_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => {
+            let quoted = Value::String(s.clone()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Check that a webhook template only uses known fields and renders to JSON
+pub fn check_template(template: &str) -> Result<(), String> {
+    let rendered = render(template, |name| {
+        if NUMBER_FIELDS.contains(&name) {
+            Ok("1".to_string())
+        } else if BOOL_FIELDS.contains(&name) {
+            Ok("true".to_string())
+        } else if STRING_FIELDS.contains(&name) {
+            Ok("text".to_string())
+        } else {
+            Err(format!("unknown field '{name}'"))
+        }
+    })?;
+    serde_json::from_str::<Value>(&rendered)
+        .map(|_| ())
+        .map_err(|e| format!("does not render to JSON: {e}"))
+}
+
+/// Format a Unix timestamp in milliseconds as an RFC 3339 UTC date-time
+fn rfc3339(timestamp_ms: u64) -> String {
+    let seconds = timestamp_ms / 1000;
+    let (days, time) = ((seconds / 86400) as i64, seconds % 86400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        timestamp_ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{EventLog, Layer};
+    use wiremock::matchers::{body_json, body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn sink(name: &str, kind: SinkKind, url: String) -> NotificationSink {
+        NotificationSink {
+            name: name.to_string(),
+            kind,
+            url,
+            template: None,
+            events: Vec::new(),
+        }
+    }
+
+    fn notifications(sinks: Vec<NotificationSink>) -> config::Notifications {
+        config::Notifications {
+            enabled: true,
+            retry_backoff_ms: 10,
+            sinks,
+            ..Default::default()
+        }
+    }
+
+    fn failover(active: bool) -> EventKind {
+        EventKind::Failover {
+            layer: Layer::El,
+            active,
+        }
+    }
+
+    /// Feed events to a notifier and wait for deliveries
+    async fn notify_all(config: config::Notifications, events: Vec<EventKind>) {
+        let log = EventLog::new(10);
+        let stream = log.stream(None);
+        for event in events {
+            log.record(event);
+        }
+        drop(log);
+
+        let notifier = Notifier::new(&config).expect("Should build notifier");
+        notifier.run(stream).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    #[tokio::test]
+    async fn test_webhook_and_slack_notifications() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_json(json!({
+                "text": "EL node geth-1 (primary) is unhealthy: timeout (lag 2, block 100)",
+                "node": "geth-1",
+                "lag": 2,
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/slack"))
+            .and(body_json(json!({
+                "text": "EL failover activated: no primary node is healthy, using backups"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut hook = sink("hook", SinkKind::Webhook, format!("{}/hook", server.uri()));
+        hook.template =
+            Some(r#"{"text": "{{summary}}", "node": "{{node}}", "lag": {{lag}}}"#.to_string());
+        hook.events = vec![NotificationEvent::NodeHealth];
+        let mut slack = sink("slack", SinkKind::Slack, format!("{}/slack", server.uri()));
+        slack.events = vec![NotificationEvent::Failover];
+
+        notify_all(
+            notifications(vec![hook, slack]),
+            vec![
+                EventKind::NodeHealth {
+                    layer: Layer::El,
+                    node: "geth-1".to_string(),
+                    tier: "primary",
+                    old_state: HealthState::Healthy,
+                    new_state: HealthState::Unhealthy,
+                    reason: "timeout",
+                    lag: 2,
+                    block: 100,
+                },
+                failover(true),
+                // Not notified
+                EventKind::WsReconnect {
+                    from_node: "geth-1".to_string(),
+                    to_node: "geth-2".to_string(),
+                },
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_alertmanager_alerts_fire_and_resolve() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v2/alerts"))
+            .and(body_partial_json(json!([{
+                "labels": { "alertname": "VixyFailover", "layer": "el", "service": "vixy" },
+            }])))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        notify_all(
+            notifications(vec![sink(
+                "alertmanager",
+                SinkKind::Alertmanager,
+                server.uri(),
+            )]),
+            vec![failover(true), failover(false)],
+        )
+        .await;
+
+        let requests = server.received_requests().await.unwrap();
+        let firing: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(firing[0].get("endsAt").is_none());
+        let resolved: Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert!(resolved[0]["endsAt"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn test_repeats_and_excess_notifications_are_dropped() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut config = notifications(vec![sink("hook", SinkKind::Webhook, server.uri())]);
+        config.max_per_minute = 2;
+        let stall = |active| EventKind::ChainStall {
+            layer: Layer::Cl,
+            head: 10,
+            stalled_ms: 60000,
+            active,
+        };
+
+        // The repeated failover is a duplicate; the stall is over the rate limit
+        notify_all(
+            config,
+            vec![failover(true), failover(true), failover(false), stall(true)],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_recovery_without_firing_alert_is_not_notified() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        notify_all(
+            notifications(vec![
+                sink("hook", SinkKind::Webhook, server.uri()),
+                sink("alertmanager", SinkKind::Alertmanager, server.uri()),
+            ]),
+            vec![
+                // A node becoming healthy in the first health check after startup
+                EventKind::NodeHealth {
+                    layer: Layer::El,
+                    node: "geth-1".to_string(),
+                    tier: "primary",
+                    old_state: HealthState::Unhealthy,
+                    new_state: HealthState::Healthy,
+                    reason: "recovered",
+                    lag: 0,
+                    block: 100,
+                },
+                failover(false),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_startup_health_check_sends_no_notifications() {
+        let node = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "result": "0x3e8",
+                "id": 1
+            })))
+            .mount(&node)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/node/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&node)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/head"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "header": { "message": { "slot": "12345" } } }
+            })))
+            .mount(&node)
+            .await;
+        let hook = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&hook)
+            .await;
+
+        let config = crate::config::Config::parse(&format!(
+            r#"
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "{node}"
+ws_url = "{node}"
+
+[[cl]]
+name = "lighthouse-1"
+url = "{node}"
+"#,
+            node = node.uri()
+        ))
+        .expect("Test config should parse");
+        let state = std::sync::Arc::new(crate::state::AppState::new(&config));
+
+        // Subscribed before the initial health check, as at startup
+        let notifier = Notifier::new(&notifications(vec![sink(
+            "hook",
+            SinkKind::Webhook,
+            hook.uri(),
+        )]))
+        .expect("Should build notifier");
+        let notifying = tokio::spawn(notifier.run(state.events.stream(None)));
+        crate::monitor::run_health_check_cycle(&state).await;
+        assert!(state.el_nodes.read().await[0].is_healthy);
+        assert!(state.cl_nodes.read().await[0].is_healthy);
+
+        // Dropping the state ends the event stream
+        drop(state);
+        notifying.await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        notify_all(
+            notifications(vec![sink("hook", SinkKind::Webhook, server.uri())]),
+            vec![failover(true)],
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_check_template() {
+        assert!(check_template(r#"{"text": "{{ summary }}", "lag": {{lag}}}"#).is_ok());
+        assert!(check_template(r#"{"text": "{{summary}"#).is_err());
+        assert!(check_template(r#"{"text": "{{unknown}}"}"#).is_err());
+        assert!(check_template(r#"{"text": {{summary}}}"#).is_err());
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(rfc3339(1_760_745_600_123), "2025-10-18T00:00:00.123Z");
+        assert_eq!(rfc3339(951_827_696_000), "2000-02-29T12:34:56.000Z");
+    }
+}
@@ -0,0 +1,368 @@
+//! Persistence of node health state across restarts
+//!
+//! The last known state of every node is saved to a local file periodically and on
+//! shutdown, and restored at startup unless it is older than the configured bound, so a
+//! restarted proxy keeps routing to the nodes it knew were healthy instead of starting
+//! with every node unhealthy.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::monitor;
+use crate::state::{AppState, ClNodeState, ElNodeState};
+
+/// Node state as saved to the state file
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    /// When the state was saved, in milliseconds since the Unix epoch
+    saved_at_ms: u64,
+    el_chain_head: u64,
+    cl_chain_head: u64,
+    el_nodes: Vec<SavedElNode>,
+    cl_nodes: Vec<SavedClNode>,
+}
+
+/// Saved state of an EL node
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedElNode {
+    name: String,
+    http_url: String,
+    block_number: u64,
+    check_ok: bool,
+    is_healthy: bool,
+    lag: u64,
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+    check_latency_ms: u64,
+    flaps: u32,
+}
+
+impl SavedElNode {
+    fn from_state(node: &ElNodeState) -> Self {
+        Self {
+            name: node.name.clone(),
+            http_url: node.http_url.clone(),
+            block_number: node.block_number,
+            check_ok: node.check_ok,
+            is_healthy: node.is_healthy,
+            lag: node.lag,
+            consecutive_failures: node.consecutive_failures,
+            consecutive_successes: node.consecutive_successes,
+            check_latency_ms: node.check_latency_ms,
+            flaps: node.flaps,
+        }
+    }
+
+    /// Restore the saved state into `node`
+    fn apply(&self, node: &mut ElNodeState) {
+        node.block_number = self.block_number;
+        node.check_ok = self.check_ok;
+        node.is_healthy = self.is_healthy;
+        node.lag = self.lag;
+        node.consecutive_failures = self.consecutive_failures;
+        node.consecutive_successes = self.consecutive_successes;
+        node.check_latency_ms = self.check_latency_ms;
+        node.flaps = self.flaps;
+    }
+}
+
+/// Saved state of a CL node
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedClNode {
+    name: String,
+    url: String,
+    slot: u64,
+    health_ok: bool,
+    is_healthy: bool,
+    lag: u64,
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+    check_latency_ms: u64,
+    flaps: u32,
+}
+
+impl SavedClNode {
+    fn from_state(node: &ClNodeState) -> Self {
+        Self {
+            name: node.name.clone(),
+            url: node.url.clone(),
+            slot: node.slot,
+            health_ok: node.health_ok,
+            is_healthy: node.is_healthy,
+            lag: node.lag,
+            consecutive_failures: node.consecutive_failures,
+            consecutive_successes: node.consecutive_successes,
+            check_latency_ms: node.check_latency_ms,
+            flaps: node.flaps,
+        }
+    }
+
+    /// Restore the saved state into `node`
+    fn apply(&self, node: &mut ClNodeState) {
+        node.slot = self.slot;
+        node.health_ok = self.health_ok;
+        node.is_healthy = self.is_healthy;
+        node.lag = self.lag;
+        node.consecutive_failures = self.consecutive_failures;
+        node.consecutive_successes = self.consecutive_successes;
+        node.check_latency_ms = self.check_latency_ms;
+        node.flaps = self.flaps;
+    }
+}
+
+/// Milliseconds since the Unix epoch
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Save the state of all nodes to `path`
+pub async fn save(state: &AppState, path: &Path) -> Result<()> {
+    let snapshot = Snapshot {
+        saved_at_ms: now_ms(),
+        el_chain_head: state.el_chain_head.load(Ordering::SeqCst),
+        cl_chain_head: state.cl_chain_head.load(Ordering::SeqCst),
+        el_nodes: state
+            .el_nodes
+            .read()
+            .await
+            .iter()
+            .map(SavedElNode::from_state)
+            .collect(),
+        cl_nodes: state
+            .cl_nodes
+            .read()
+            .await
+            .iter()
+            .map(SavedClNode::from_state)
+            .collect(),
+    };
+    let contents = serde_json::to_vec_pretty(&snapshot).wrap_err("failed to encode node state")?;
+
+    if let Some(directory) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(directory)
+            .await
+            .wrap_err_with(|| format!("failed to create directory for {}", path.display()))?;
+    }
+    // Replace the file in one step so a crash never leaves a truncated state file
+    let temporary = path.with_extension("tmp");
+    tokio::fs::write(&temporary, contents)
+        .await
+        .wrap_err_with(|| format!("failed to write {}", temporary.display()))?;
+    tokio::fs::rename(&temporary, path)
+        .await
+        .wrap_err_with(|| format!("failed to replace {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Restore node state saved to `path`, unless it is older than `max_age`
+///
+/// Nodes are matched by name and URL, so nodes added or changed since the state was
+/// saved start unhealthy as usual. The failover flags are set from the restored node
+/// health. Returns the number of nodes restored; a missing file restores nothing.
+pub async fn restore(state: &AppState, path: &Path, max_age: Duration) -> Result<usize> {
+    let contents = match tokio::fs::read(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+    let snapshot: Snapshot = serde_json::from_slice(&contents)
+        .wrap_err_with(|| format!("failed to parse {}", path.display()))?;
+
+    let age_ms = now_ms().saturating_sub(snapshot.saved_at_ms);
+    if Duration::from_millis(age_ms) > max_age {
+        info!(age_ms, "Saved node state is too old, not restoring it");
+        return Ok(0);
+    }
+
+    let mut restored = 0;
+    {
+        let mut el_nodes = state.el_nodes.write().await;
+        for saved in &snapshot.el_nodes {
+            if let Some(node) = el_nodes
+                .iter_mut()
+                .find(|n| n.name == saved.name && n.http_url == saved.http_url)
+            {
+                saved.apply(node);
+                restored += 1;
+            }
+        }
+    }
+    {
+        let mut cl_nodes = state.cl_nodes.write().await;
+        for saved in &snapshot.cl_nodes {
+            if let Some(node) = cl_nodes
+                .iter_mut()
+                .find(|n| n.name == saved.name && n.url == saved.url)
+            {
+                saved.apply(node);
+                restored += 1;
+            }
+        }
+    }
+    state
+        .el_chain_head
+        .store(snapshot.el_chain_head, Ordering::SeqCst);
+    state
+        .cl_chain_head
+        .store(snapshot.cl_chain_head, Ordering::SeqCst);
+
+    // Without this, requests would find no eligible node until the first health check
+    // if the saved primaries were unhealthy
+    if restored > 0 {
+        let any_el_primary = state
+            .el_nodes
+            .read()
+            .await
+            .iter()
+            .any(|n| n.is_primary && n.is_healthy);
+        monitor::update_failover_flag(state, any_el_primary);
+        let any_cl_primary = state
+            .cl_nodes
+            .read()
+            .await
+            .iter()
+            .any(|n| n.is_primary && n.is_healthy);
+        monitor::update_cl_failover_flag(state, any_cl_primary);
+    }
+
+    Ok(restored)
+}
+
+/// Save node state to `path` every `interval`
+pub async fn run_periodic_save(state: Arc<AppState>, path: PathBuf, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+
+    loop {
+        ticker.tick().await;
+        match save(&state, &path).await {
+            Ok(()) => debug!(file = %path.display(), "Saved node state"),
+            Err(e) => warn!(error = format!("{e:#}"), "Failed to save node state"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn test_state() -> AppState {
+        let config = Config::parse(
+            r#"
+[el]
+[[el.primary]]
+name = "geth-1"
+http_url = "http://localhost:8545"
+ws_url = "ws://localhost:8546"
+
+[[el.primary]]
+name = "geth-2"
+http_url = "http://localhost:8547"
+ws_url = "ws://localhost:8548"
+
+[[cl]]
+name = "lighthouse-1"
+url = "http://localhost:5052"
+"#,
+        )
+        .expect("Test config should parse");
+        AppState::new(&config)
+    }
+
+    fn state_file(test: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("vixy-state-{}-{test}", std::process::id()))
+            .join("state.json")
+    }
+
+    #[tokio::test]
+    async fn test_node_state_is_restored() {
+        let path = state_file("restore");
+        let saved = test_state();
+        {
+            let mut el_nodes = saved.el_nodes.write().await;
+            el_nodes[0].block_number = 1000;
+            el_nodes[0].check_ok = true;
+            el_nodes[0].is_healthy = true;
+            el_nodes[0].check_latency_ms = 12;
+            el_nodes[0].flaps = 3;
+            let mut cl_nodes = saved.cl_nodes.write().await;
+            cl_nodes[0].slot = 5000;
+            cl_nodes[0].is_healthy = true;
+        }
+        saved.el_chain_head.store(1000, Ordering::SeqCst);
+        save(&saved, &path).await.expect("Should save state");
+
+        let restored = test_state();
+        // geth-2 has moved since the state was saved
+        restored.el_nodes.write().await[1].http_url = "http://10.0.0.2:8545".to_string();
+        let count = restore(&restored, &path, Duration::from_secs(60))
+            .await
+            .expect("Should restore state");
+        assert_eq!(count, 2);
+
+        let el_nodes = restored.el_nodes.read().await;
+        assert_eq!(el_nodes[0].block_number, 1000);
+        assert!(el_nodes[0].is_healthy);
+        assert_eq!(el_nodes[0].check_latency_ms, 12);
+        assert_eq!(el_nodes[0].flaps, 3);
+        assert!(!el_nodes[1].is_healthy);
+        let cl_nodes = restored.cl_nodes.read().await;
+        assert_eq!(cl_nodes[0].slot, 5000);
+        assert!(cl_nodes[0].is_healthy);
+        assert_eq!(restored.el_chain_head.load(Ordering::SeqCst), 1000);
+        assert!(!restored.el_failover_active.load(Ordering::SeqCst));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failover_follows_restored_primaries() {
+        let path = state_file("failover");
+        // Every primary was unhealthy when the state was saved
+        save(&test_state(), &path).await.expect("Should save state");
+
+        let restored = test_state();
+        restore(&restored, &path, Duration::from_secs(60))
+            .await
+            .expect("Should restore state");
+
+        assert!(restored.el_failover_active.load(Ordering::SeqCst));
+        assert!(restored.cl_failover_active.load(Ordering::SeqCst));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stale_or_missing_state_is_not_restored() {
+        let path = state_file("stale");
+        let state = test_state();
+
+        let count = restore(&state, &path, Duration::from_secs(60))
+            .await
+            .expect("Missing state file should restore nothing");
+        assert_eq!(count, 0);
+
+        state.el_nodes.write().await[0].is_healthy = true;
+        save(&state, &path).await.expect("Should save state");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let restored = test_state();
+        let count = restore(&restored, &path, Duration::from_millis(10))
+            .await
+            .expect("Stale state should restore nothing");
+        assert_eq!(count, 0);
+        assert!(!restored.el_nodes.read().await[0].is_healthy);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,384 @@
+//! In-memory cache for EL JSON-RPC responses
+//!
+//! Results are keyed by method and params. Immutable data (hash-addressed lookups and
+//! blocks deep enough behind the head to be final) is kept until evicted for memory;
+//! head-dependent data is only served until the EL chain head advances.
+
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::proxy::jsonrpc::{self, BlockRef};
+
+/// Methods whose result never changes
+const IMMUTABLE_METHODS: &[&str] = &[
+    "eth_chainId",
+    "net_version",
+    "eth_getBlockByHash",
+    "eth_getBlockTransactionCountByHash",
+    "eth_getTransactionByBlockHashAndIndex",
+    "eth_getUncleByBlockHashAndIndex",
+    "eth_getUncleCountByBlockHash",
+];
+
+/// Methods whose result only depends on the current chain head
+const HEAD_METHODS: &[&str] = &[
+    "eth_blockNumber",
+    "eth_gasPrice",
+    "eth_maxPriorityFeePerGas",
+    "eth_blobBaseFee",
+];
+
+/// Transaction lookups that become immutable once the containing block is final
+const TRANSACTION_METHODS: &[&str] = &["eth_getTransactionByHash", "eth_getTransactionReceipt"];
+
+/// Block-addressed methods, cached according to the block they refer to
+const BLOCK_METHODS: &[&str] = &[
+    "eth_getBlockByNumber",
+    "eth_getBlockReceipts",
+    "eth_getBlockTransactionCountByNumber",
+    "eth_getTransactionByBlockNumberAndIndex",
+    "eth_getBalance",
+    "eth_getCode",
+    "eth_getTransactionCount",
+    "eth_getStorageAt",
+    "eth_getProof",
+    "eth_call",
+    "eth_feeHistory",
+    "eth_getLogs",
+];
+
+/// How long a cached result stays valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lifetime {
+    /// Valid until evicted
+    Forever,
+    /// Valid only while the chain head stays the same
+    CurrentBlock,
+    /// Decided from the `blockNumber` of the result (transactions and receipts)
+    ResultBlock,
+}
+
+/// A cacheable JSON-RPC call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheKey {
+    key: String,
+    lifetime: Lifetime,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    result: Value,
+    /// Chain head the entry is valid for, or None if it never expires
+    head: Option<u64>,
+    size: usize,
+    seq: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    entries: HashMap<String, CacheEntry>,
+    /// Insertion order used for eviction: (seq, key)
+    order: VecDeque<(u64, String)>,
+    bytes: usize,
+    head: u64,
+    next_seq: u64,
+}
+
+impl CacheInner {
+    /// Drop head-dependent entries once the chain head has advanced
+    fn advance_head(&mut self, head: u64) {
+        if head <= self.head {
+            return;
+        }
+        self.head = head;
+
+        let mut freed = 0;
+        self.entries.retain(|_, entry| {
+            let keep = entry.head.is_none();
+            if !keep {
+                freed += entry.size;
+            }
+            keep
+        });
+        self.bytes -= freed;
+
+        let entries = &self.entries;
+        self.order
+            .retain(|(seq, key)| entries.get(key).is_some_and(|e| e.seq == *seq));
+    }
+
+    /// Evict the oldest entries until `size` more bytes fit within `max_bytes`
+    fn make_room(&mut self, size: usize, max_bytes: usize) {
+        while self.bytes + size > max_bytes {
+            let Some((seq, key)) = self.order.pop_front() else {
+                break;
+            };
+            if self.entries.get(&key).is_some_and(|e| e.seq == seq)
+                && let Some(entry) = self.entries.remove(&key)
+            {
+                self.bytes -= entry.size;
+            }
+        }
+    }
+}
+
+/// Bounded in-memory cache of EL JSON-RPC results
+#[derive(Debug)]
+pub struct ResponseCache {
+    max_bytes: usize,
+    finality_depth: u64,
+    inner: Mutex<CacheInner>,
+}
+
+impl ResponseCache {
+    /// Create a cache from its configuration
+    pub fn new(config: &crate::config::Cache) -> Self {
+        Self {
+            max_bytes: config.max_memory_mb * 1024 * 1024,
+            finality_depth: config.finality_depth,
+            inner: Mutex::new(CacheInner::default()),
+        }
+    }
+
+    /// Check whether a block is far enough behind the head to be treated as final
+    fn is_final(&self, block: u64, head: u64) -> bool {
+        block.saturating_add(self.finality_depth) <= head
+    }
+
+    /// Get the cache key for a call, or None if the call must not be cached
+    pub fn key_for(&self, call: &Value, head: u64) -> Option<CacheKey> {
+        let method = jsonrpc::method_of(call)?;
+
+        let lifetime = if IMMUTABLE_METHODS.contains(&method) {
+            Lifetime::Forever
+        } else if HEAD_METHODS.contains(&method) {
+            Lifetime::CurrentBlock
+        } else if TRANSACTION_METHODS.contains(&method) {
+            Lifetime::ResultBlock
+        } else if BLOCK_METHODS.contains(&method) {
+            match jsonrpc::block_reference(call)? {
+                BlockRef::Hash => Lifetime::Forever,
+                BlockRef::Number(n) if self.is_final(n, head) => Lifetime::Forever,
+                BlockRef::Number(_) => Lifetime::CurrentBlock,
+                BlockRef::Tag(tag) if tag == "pending" => return None,
+                BlockRef::Tag(_) => Lifetime::CurrentBlock,
+            }
+        } else {
+            return None;
+        };
+
+        let params = call.get("params").unwrap_or(&Value::Null);
+        Some(CacheKey {
+            key: format!("{method}:{params}"),
+            lifetime,
+        })
+    }
+
+    /// Look up a cached result
+    pub fn get(&self, key: &CacheKey, head: u64) -> Option<Value> {
+        let mut inner = self.inner.lock().expect("cache lock poisoned");
+        inner.advance_head(head);
+
+        let entry = inner.entries.get(&key.key)?;
+        entry
+            .head
+            .is_none_or(|h| h == inner.head)
+            .then(|| entry.result.clone())
+    }
+
+    /// Store the result of a call that was answered at the given chain head
+    ///
+    /// Null results are not cached, since the data may simply not exist yet.
+    pub fn insert(&self, key: CacheKey, result: Value, head: u64) {
+        if result.is_null() {
+            return;
+        }
+
+        let expires = match key.lifetime {
+            Lifetime::Forever => None,
+            Lifetime::CurrentBlock => Some(head),
+            Lifetime::ResultBlock => {
+                // Pending transactions have no block yet and may still change
+                let Some(block) = result
+                    .get("blockNumber")
+                    .and_then(|b| b.as_str())
+                    .and_then(|b| b.strip_prefix("0x"))
+                    .and_then(|b| u64::from_str_radix(b, 16).ok())
+                else {
+                    return;
+                };
+                (!self.is_final(block, head)).then_some(head)
+            }
+        };
+
+        let size = key.key.len() + result.to_string().len();
+        if size > self.max_bytes {
+            return;
+        }
+
+        let mut inner = self.inner.lock().expect("cache lock poisoned");
+        inner.advance_head(head);
+        // The head moved on while the request was in flight
+        if expires.is_some_and(|h| h < inner.head) {
+            return;
+        }
+
+        if let Some(old) = inner.entries.remove(&key.key) {
+            inner.bytes -= old.size;
+        }
+        inner.make_room(size, self.max_bytes);
+
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        inner.order.push_back((seq, key.key.clone()));
+        inner.bytes += size;
+        inner.entries.insert(
+            key.key,
+            CacheEntry {
+                result,
+                head: expires,
+                size,
+                seq,
+            },
+        );
+    }
+
+    /// Number of cached entries and their approximate size in bytes
+    pub fn usage(&self) -> (usize, usize) {
+        let inner = self.inner.lock().expect("cache lock poisoned");
+        (inner.entries.len(), inner.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn make_cache(max_memory_mb: usize) -> ResponseCache {
+        ResponseCache::new(&crate::config::Cache {
+            enabled: true,
+            max_memory_mb,
+            finality_depth: 64,
+        })
+    }
+
+    fn call(method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params})
+    }
+
+    #[test]
+    fn test_immutable_result_survives_new_blocks() {
+        let cache = make_cache(1);
+        let key = cache.key_for(&call("eth_chainId", json!([])), 100).unwrap();
+
+        cache.insert(key.clone(), json!("0x1"), 100);
+
+        assert_eq!(cache.get(&key, 100), Some(json!("0x1")));
+        assert_eq!(cache.get(&key, 101), Some(json!("0x1")));
+    }
+
+    #[test]
+    fn test_head_dependent_result_invalidated_on_new_block() {
+        let cache = make_cache(1);
+        let key = cache
+            .key_for(&call("eth_blockNumber", json!([])), 100)
+            .unwrap();
+
+        cache.insert(key.clone(), json!("0x64"), 100);
+        assert_eq!(cache.get(&key, 100), Some(json!("0x64")));
+
+        assert_eq!(cache.get(&key, 101), None);
+        assert_eq!(cache.usage().0, 0);
+    }
+
+    #[test]
+    fn test_block_lifetime_depends_on_finality() {
+        let cache = make_cache(1);
+
+        // Deep enough behind the head to be final
+        let final_call = call("eth_getBalance", json!(["0xabc", "0x10"]));
+        let key = cache.key_for(&final_call, 1000).unwrap();
+        cache.insert(key.clone(), json!("0x1"), 1000);
+        assert_eq!(cache.get(&key, 1001), Some(json!("0x1")));
+
+        // Recent block can still be reorged
+        let recent_call = call("eth_getBalance", json!(["0xabc", "0x3e8"]));
+        let key = cache.key_for(&recent_call, 1000).unwrap();
+        cache.insert(key.clone(), json!("0x2"), 1000);
+        assert_eq!(cache.get(&key, 1001), None);
+    }
+
+    #[test]
+    fn test_uncacheable_calls() {
+        let cache = make_cache(1);
+
+        assert!(
+            cache
+                .key_for(&call("eth_sendRawTransaction", json!(["0x00"])), 100)
+                .is_none()
+        );
+        assert!(
+            cache
+                .key_for(&call("eth_getBalance", json!(["0xabc", "pending"])), 100)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_receipt_cached_forever_only_when_final() {
+        let cache = make_cache(1);
+        let key = cache
+            .key_for(&call("eth_getTransactionReceipt", json!(["0x01"])), 1000)
+            .unwrap();
+
+        cache.insert(key.clone(), json!({"blockNumber": "0x3e0"}), 1000);
+        assert_eq!(cache.get(&key, 1001), None);
+
+        cache.insert(key.clone(), json!({"blockNumber": "0x10"}), 1001);
+        assert!(cache.get(&key, 1002).is_some());
+    }
+
+    #[test]
+    fn test_null_and_pending_results_not_cached() {
+        let cache = make_cache(1);
+        let key = cache
+            .key_for(&call("eth_getTransactionByHash", json!(["0x01"])), 100)
+            .unwrap();
+
+        cache.insert(key.clone(), Value::Null, 100);
+        cache.insert(key.clone(), json!({"blockNumber": null}), 100);
+
+        assert_eq!(cache.get(&key, 100), None);
+    }
+
+    #[test]
+    fn test_oldest_entries_evicted_when_full() {
+        let cache = make_cache(1);
+        let big = json!("x".repeat(400 * 1024));
+
+        for i in 0..3 {
+            let hash = format!("0x{i:064x}");
+            let key = cache
+                .key_for(&call("eth_getBlockByHash", json!([hash, false])), 100)
+                .unwrap();
+            cache.insert(key, big.clone(), 100);
+        }
+
+        let (entries, bytes) = cache.usage();
+        assert_eq!(entries, 2);
+        assert!(bytes <= 1024 * 1024);
+
+        let first = cache
+            .key_for(
+                &call(
+                    "eth_getBlockByHash",
+                    json!([format!("0x{:064x}", 0), false]),
+                ),
+                100,
+            )
+            .unwrap();
+        assert_eq!(cache.get(&first, 100), None);
+    }
+}
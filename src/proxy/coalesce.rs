@@ -0,0 +1,176 @@
+//! Single-flight coalescing of identical in-flight upstream requests
+//!
+//! While a request is in flight, identical requests wait for its response instead of
+//! being forwarded again. The shared upstream call keeps running as long as any
+//! waiter is still interested, even if the request that started it goes away.
+
+use axum::body::{Body, Bytes};
+use axum::http::{Extensions, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures_util::FutureExt;
+use futures_util::future::{BoxFuture, Shared};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// A fully buffered upstream response that can be handed to several waiters
+#[derive(Debug, Clone)]
+pub struct BufferedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub extensions: Extensions,
+    pub body: Bytes,
+}
+
+impl BufferedResponse {
+    /// Buffer the body of a response
+    async fn from_response(response: Response) -> Self {
+        let (parts, body) = response.into_parts();
+        match axum::body::to_bytes(body, usize::MAX).await {
+            Ok(body) => Self {
+                status: parts.status,
+                headers: parts.headers,
+                extensions: parts.extensions,
+                body,
+            },
+            Err(_) => Self {
+                status: StatusCode::BAD_GATEWAY,
+                headers: HeaderMap::new(),
+                extensions: Extensions::new(),
+                body: Bytes::from_static(b"Failed to read upstream response"),
+            },
+        }
+    }
+}
+
+impl IntoResponse for BufferedResponse {
+    fn into_response(self) -> Response {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        *response.extensions_mut() = self.extensions;
+        response
+    }
+}
+
+type SharedResponse = Shared<BoxFuture<'static, BufferedResponse>>;
+
+/// Tracks in-flight requests by key so identical requests can share one upstream call
+#[derive(Default)]
+pub struct Coalescer {
+    inflight: Arc<Mutex<HashMap<String, SharedResponse>>>,
+}
+
+impl std::fmt::Debug for Coalescer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inflight = self.inflight.lock().map(|m| m.len()).unwrap_or_default();
+        f.debug_struct("Coalescer")
+            .field("inflight", &inflight)
+            .finish()
+    }
+}
+
+impl Coalescer {
+    /// Create an empty coalescer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `request` unless an identical request is already in flight
+    ///
+    /// Returns the response and whether it was shared from another request.
+    pub async fn run<F>(&self, key: String, request: F) -> (BufferedResponse, bool)
+    where
+        F: Future<Output = Response> + Send + 'static,
+    {
+        let (shared, coalesced) = {
+            let mut inflight = self.inflight.lock().expect("coalescer lock poisoned");
+            match inflight.get(&key) {
+                Some(existing) => (existing.clone(), true),
+                None => {
+                    let map = self.inflight.clone();
+                    let map_key = key.clone();
+                    let shared = async move {
+                        let response = BufferedResponse::from_response(request.await).await;
+                        // Later requests must go upstream again
+                        map.lock()
+                            .expect("coalescer lock poisoned")
+                            .remove(&map_key);
+                        response
+                    }
+                    .boxed()
+                    .shared();
+                    inflight.insert(key, shared.clone());
+                    (shared, false)
+                }
+            }
+        };
+
+        (shared.await, coalesced)
+    }
+
+    /// Number of distinct requests currently in flight
+    pub fn inflight(&self) -> usize {
+        self.inflight.lock().expect("coalescer lock poisoned").len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    async fn slow_request(calls: Arc<AtomicUsize>) -> Response {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        (StatusCode::OK, "0x1").into_response()
+    }
+
+    #[tokio::test]
+    async fn test_identical_requests_share_one_call() {
+        let coalescer = Arc::new(Coalescer::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (first, second) = tokio::join!(
+            coalescer.run("key".to_string(), slow_request(calls.clone())),
+            coalescer.run("key".to_string(), slow_request(calls.clone())),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(!first.1);
+        assert!(second.1);
+        assert_eq!(second.0.body, Bytes::from_static(b"0x1"));
+        assert_eq!(coalescer.inflight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_different_keys_are_not_coalesced() {
+        let coalescer = Coalescer::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (first, second) = tokio::join!(
+            coalescer.run("a".to_string(), slow_request(calls.clone())),
+            coalescer.run("b".to_string(), slow_request(calls.clone())),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(!first.1 && !second.1);
+    }
+
+    #[tokio::test]
+    async fn test_completed_request_is_not_reused() {
+        let coalescer = Coalescer::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        coalescer
+            .run("key".to_string(), slow_request(calls.clone()))
+            .await;
+        let (_, coalesced) = coalescer
+            .run("key".to_string(), slow_request(calls.clone()))
+            .await;
+
+        assert!(!coalesced);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,159 @@
+//! Hedged EL requests
+//!
+//! A hedged call is sent to a second node if the first hasn't answered within a
+//! delay derived from recent latencies, and whichever node answers first wins.
+//! The delay is the configured percentile of the last requests' latencies, so only
+//! the slowest few percent of calls are hedged.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Hedging;
+
+/// Number of recent latencies the hedge delay is computed from
+const SAMPLE_SIZE: usize = 1000;
+
+/// Minimum number of latencies needed before the percentile is used
+const MIN_SAMPLES: usize = 20;
+
+/// Hedging policy and the latencies it is based on
+#[derive(Debug)]
+pub struct Hedger {
+    config: Hedging,
+    /// Recent latencies of hedgeable calls, oldest first
+    latencies: Mutex<VecDeque<Duration>>,
+}
+
+impl Hedger {
+    /// Create a hedger from the hedging settings
+    pub fn new(config: &Hedging) -> Self {
+        Self {
+            config: config.clone(),
+            latencies: Mutex::new(VecDeque::with_capacity(SAMPLE_SIZE)),
+        }
+    }
+
+    /// Check whether calls to a JSON-RPC method are hedged
+    pub fn applies_to(&self, method: &str) -> bool {
+        self.config.matches(method)
+    }
+
+    /// How long to wait for the first node before sending a hedge
+    ///
+    /// Until enough latencies have been recorded, the maximum delay is used.
+    pub fn delay(&self) -> Duration {
+        let min = Duration::from_millis(self.config.min_delay_ms);
+        let max = Duration::from_millis(self.config.max_delay_ms);
+
+        let latencies = self.latencies.lock().expect("hedger lock poisoned");
+        if latencies.len() < MIN_SAMPLES {
+            return max;
+        }
+
+        let mut sorted: Vec<Duration> = latencies.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (self.config.percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1].clamp(min, max)
+    }
+
+    /// Run the request to the first node of a hedgeable call, recording how long it took
+    ///
+    /// Nothing is recorded if the request is dropped before it answers, e.g. because
+    /// a hedge won.
+    pub async fn timed<F: Future>(&self, request: F) -> F::Output {
+        let start = Instant::now();
+        let output = request.await;
+        self.record(start.elapsed());
+        output
+    }
+
+    /// Record how long the first node took to answer a hedgeable call
+    pub fn record(&self, latency: Duration) {
+        let mut latencies = self.latencies.lock().expect("hedger lock poisoned");
+        if latencies.len() == SAMPLE_SIZE {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hedger() -> Hedger {
+        Hedger::new(&Hedging {
+            enabled: true,
+            methods: vec!["eth_call".to_string(), "eth_get*".to_string()],
+            percentile: 90.0,
+            min_delay_ms: 5,
+            max_delay_ms: 500,
+        })
+    }
+
+    #[test]
+    fn test_hedger_applies_to_configured_idempotent_methods() {
+        let hedger = hedger();
+        assert!(hedger.applies_to("eth_call"));
+        assert!(hedger.applies_to("eth_getLogs"));
+        assert!(!hedger.applies_to("eth_blockNumber"));
+
+        let hedger = Hedger::new(&Hedging {
+            methods: vec!["eth_*".to_string()],
+            ..Default::default()
+        });
+        assert!(!hedger.applies_to("eth_sendRawTransaction"));
+    }
+
+    #[test]
+    fn test_hedger_uses_max_delay_without_samples() {
+        let hedger = hedger();
+        assert_eq!(hedger.delay(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_hedger_delay_follows_percentile() {
+        let hedger = hedger();
+        for ms in 1..=100 {
+            hedger.record(Duration::from_millis(ms));
+        }
+        assert_eq!(hedger.delay(), Duration::from_millis(90));
+
+        // Clamped to the configured bounds
+        let hedger = Hedger::new(&Hedging {
+            percentile: 50.0,
+            min_delay_ms: 100,
+            ..hedger.config.clone()
+        });
+        for _ in 0..MIN_SAMPLES {
+            hedger.record(Duration::from_millis(1));
+        }
+        assert_eq!(hedger.delay(), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_hedger_times_first_request() {
+        let hedger = hedger();
+        hedger
+            .timed(tokio::time::sleep(Duration::from_millis(20)))
+            .await;
+
+        let latencies = hedger.latencies.lock().unwrap();
+        assert_eq!(latencies.len(), 1);
+        assert!(latencies[0] >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_hedger_skips_cancelled_request() {
+        let hedger = hedger();
+        let request = hedger.timed(tokio::time::sleep(Duration::from_secs(5)));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), request)
+                .await
+                .is_err()
+        );
+
+        assert!(hedger.latencies.lock().unwrap().is_empty());
+    }
+}
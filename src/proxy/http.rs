@@ -1,152 +1,393 @@
 //! HTTP proxy handlers for EL and CL requests
 
 use axum::Json;
-use axum::body::Body;
+use axum::body::{Body, Bytes};
 use axum::extract::State;
-use axum::http::{Request, StatusCode};
+use axum::http::{HeaderMap, Method, Request, StatusCode, Uri, header};
+use axum::response::sse::{Event as SseEvent, KeepAlive, Sse};
 use axum::response::{IntoResponse, Response};
+use futures_util::StreamExt;
 use serde::Serialize;
+use serde_json::Value;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::time::{Duration, Instant};
-use tracing::{debug, warn};
+use tokio::sync::mpsc;
+use tracing::{Instrument, Span, debug, field, info_span, warn};
 
+use crate::access_log::AccessRecord;
+use crate::auth::{ApiKeyState, AuthorizedKey, ProxyRoute};
+use crate::config::{BroadcastPolicy, ClRouteAction, MethodRoute, RetryRule};
+use crate::events::{EventKind, HealthState, Layer};
 use crate::metrics::VixyMetrics;
-use crate::proxy::selection;
-use crate::state::AppState;
-
-/// Default timeout for proxy requests
-const DEFAULT_TIMEOUT_MS: u64 = 30000;
+use crate::proxy::cache::{CacheKey, ResponseCache};
+use crate::proxy::hedge::Hedger;
+use crate::proxy::jsonrpc::{self, JsonRpcPayload};
+use crate::proxy::outlier::OutlierDetector;
+use crate::proxy::{path_pattern, selection, sse};
+use crate::request_id::RequestId;
+use crate::state::{AppState, ElNodeState};
+use crate::telemetry;
 
 /// Handle EL HTTP proxy requests (POST /el)
 pub async fn el_proxy_handler(
     State(state): State<Arc<AppState>>,
     request: Request<Body>,
 ) -> Response {
-    let start = Instant::now();
+    let request_id = request.extensions().get::<RequestId>();
+    let span = info_span!(
+        "el_request",
+        otel.kind = "server",
+        request_id = request_id.map(RequestId::as_str),
+        rpc.method = field::Empty,
+        http.status_code = field::Empty,
+    );
+    telemetry::set_parent_from(&span, request.headers());
+
+    let response = proxy_el_request(state, request)
+        .instrument(span.clone())
+        .await;
+    span.record("http.status_code", response.status().as_u16());
+    response
+}
 
-    // Read the failover flag
-    let failover_active = state.el_failover_active.load(Ordering::SeqCst);
+/// Filter, route and proxy an EL request, recording per-method metrics
+async fn proxy_el_request(state: Arc<AppState>, request: Request<Body>) -> Response {
+    let start = Instant::now();
 
-    // Get a read lock on EL nodes and extract what we need
-    let (target_url, node_name, tier) = {
-        let el_nodes = state.el_nodes.read().await;
+    // Read the body up front so JSON-RPC batches can be inspected
+    let (parts, body) = request.into_parts();
+    let body_bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!(error = %e, "Failed to read request body");
+            return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+        }
+    };
 
-        // Select a healthy node
-        match selection::select_el_node(&el_nodes, failover_active) {
-            Some(n) => {
-                let tier = if n.is_primary { "primary" } else { "backup" };
-                (n.http_url.clone(), n.name.clone(), tier)
+    // Reject methods the global or per-key method filter doesn't allow
+    let api_key = parts.extensions.get::<AuthorizedKey>().map(|k| k.0.clone());
+    let key_name = api_key.as_ref().map_or("anonymous", |k| k.name.as_str());
+    let is_allowed = |call: &Value| method_allowed(&state, api_key.as_deref(), call);
+
+    // Calls of a batch answered here, with their positions in `batch`
+    let mut batch = Vec::new();
+    let mut rejected = Vec::new();
+    let (payload, body_bytes) = match jsonrpc::parse_payload(&body_bytes) {
+        Some(JsonRpcPayload::Single(call)) if !is_allowed(&call) => {
+            debug!(
+                method = jsonrpc::method_of(&call),
+                "Rejecting disallowed EL method"
+            );
+            VixyMetrics::inc_el_method_rejection(key_name, "el");
+            return Json(jsonrpc::method_not_allowed(&call)).into_response();
+        }
+        Some(JsonRpcPayload::Batch(calls)) => {
+            if let Some(response) = check_batch_size(&state, &calls) {
+                return response;
             }
-            None => {
-                warn!("No healthy EL node available");
-                return (
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    "No healthy EL node available",
-                )
-                    .into_response();
+            let mut allowed = Vec::with_capacity(calls.len());
+            for (position, call) in calls.iter().enumerate() {
+                if is_allowed(call) {
+                    allowed.push(call.clone());
+                    continue;
+                }
+                VixyMetrics::inc_el_method_rejection(key_name, "el");
+                if jsonrpc::id_of(call).is_some() {
+                    rejected.push((position, jsonrpc::method_not_allowed(call)));
+                }
+            }
+            if allowed.len() == calls.len() {
+                (Some(JsonRpcPayload::Batch(calls)), body_bytes)
+            } else if allowed.is_empty() {
+                return batch_response(rejected.into_iter().map(|(_, r)| r).collect());
+            } else {
+                let body_bytes = Bytes::from(serde_json::to_vec(&allowed).unwrap_or_default());
+                batch = calls;
+                (Some(JsonRpcPayload::Batch(allowed)), body_bytes)
             }
         }
+        payload => (payload, body_bytes),
     };
 
-    debug!(target_url, node_name, tier, "Proxying EL request");
-
-    // Forward the request
-    let response = forward_request(request, &target_url).await;
+    let method_label = match &payload {
+        Some(JsonRpcPayload::Single(call)) => jsonrpc::method_of(call)
+            .and_then(|m| state.el_metric_methods.get(m))
+            .map_or("other", |m| m.as_str())
+            .to_string(),
+        Some(JsonRpcPayload::Batch(_)) => "batch".to_string(),
+        None => "other".to_string(),
+    };
+    Span::current().record("rpc.method", &method_label);
+    if let Some(record) = parts.extensions.get::<AccessRecord>()
+        && let Some(payload) = &payload
+    {
+        record.set_methods(payload_methods(payload));
+    }
 
-    // Record metrics
-    let duration = start.elapsed().as_secs_f64();
-    VixyMetrics::inc_el_requests(&node_name, tier);
-    VixyMetrics::observe_el_duration(&node_name, tier, duration);
+    let mut response = proxy_el_payload(state, parts, body_bytes, payload, start).await;
+    if !rejected.is_empty() {
+        response = merge_batch_response(response, &batch, rejected).await;
+    }
 
+    let (response, outcome) = el_outcome(response).await;
+    VixyMetrics::observe_el_method_request(
+        &method_label,
+        outcome,
+        response.status().as_u16(),
+        start.elapsed().as_secs_f64(),
+    );
     response
 }
 
-/// Handle CL HTTP proxy requests (GET/POST /cl/*)
-pub async fn cl_proxy_handler(
-    State(state): State<Arc<AppState>>,
-    request: Request<Body>,
-) -> Response {
-    let start = Instant::now();
+/// Distinct methods of a JSON-RPC payload, comma separated, for the access log
+fn payload_methods(payload: &JsonRpcPayload) -> String {
+    let calls = match payload {
+        JsonRpcPayload::Single(call) => std::slice::from_ref(call),
+        JsonRpcPayload::Batch(calls) => calls.as_slice(),
+    };
+    let mut methods: Vec<&str> = Vec::new();
+    for method in calls.iter().filter_map(jsonrpc::method_of) {
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+    }
+    methods.join(",")
+}
 
-    // Get a read lock on CL nodes and extract what we need
-    let (target_url, node_name) = {
-        let cl_nodes = state.cl_nodes.read().await;
+/// Buffer an EL response and classify its outcome for metrics
+///
+/// Returns `timeout`, `http_error`, `jsonrpc_error` (if the response, or any response
+/// in a batch, is a JSON-RPC error) or `ok`.
+async fn el_outcome(response: Response) -> (Response, &'static str) {
+    let status = response.status();
+    if status == StatusCode::GATEWAY_TIMEOUT {
+        return (response, "timeout");
+    }
+    if !status.is_success() {
+        return (response, "http_error");
+    }
 
-        // Select a healthy node
-        match selection::select_cl_node(&cl_nodes) {
-            Some(n) => (n.url.clone(), n.name.clone()),
-            None => {
-                warn!("No healthy CL node available");
-                return (
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    "No healthy CL node available",
-                )
-                    .into_response();
-            }
-        }
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return (
+            (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response(),
+            "http_error",
+        );
+    };
+    let is_error = |r: &Value| r.get("error").is_some_and(|e| !e.is_null());
+    let outcome = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Array(responses)) if responses.iter().any(is_error) => "jsonrpc_error",
+        Ok(response) if is_error(&response) => "jsonrpc_error",
+        _ => "ok",
     };
+    (Response::from_parts(parts, Body::from(bytes)), outcome)
+}
 
-    // Extract the path from the request (strip /cl prefix)
-    let path = request.uri().path();
-    let cl_path = path
-        .strip_prefix("/cl/")
-        .or_else(|| path.strip_prefix("/cl"))
-        .unwrap_or(path);
-    // Ensure path starts with / for proper URL construction
-    let cl_path = if cl_path.is_empty() || cl_path == "/" {
-        ""
-    } else if cl_path.starts_with('/') {
-        cl_path
+/// Check a JSON-RPC call against the global and per-key method filters
+///
+/// Calls without a method are left for the upstream node to reject.
+fn method_allowed(state: &AppState, api_key: Option<&ApiKeyState>, call: &Value) -> bool {
+    let Some(method) = jsonrpc::method_of(call) else {
+        return true;
+    };
+    state.el_method_filter.permits(method)
+        && api_key.is_none_or(|key| key.method_filter.permits(method))
+}
+
+/// Build the response to a batch from its individual call responses
+///
+/// A batch without responses (only notifications) gets an empty body.
+fn batch_response(responses: Vec<Value>) -> Response {
+    if responses.is_empty() {
+        StatusCode::OK.into_response()
     } else {
-        // This shouldn't happen but handle it gracefully
-        cl_path
+        Json(Value::Array(responses)).into_response()
+    }
+}
+
+/// Merge the responses to calls answered locally into an upstream batch response
+///
+/// Responses are returned in the order of the calls in `batch`, the batch as sent by
+/// the client; see jsonrpc::merge_batch_responses.
+async fn merge_batch_response(
+    response: Response,
+    batch: &[Value],
+    rejected: Vec<(usize, Value)>,
+) -> Response {
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!(error = %e, "Failed to read upstream response");
+            return (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response();
+        }
     };
-    let query = request
-        .uri()
-        .query()
-        .map(|q| format!("?{q}"))
-        .unwrap_or_default();
 
-    // Build full URL, ensuring proper slash handling
-    let base_url = target_url.trim_end_matches('/');
-    let full_url = if cl_path.is_empty() {
-        format!("{base_url}{query}")
-    } else if cl_path.starts_with('/') {
-        format!("{base_url}{cl_path}{query}")
+    let upstream = if bytes.is_empty() {
+        Vec::new()
     } else {
-        format!("{base_url}/{cl_path}{query}")
+        match serde_json::from_slice::<Value>(&bytes) {
+            Ok(Value::Array(responses)) => responses,
+            _ => return Response::from_parts(parts, Body::from(bytes)),
+        }
     };
+    batch_response(jsonrpc::merge_batch_responses(batch, rejected, upstream))
+}
 
-    debug!(full_url, node_name, "Proxying CL request");
+/// Route and forward a parsed EL request body
+async fn proxy_el_payload(
+    state: Arc<AppState>,
+    parts: axum::http::request::Parts,
+    body_bytes: Bytes,
+    payload: Option<JsonRpcPayload>,
+    start: Instant,
+) -> Response {
+    // Read the failover flag
+    let failover_active = state.el_failover_active.load(Ordering::SeqCst);
 
-    // Forward the request to the constructed URL
-    let response = forward_request_to_url(request, &full_url).await;
+    // Chain head cache lookups are made against
+    let head = state.el_chain_head.load(Ordering::SeqCst);
+    let mut cache_key = None;
+    let mut coalesce_key = None;
+    let mut single_call = false;
+
+    // Method used for routing (the call's method, or the first call's method when
+    // every call in an unsplit batch is routed the same way) and the block height
+    // the selected node must have reached
+    let (routing_method, min_block) = match payload {
+        Some(JsonRpcPayload::Single(call)) => {
+            let method = jsonrpc::method_of(&call);
+            if state.el_broadcast.enabled
+                && method.is_some_and(|m| state.el_broadcast.methods.iter().any(|b| b == m))
+            {
+                return broadcast_el_call(&state, body_bytes, failover_active).await;
+            }
+            if let Some(cache) = &state.el_cache
+                && let Some(key) = cache.key_for(&call, head)
+            {
+                if bypasses_cache(&parts.headers) {
+                    VixyMetrics::inc_el_cache_request("bypass");
+                } else if let Some(result) = cache.get(&key, head) {
+                    VixyMetrics::inc_el_cache_request("hit");
+                    let id = jsonrpc::id_of(&call).cloned().unwrap_or(Value::Null);
+                    return Json(jsonrpc::success_response(id, result)).into_response();
+                } else {
+                    VixyMetrics::inc_el_cache_request("miss");
+                }
+                cache_key = Some(key);
+            }
+            // A client bypassing the cache wants a fresh answer, not a shared one
+            if state.coalescer.is_some() && !bypasses_cache(&parts.headers) {
+                coalesce_key = el_coalesce_key(&call);
+            }
+            single_call = true;
+            (method.map(String::from), jsonrpc::requested_block(&call))
+        }
+        Some(JsonRpcPayload::Batch(calls)) => {
+            if state.split_batches {
+                return split_el_batch(&state, &parts, calls, failover_active, start).await;
+            }
+            (
+                batch_routing_method(&state.el_routes, &calls),
+                calls.iter().filter_map(jsonrpc::requested_block).max(),
+            )
+        }
+        None => (None, None),
+    };
+    let request = Request::from_parts(parts, Body::from(body_bytes));
+
+    let forward = forward_el_request(
+        state.clone(),
+        request,
+        failover_active,
+        routing_method,
+        min_block,
+        single_call,
+        start,
+    );
+    let mut response = match coalesce_key {
+        Some((key, id)) => coalesce(&state, "el", key, forward, Some(id)).await,
+        None => forward.await,
+    };
 
-    // Record metrics
-    let duration = start.elapsed().as_secs_f64();
-    VixyMetrics::inc_cl_requests(&node_name);
-    VixyMetrics::observe_cl_duration(&node_name, duration);
+    // Results are cached against the height of the node that served them, so a
+    // lagging node's answer is not served as current
+    if let (Some(cache), Some(key)) = (&state.el_cache, cache_key)
+        && let Some(ServingNode(node_name)) = response.extensions().get::<ServingNode>()
+        && let Some(node_head) = state
+            .el_nodes
+            .read()
+            .await
+            .iter()
+            .find(|n| &n.name == node_name)
+            .map(|n| n.block_number)
+    {
+        response = store_in_cache(cache, key, response, node_head).await;
+    }
 
     response
 }
 
-/// Forward a request to a target URL
-async fn forward_request(request: Request<Body>, target_url: &str) -> Response {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(DEFAULT_TIMEOUT_MS))
-        .build()
-        .expect("Failed to build HTTP client");
+/// An EL node a request is sent to: (url, name, tier)
+type ElTarget = (String, String, &'static str);
 
-    // Extract method, headers, and body
-    let method = request.method().clone();
-    let content_type = request
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .map(String::from);
+/// Name of the EL node that served a response, kept in the response extensions
+#[derive(Debug, Clone)]
+struct ServingNode(String);
 
-    let body_bytes = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
+/// Get the target of an EL node
+fn el_target(node: &ElNodeState) -> ElTarget {
+    let tier = if node.is_primary { "primary" } else { "backup" };
+    (node.http_url.clone(), node.name.clone(), tier)
+}
+
+/// Select an EL node for a request and forward it
+///
+/// Single calls (`single_call`) may be hedged and, if idempotent, are retried on
+/// another node when the response matches a retry rule.
+async fn forward_el_request(
+    state: Arc<AppState>,
+    request: Request<Body>,
+    failover_active: bool,
+    routing_method: Option<String>,
+    min_block: Option<u64>,
+    single_call: bool,
+    start: Instant,
+) -> Response {
+    let method = routing_method.as_deref();
+    let call_method = method.filter(|_| single_call);
+    let hedger = state
+        .el_hedger
+        .as_ref()
+        .filter(|h| call_method.is_some_and(|m| h.applies_to(m)));
+    let retry_method =
+        call_method.filter(|m| !state.el_retry_rules.is_empty() && jsonrpc::is_idempotent(m));
+
+    let Some((mut target, mut hedge_target)) = select_el_targets(
+        &state,
+        failover_active,
+        method,
+        min_block,
+        hedger.is_some(),
+        &[],
+    )
+    .await
+    else {
+        warn!("No healthy EL node available");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No healthy EL node available",
+        )
+            .into_response();
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
         Ok(bytes) => bytes,
         Err(e) => {
             warn!(error = %e, "Failed to read request body");
@@ -154,294 +395,2530 @@ async fn forward_request(request: Request<Body>, target_url: &str) -> Response {
         }
     };
 
-    // Build the forwarded request with Content-Type header
-    let mut forward_request = client.request(method, target_url);
-    if let Some(ct) = content_type {
-        forward_request = forward_request.header("content-type", ct);
-    }
-    forward_request = forward_request.body(body_bytes);
-
-    // Send the request
-    match forward_request.send().await {
-        Ok(response) => convert_response(response).await,
-        Err(e) => {
-            if e.is_timeout() {
-                warn!(error = %e, "Proxy request timed out");
-                return (StatusCode::GATEWAY_TIMEOUT, "Request timed out").into_response();
+    let record = parts.extensions.get::<AccessRecord>().cloned();
+    let mut tried = Vec::new();
+    let mut retries = 0;
+    loop {
+        tried.push(target.1.clone());
+        let attempt_span = info_span!("el_attempt", attempt = retries + 1);
+        let response = match (hedger, &hedge_target) {
+            (Some(hedger), Some(hedge_target)) => {
+                let (response, hedged) =
+                    hedged_el_request(&state, hedger, &parts, &body, &target, hedge_target, start)
+                        .instrument(attempt_span)
+                        .await;
+                if hedged {
+                    tried.push(hedge_target.1.clone());
+                }
+                response
             }
-            warn!(error = %e, "Proxy request failed");
-            (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response()
+            _ => {
+                send_el_request(&state, build_request(&parts, &body), &target, start)
+                    .instrument(attempt_span)
+                    .await
+            }
+        };
+        if let Some(record) = &record {
+            record.set_upstream(&target.1, tried.len());
         }
+
+        let Some(method) = retry_method.filter(|_| retries < state.max_retries) else {
+            return response;
+        };
+        let (response, reason) = el_retry_reason(&state.el_retry_rules, method, response).await;
+        let Some(reason) = reason else {
+            return response;
+        };
+        let Some(next) = select_el_targets(
+            &state,
+            failover_active,
+            Some(method),
+            min_block,
+            hedger.is_some(),
+            &tried,
+        )
+        .await
+        else {
+            return response;
+        };
+
+        debug!(
+            node = %target.1,
+            next = %next.0.1,
+            method,
+            reason,
+            "Retrying EL call on another node"
+        );
+        VixyMetrics::inc_el_retry(&target.1, reason);
+        retries += 1;
+        (target, hedge_target) = next;
     }
 }
 
-/// Forward a request to a specific URL (used for CL with path construction)
-async fn forward_request_to_url(request: Request<Body>, target_url: &str) -> Response {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(DEFAULT_TIMEOUT_MS))
-        .build()
-        .expect("Failed to build HTTP client");
+/// Select the EL node for a call and, if `hedge` is set, a second node to hedge to
+///
+/// Nodes named in `tried` are skipped; when it is non-empty, only nodes that have
+/// reached `min_block` are chosen.
+async fn select_el_targets(
+    state: &AppState,
+    failover_active: bool,
+    method: Option<&str>,
+    min_block: Option<u64>,
+    hedge: bool,
+    tried: &[String],
+) -> Option<(ElTarget, Option<ElTarget>)> {
+    let el_nodes = state.el_nodes.read().await;
+
+    let node = if tried.is_empty() {
+        selection::select_el_node_for_call(
+            &el_nodes,
+            failover_active,
+            &state.el_routes,
+            method,
+            min_block,
+        )
+    } else {
+        selection::select_other_el_node(
+            &el_nodes,
+            failover_active,
+            &state.el_routes,
+            method,
+            min_block,
+            tried,
+        )
+    }?;
+
+    let hedge_node = if hedge {
+        let mut exclude = tried.to_vec();
+        exclude.push(node.name.clone());
+        selection::select_other_el_node(
+            &el_nodes,
+            failover_active,
+            &state.el_routes,
+            method,
+            min_block,
+            &exclude,
+        )
+    } else {
+        None
+    };
 
-    // Extract method, headers, and body
-    let method = request.method().clone();
-    let content_type = request
-        .headers()
-        .get("content-type")
-        .and_then(|v| v.to_str().ok())
-        .map(String::from);
+    Some((el_target(node), hedge_node.map(el_target)))
+}
 
-    let body_bytes = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            warn!(error = %e, "Failed to read request body");
-            return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
-        }
+/// Build a request to forward from buffered request parts and body
+fn build_request(parts: &axum::http::request::Parts, body: &Bytes) -> Request<Body> {
+    let mut request = Request::new(Body::from(body.clone()));
+    *request.method_mut() = parts.method.clone();
+    *request.uri_mut() = parts.uri.clone();
+    *request.headers_mut() = parts.headers.clone();
+    request
+}
+
+/// Send a request to an EL node, recording its outcome
+async fn send_el_request(
+    state: &AppState,
+    request: Request<Body>,
+    (target_url, node_name, tier): &ElTarget,
+    start: Instant,
+) -> Response {
+    debug!(target_url, node_name, tier, "Proxying EL request");
+
+    // Forward the request
+    let span = info_span!(
+        "el_upstream",
+        otel.kind = "client",
+        node = %node_name,
+        tier,
+        http.status_code = field::Empty,
+    );
+    let mut response = forward_request(&state.http_client, request, target_url)
+        .instrument(span.clone())
+        .await;
+    span.record("http.status_code", response.status().as_u16());
+
+    if let Some(detector) = &state.outlier_detector {
+        let is_error;
+        (response, is_error) = inspect_el_response(detector, response).await;
+        record_el_outcome(state, detector, node_name, tier, is_error).await;
+    }
+    response
+        .extensions_mut()
+        .insert(ServingNode(node_name.clone()));
+
+    // Record metrics
+    let duration = start.elapsed().as_secs_f64();
+    VixyMetrics::inc_el_requests(node_name, tier);
+    VixyMetrics::observe_el_duration(node_name, tier, duration);
+
+    response
+}
+
+/// Send a request to `first`, hedging it to `second` if `first` hasn't answered
+/// within the hedge delay
+///
+/// Whichever node answers first wins and the other request is cancelled. The latency
+/// of `first` is only recorded if it answers. Also returns whether the hedge was sent.
+async fn hedged_el_request(
+    state: &AppState,
+    hedger: &Hedger,
+    parts: &axum::http::request::Parts,
+    body: &Bytes,
+    first: &ElTarget,
+    second: &ElTarget,
+    start: Instant,
+) -> (Response, bool) {
+    let first_request = hedger.timed(send_el_request(
+        state,
+        build_request(parts, body),
+        first,
+        start,
+    ));
+    tokio::pin!(first_request);
+
+    let delay = hedger.delay();
+    if let Ok(response) = tokio::time::timeout(delay, &mut first_request).await {
+        VixyMetrics::inc_el_hedge_request("not_hedged");
+        return (response, false);
+    }
+
+    debug!(
+        node = %second.1,
+        delay_ms = delay.as_millis() as u64,
+        "Hedging slow EL request"
+    );
+    let hedge_request = send_el_request(state, build_request(parts, body), second, start);
+    let (response, outcome) = tokio::select! {
+        response = &mut first_request => (response, "first_won"),
+        response = hedge_request => (response, "hedge_won"),
     };
+    VixyMetrics::inc_el_hedge_request(outcome);
 
-    // Build the forwarded request with Content-Type header
-    let mut forward_request = client.request(method, target_url);
-    if let Some(ct) = content_type {
-        forward_request = forward_request.header("content-type", ct);
+    (response, true)
+}
+
+/// Buffer an EL response and find the reason it should be retried, if any
+async fn el_retry_reason(
+    rules: &[RetryRule],
+    method: &str,
+    response: Response,
+) -> (Response, Option<&'static str>) {
+    if response.status() != StatusCode::OK {
+        return (response, None);
     }
-    if !body_bytes.is_empty() {
-        forward_request = forward_request.body(body_bytes);
+
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return (
+            (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response(),
+            None,
+        );
+    };
+    let reason = serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .and_then(|r| rules.iter().find_map(|rule| rule.retry_reason(method, &r)));
+    (Response::from_parts(parts, Body::from(bytes)), reason)
+}
+
+/// Buffer an EL response and check whether it counts as a node error
+async fn inspect_el_response(detector: &OutlierDetector, response: Response) -> (Response, bool) {
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return (
+            (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response(),
+            true,
+        );
+    };
+    let is_error = detector.is_el_error(parts.status, &bytes);
+    (Response::from_parts(parts, Body::from(bytes)), is_error)
+}
+
+/// Record the outcome of a request to an EL node, ejecting the node if it became an outlier
+///
+/// The last EL node that can be selected under the current failover state is never
+/// ejected, even if healthy backups are standing by.
+async fn record_el_outcome(
+    state: &AppState,
+    detector: &OutlierDetector,
+    node_name: &str,
+    tier: &str,
+    is_error: bool,
+) {
+    if !detector.record(node_name, is_error) {
+        return;
     }
 
-    // Send the request
-    match forward_request.send().await {
-        Ok(response) => convert_response(response).await,
-        Err(e) => {
-            if e.is_timeout() {
-                warn!(error = %e, "Proxy request timed out");
-                return (StatusCode::GATEWAY_TIMEOUT, "Request timed out").into_response();
-            }
-            warn!(error = %e, "Proxy request failed");
-            (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response()
+    let mut el_nodes = state.el_nodes.write().await;
+    let failover_active = state.el_failover_active.load(Ordering::SeqCst);
+    let selectable = selection::healthy_el_nodes(&el_nodes, failover_active);
+    let last_selectable = selectable.len() == 1 && selectable[0].name == node_name;
+    if let Some(node) = el_nodes.iter_mut().find(|n| n.name == node_name)
+        && node.is_healthy
+        && !last_selectable
+    {
+        warn!(
+            node = node_name,
+            "Ejecting EL node after too many failed requests"
+        );
+        node.is_healthy = false;
+        node.ejected_until = Some(Instant::now() + detector.ejection());
+        node.flaps = node.flaps.saturating_add(1);
+        VixyMetrics::inc_el_node_ejections(node_name, tier);
+        VixyMetrics::inc_el_node_flaps(node_name, tier);
+        state.events.record(EventKind::NodeHealth {
+            layer: Layer::El,
+            node: node.name.clone(),
+            tier: if node.is_primary { "primary" } else { "backup" },
+            old_state: HealthState::Healthy,
+            new_state: HealthState::Unhealthy,
+            reason: "ejected",
+            lag: node.lag,
+            block: node.block_number,
+        });
+    }
+}
+
+/// Methods that are idempotent but whose result depends on who is asking
+const UNCOALESCABLE_METHODS: &[&str] = &[
+    "eth_getFilterChanges",
+    "eth_newFilter",
+    "eth_newBlockFilter",
+    "eth_newPendingTransactionFilter",
+    "eth_subscribe",
+];
+
+/// Get the coalescing key and id of a single JSON-RPC call, if it may share an upstream call
+fn el_coalesce_key(call: &Value) -> Option<(String, Value)> {
+    let method = jsonrpc::method_of(call)?;
+    if !jsonrpc::is_idempotent(method) || UNCOALESCABLE_METHODS.contains(&method) {
+        return None;
+    }
+    let id = jsonrpc::id_of(call)?.clone();
+    let params = call.get("params").unwrap_or(&Value::Null);
+    Some((format!("{method}:{params}"), id))
+}
+
+/// Forward a request, sharing the upstream call with identical in-flight requests
+///
+/// For JSON-RPC requests the shared response gets the id of each waiting request.
+async fn coalesce<F>(
+    state: &AppState,
+    layer: &str,
+    key: String,
+    forward: F,
+    id: Option<Value>,
+) -> Response
+where
+    F: std::future::Future<Output = Response> + Send + 'static,
+{
+    let Some(coalescer) = &state.coalescer else {
+        return forward.await;
+    };
+
+    let (mut response, coalesced) = coalescer.run(format!("{layer}:{key}"), forward).await;
+    if coalesced {
+        VixyMetrics::inc_coalesced_requests(layer);
+        if let Some(body) = id.and_then(|id| jsonrpc::replace_id(&response.body, &id)) {
+            response.body = Bytes::from(body);
         }
     }
+    response.into_response()
 }
 
-/// Convert a reqwest response to an axum response
-async fn convert_response(response: reqwest::Response) -> Response {
-    let status = StatusCode::from_u16(response.status().as_u16())
-        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+/// Check whether the client asked to skip the response cache (`Cache-Control: no-cache`)
+///
+/// Such requests are not coalesced either.
+fn bypasses_cache(headers: &axum::http::HeaderMap) -> bool {
+    headers
+        .get_all(axum::http::header::CACHE_CONTROL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|directive| matches!(directive.trim(), "no-cache" | "no-store"))
+}
 
-    match response.bytes().await {
-        Ok(bytes) => (status, bytes.to_vec()).into_response(),
+/// Cache the result of a successful upstream response and pass the response on
+async fn store_in_cache(
+    cache: &ResponseCache,
+    key: CacheKey,
+    response: Response,
+    head: u64,
+) -> Response {
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
         Err(e) => {
-            warn!(error = %e, "Failed to read response body");
-            (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response()
+            warn!(error = %e, "Failed to read upstream response");
+            return (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response();
         }
+    };
+
+    if let Ok(mut json) = serde_json::from_slice::<Value>(&bytes)
+        && json.get("error").is_none_or(Value::is_null)
+        && let Some(result) = json.get_mut("result").map(Value::take)
+    {
+        cache.insert(key, result, head);
+        let (entries, size) = cache.usage();
+        VixyMetrics::set_el_cache_usage(entries as u64, size as u64);
     }
+
+    Response::from_parts(parts, Body::from(bytes))
 }
 
-// ============================================================================
-// Status endpoint
-// ============================================================================
+/// Reject empty batches and batches larger than the configured limit
+///
+/// Returns a JSON-RPC error response if the batch is not acceptable.
+fn check_batch_size(state: &AppState, calls: &[Value]) -> Option<Response> {
+    let message = if calls.is_empty() {
+        "empty batch".to_string()
+    } else if state.max_batch_size > 0 && calls.len() > state.max_batch_size {
+        format!(
+            "batch of {} calls exceeds the limit of {}",
+            calls.len(),
+            state.max_batch_size
+        )
+    } else {
+        return None;
+    };
 
-/// EL node status for JSON response
-#[derive(Debug, Serialize)]
-pub struct ElNodeStatus {
-    pub name: String,
-    pub http_url: String,
-    pub is_primary: bool,
-    pub block_number: u64,
-    pub lag: u64,
-    pub check_ok: bool,
-    pub is_healthy: bool,
+    warn!(
+        batch_size = calls.len(),
+        "Rejecting JSON-RPC batch: {message}"
+    );
+    let error = jsonrpc::error_response(Value::Null, jsonrpc::INVALID_REQUEST, &message);
+    Some((StatusCode::OK, Json(error)).into_response())
 }
 
-/// CL node status for JSON response
-#[derive(Debug, Serialize)]
-pub struct ClNodeStatus {
-    pub name: String,
-    pub url: String,
-    pub slot: u64,
-    pub lag: u64,
-    pub health_ok: bool,
-    pub is_healthy: bool,
+/// Pick the method an unsplit batch is routed by
+///
+/// Returns the first call's method if every call maps to the same routing tag,
+/// otherwise None so the batch goes to the default tier.
+fn batch_routing_method(routes: &[MethodRoute], calls: &[Value]) -> Option<String> {
+    let first = jsonrpc::method_of(calls.first()?)?;
+    let tag = selection::route_tag(routes, first);
+    let uniform = calls
+        .iter()
+        .all(|c| jsonrpc::method_of(c).and_then(|m| selection::route_tag(routes, m)) == tag);
+
+    uniform.then(|| first.to_string())
 }
 
-/// Full status response
-#[derive(Debug, Serialize)]
-pub struct StatusResponse {
-    pub el_chain_head: u64,
-    pub cl_chain_head: u64,
-    pub el_failover_active: bool,
-    pub el_nodes: Vec<ElNodeStatus>,
-    pub cl_nodes: Vec<ClNodeStatus>,
+/// Split a JSON-RPC batch across healthy EL nodes and reassemble the responses
+///
+/// Sub-batches are sent concurrently, one per node. Responses are returned in the
+/// order of the original calls; notifications (calls without an id) get no response.
+async fn split_el_batch(
+    state: &Arc<AppState>,
+    parts: &axum::http::request::Parts,
+    calls: Vec<Value>,
+    failover_active: bool,
+    start: Instant,
+) -> Response {
+    // Group calls by target node
+    let groups: Vec<(ElTarget, Vec<usize>)> = {
+        let el_nodes = state.el_nodes.read().await;
+
+        let Some(assignment) =
+            selection::assign_batch_calls(&el_nodes, failover_active, &state.el_routes, &calls)
+        else {
+            warn!("No healthy EL node available");
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "No healthy EL node available",
+            )
+                .into_response();
+        };
+
+        let mut groups: Vec<(ElTarget, Vec<usize>)> = el_nodes
+            .iter()
+            .map(|n| (el_target(n), Vec::new()))
+            .collect();
+        for (call_index, node_index) in assignment.into_iter().enumerate() {
+            groups[node_index].1.push(call_index);
+        }
+        groups.retain(|g| !g.1.is_empty());
+        groups
+    };
+
+    debug!(
+        batch_size = calls.len(),
+        nodes = groups.len(),
+        "Splitting EL batch across nodes"
+    );
+
+    let sub_batches = groups.iter().map(|(target, indices)| {
+        let sub_batch = indices.iter().map(|&i| calls[i].clone()).collect();
+        forward_sub_batch(
+            state,
+            parts,
+            sub_batch,
+            target.clone(),
+            failover_active,
+            start,
+        )
+    });
+    let results = futures_util::future::join_all(sub_batches).await;
+
+    // Reassemble in original order, matching responses to calls by id
+    let mut responses_by_call: Vec<Option<Value>> = vec![None; calls.len()];
+    for ((_, indices), responses) in groups.iter().zip(results) {
+        let mut by_id = jsonrpc::responses_by_id(responses);
+        for &i in indices {
+            let Some(id) = jsonrpc::id_of(&calls[i]) else {
+                continue;
+            };
+            let response = by_id
+                .get_mut(&id.to_string())
+                .and_then(|queue| queue.pop_front())
+                .unwrap_or_else(|| {
+                    jsonrpc::error_response(
+                        id.clone(),
+                        jsonrpc::INTERNAL_ERROR,
+                        "no response from upstream node",
+                    )
+                });
+            responses_by_call[i] = Some(response);
+        }
+    }
+
+    let responses: Vec<Value> = responses_by_call.into_iter().flatten().collect();
+    if responses.is_empty() {
+        // A batch of only notifications gets no response body
+        return StatusCode::OK.into_response();
+    }
+    (StatusCode::OK, Json(Value::Array(responses))).into_response()
 }
 
-/// Handle status requests (GET /status)
+/// Send a sub-batch of a split batch to its node and return the response objects
 ///
-/// Returns JSON with all node health states
-pub async fn status_handler(State(state): State<Arc<AppState>>) -> Json<StatusResponse> {
-    let el_chain_head = state.el_chain_head.load(Ordering::SeqCst);
-    let cl_chain_head = state.cl_chain_head.load(Ordering::SeqCst);
-    let el_failover_active = state.el_failover_active.load(Ordering::SeqCst);
+/// Like a single call, the sub-batch is hedged if every call in it may be hedged, and
+/// idempotent calls whose responses match a retry rule are sent again, together, to
+/// another node.
+async fn forward_sub_batch(
+    state: &Arc<AppState>,
+    parts: &axum::http::request::Parts,
+    calls: Vec<Value>,
+    mut target: ElTarget,
+    failover_active: bool,
+    start: Instant,
+) -> Vec<Value> {
+    let hedger = state.el_hedger.as_ref().filter(|h| {
+        calls
+            .iter()
+            .all(|c| jsonrpc::method_of(c).is_some_and(|m| h.applies_to(m)))
+    });
+
+    let mut pending = calls;
+    let mut responses = Vec::new();
+    let mut tried = Vec::new();
+    let mut retries = 0;
+    loop {
+        tried.push(target.1.clone());
+        let method = batch_routing_method(&state.el_routes, &pending);
+        let min_block = pending.iter().filter_map(jsonrpc::requested_block).max();
+        let body = Bytes::from(serde_json::to_vec(&pending).unwrap_or_default());
+        let attempt_span = info_span!("el_attempt", attempt = retries + 1);
+
+        let hedge_target = match hedger {
+            Some(_) => {
+                other_el_target(state, failover_active, method.as_deref(), min_block, &tried).await
+            }
+            None => None,
+        };
+        let response = match (hedger, &hedge_target) {
+            (Some(hedger), Some(hedge_target)) => {
+                let (response, hedged) =
+                    hedged_el_request(state, hedger, parts, &body, &target, hedge_target, start)
+                        .instrument(attempt_span)
+                        .await;
+                if hedged {
+                    tried.push(hedge_target.1.clone());
+                }
+                response
+            }
+            _ => {
+                send_el_request(state, build_request(parts, &body), &target, start)
+                    .instrument(attempt_span)
+                    .await
+            }
+        };
 
-    // Collect EL node statuses
-    let el_nodes = {
-        let nodes = state.el_nodes.read().await;
-        nodes
+        // Match responses to the pending calls, in call order
+        let mut by_id = jsonrpc::responses_by_id(sub_batch_responses(&target.1, response).await);
+        let answered: Vec<Option<Value>> = pending
             .iter()
-            .map(|n| ElNodeStatus {
-                name: n.name.clone(),
-                http_url: n.http_url.clone(),
-                is_primary: n.is_primary,
-                block_number: n.block_number,
-                lag: n.lag,
-                check_ok: n.check_ok,
-                is_healthy: n.is_healthy,
+            .map(|call| {
+                let id = jsonrpc::id_of(call)?;
+                by_id.get_mut(&id.to_string())?.pop_front()
             })
-            .collect()
+            .collect();
+        let reasons: Vec<Option<&'static str>> = pending
+            .iter()
+            .zip(&answered)
+            .map(|(call, response)| {
+                let method = jsonrpc::method_of(call).filter(|m| jsonrpc::is_idempotent(m))?;
+                let response = response.as_ref()?;
+                state
+                    .el_retry_rules
+                    .iter()
+                    .find_map(|rule| rule.retry_reason(method, response))
+            })
+            .collect();
+
+        let reason = reasons.iter().flatten().next().copied();
+        let retry_calls: Vec<Value> = pending
+            .iter()
+            .zip(&reasons)
+            .filter(|(_, reason)| reason.is_some())
+            .map(|(call, _)| call.clone())
+            .collect();
+        let next = match reason.filter(|_| retries < state.max_retries) {
+            Some(_) => {
+                let method = batch_routing_method(&state.el_routes, &retry_calls);
+                let min_block = retry_calls
+                    .iter()
+                    .filter_map(jsonrpc::requested_block)
+                    .max();
+                other_el_target(state, failover_active, method.as_deref(), min_block, &tried).await
+            }
+            None => None,
+        };
+        let (Some(next), Some(reason)) = (next, reason) else {
+            responses.extend(answered.into_iter().flatten());
+            return responses;
+        };
+
+        debug!(
+            node = %target.1,
+            next = %next.1,
+            calls = retry_calls.len(),
+            reason,
+            "Retrying EL sub-batch calls on another node"
+        );
+        VixyMetrics::inc_el_retry(&target.1, reason);
+        responses.extend(
+            answered
+                .into_iter()
+                .zip(&reasons)
+                .filter(|(_, reason)| reason.is_none())
+                .filter_map(|(response, _)| response),
+        );
+        pending = retry_calls;
+        target = next;
+        retries += 1;
+    }
+}
+
+/// Select an EL node not named in `tried` that has reached `min_block`
+async fn other_el_target(
+    state: &AppState,
+    failover_active: bool,
+    method: Option<&str>,
+    min_block: Option<u64>,
+    tried: &[String],
+) -> Option<ElTarget> {
+    let el_nodes = state.el_nodes.read().await;
+    selection::select_other_el_node(
+        &el_nodes,
+        failover_active,
+        &state.el_routes,
+        method,
+        min_block,
+        tried,
+    )
+    .map(el_target)
+}
+
+/// Read the response objects of a sub-batch response
+///
+/// Nodes answer a batch with an array, but may answer with a single error object. A
+/// failed request yields no responses.
+async fn sub_batch_responses(node_name: &str, response: Response) -> Vec<Value> {
+    let bytes = match axum::body::to_bytes(response.into_body(), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!(node = %node_name, error = %e, "EL sub-batch request failed");
+            return Vec::new();
+        }
     };
+    if bytes.is_empty() {
+        return Vec::new();
+    }
 
-    // Collect CL node statuses
-    let cl_nodes = {
-        let nodes = state.cl_nodes.read().await;
-        nodes
+    match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Array(responses)) => responses,
+        Ok(other) => vec![other],
+        Err(e) => {
+            warn!(node = %node_name, error = %e, "EL sub-batch request failed");
+            Vec::new()
+        }
+    }
+}
+
+/// Broadcast a call to every healthy EL node and extra broadcast endpoint
+///
+/// Returns the first successful response. Remaining requests keep running in the
+/// background so every node's acceptance or rejection is recorded in metrics.
+async fn broadcast_el_call(state: &AppState, body: Bytes, failover_active: bool) -> Response {
+    // Collect targets: (name, url)
+    let targets: Vec<(String, String)> = {
+        let el_nodes = state.el_nodes.read().await;
+        selection::broadcast_el_nodes(
+            &el_nodes,
+            failover_active,
+            state.el_broadcast.include_backups,
+        )
+        .into_iter()
+        .map(|n| (n.name.clone(), n.http_url.clone()))
+        .chain(
+            state
+                .el_broadcast
+                .extra_endpoints
+                .iter()
+                .map(|e| (e.name.clone(), e.http_url.clone())),
+        )
+        .collect()
+    };
+
+    if targets.is_empty() {
+        warn!("No healthy EL node available");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No healthy EL node available",
+        )
+            .into_response();
+    }
+
+    debug!(targets = targets.len(), "Broadcasting EL call");
+
+    let timeout = Duration::from_millis(state.el_broadcast.timeout_ms);
+    let (tx, mut rx) = mpsc::channel(targets.len());
+    for (name, url) in targets {
+        let client = state.http_client.clone();
+        let body = body.clone();
+        let tx = tx.clone();
+        tokio::spawn(telemetry::propagate(async move {
+            let result = send_broadcast(&client, &url, body, timeout).await;
+            let accepted = matches!(
+                &result,
+                Ok((status, bytes)) if status.is_success() && !jsonrpc::is_error_response(bytes)
+            );
+            let outcome = match &result {
+                Ok(_) if accepted => "accepted",
+                Ok(_) => "rejected",
+                Err(e) => {
+                    warn!(node = %name, error = %e, "Broadcast request failed");
+                    "failed"
+                }
+            };
+            VixyMetrics::inc_el_broadcast_result(&name, outcome);
+            debug!(node = %name, outcome, "Broadcast result");
+            let _ = tx.send((accepted, result)).await;
+        }));
+    }
+    drop(tx);
+
+    // Return the first accepted response, or the first rejection if none accepted
+    let mut rejection = None;
+    while let Some((accepted, result)) = rx.recv().await {
+        if let Ok((status, bytes)) = result {
+            if accepted {
+                return (status, bytes).into_response();
+            }
+            rejection.get_or_insert((status, bytes));
+        }
+    }
+
+    match rejection {
+        Some((status, bytes)) => (status, bytes).into_response(),
+        None => (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response(),
+    }
+}
+
+/// Send a broadcast body to one endpoint, returning its status and body
+async fn send_broadcast(
+    client: &reqwest::Client,
+    url: &str,
+    body: Bytes,
+    timeout: Duration,
+) -> Result<(StatusCode, Bytes), reqwest::Error> {
+    let response = client
+        .post(url)
+        .timeout(timeout)
+        .headers(telemetry::upstream_headers())
+        .header("content-type", "application/json")
+        .body(body)
+        .send()
+        .await?;
+    let status = StatusCode::from_u16(response.status().as_u16())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    Ok((status, response.bytes().await?))
+}
+
+/// Handle CL HTTP proxy requests (GET/POST /cl/*)
+pub async fn cl_proxy_handler(
+    State(state): State<Arc<AppState>>,
+    request: Request<Body>,
+) -> Response {
+    let start = Instant::now();
+    let route = path_pattern::route_template(cl_path(request.uri()));
+    let request_id = request.extensions().get::<RequestId>();
+    let span = info_span!(
+        "cl_request",
+        otel.kind = "server",
+        request_id = request_id.map(RequestId::as_str),
+        http.route = route,
+        http.status_code = field::Empty,
+    );
+    telemetry::set_parent_from(&span, request.headers());
+    if let Some(record) = request.extensions().get::<AccessRecord>() {
+        record.set_route(route);
+    }
+
+    let response = proxy_cl_request(state, request, start)
+        .instrument(span.clone())
+        .await;
+    span.record("http.status_code", response.status().as_u16());
+
+    VixyMetrics::observe_cl_endpoint_request(
+        route,
+        response.status().as_u16(),
+        start.elapsed().as_secs_f64(),
+    );
+    response
+}
+
+/// Apply path rules to a CL request and proxy it
+async fn proxy_cl_request(
+    state: Arc<AppState>,
+    request: Request<Body>,
+    start: Instant,
+) -> Response {
+    // Apply the first matching path rule
+    let route = selection::cl_route(
+        &state.cl_routes,
+        request.method().as_str(),
+        cl_path(request.uri()),
+    );
+    if route.is_some_and(|r| r.action == ClRouteAction::Deny) {
+        debug!(
+            path = cl_path(request.uri()),
+            "CL request denied by path rule"
+        );
+        VixyMetrics::inc_cl_route_rejection(request.method().as_str());
+        return ProxyRoute::Cl.error_response(
+            StatusCode::FORBIDDEN,
+            Value::Null,
+            "access to this endpoint is not allowed",
+        );
+    }
+    let tag = route.and_then(|r| r.tag.clone());
+
+    // Event streams stay open and are proxied without buffering
+    if sse::is_event_stream(request.method(), cl_path(request.uri())) {
+        let path_and_query = build_cl_url("", request.uri());
+        return sse::proxy_event_stream(state, path_and_query, tag).await;
+    }
+
+    // Fan out publish requests to every healthy CL node
+    if state.cl_broadcast.enabled && request.method() == Method::POST {
+        let cl_path = cl_path(request.uri());
+        if state
+            .cl_broadcast
+            .paths
             .iter()
-            .map(|n| ClNodeStatus {
-                name: n.name.clone(),
-                url: n.url.clone(),
-                slot: n.slot,
-                lag: n.lag,
-                health_ok: n.health_ok,
-                is_healthy: n.is_healthy,
-            })
-            .collect()
+            .any(|p| path_pattern::matches(p, cl_path))
+        {
+            return broadcast_cl_request(&state, request).await;
+        }
+    }
+
+    // Identical concurrent reads share one upstream call, unless the client bypasses
+    // the cache
+    if request.method() == Method::GET
+        && state.coalescer.is_some()
+        && !bypasses_cache(request.headers())
+    {
+        let key = cl_coalesce_key(&request);
+        let forward = forward_cl_request(state.clone(), request, tag, start);
+        return coalesce(&state, "cl", key, forward, None).await;
+    }
+
+    forward_cl_request(state, request, tag, start).await
+}
+
+/// Request headers that select the representation of a CL response (e.g. SSZ or JSON)
+const CL_NEGOTIATION_HEADERS: [header::HeaderName; 4] = [
+    header::ACCEPT,
+    header::ACCEPT_CHARSET,
+    header::ACCEPT_ENCODING,
+    header::ACCEPT_LANGUAGE,
+];
+
+/// Get the coalescing key of a CL read request
+///
+/// Requests only share an upstream call if they ask for the same representation.
+fn cl_coalesce_key(request: &Request<Body>) -> String {
+    let mut key = request
+        .uri()
+        .path_and_query()
+        .map(|pq| pq.as_str().to_string())
+        .unwrap_or_default();
+    for name in &CL_NEGOTIATION_HEADERS {
+        for value in request.headers().get_all(name) {
+            key.push('\n');
+            key.push_str(name.as_str());
+            key.push(':');
+            key.push_str(&String::from_utf8_lossy(value.as_bytes()));
+        }
+    }
+    key
+}
+
+/// Select a CL node for a request and forward it
+///
+/// If a path rule routes the request to `tag`, a healthy node carrying it is preferred.
+async fn forward_cl_request(
+    state: Arc<AppState>,
+    request: Request<Body>,
+    tag: Option<String>,
+    start: Instant,
+) -> Response {
+    // Read the failover flag
+    let failover_active = state.cl_failover_active.load(Ordering::SeqCst);
+
+    // Get a read lock on CL nodes and extract what we need
+    let (target_url, node_name, tier) = {
+        let cl_nodes = state.cl_nodes.read().await;
+
+        // Select a healthy node
+        match selection::select_cl_node_for_tag(
+            &cl_nodes,
+            failover_active,
+            &state.cl_routes,
+            tag.as_deref(),
+        ) {
+            Some(n) => (
+                n.url.clone(),
+                n.name.clone(),
+                if n.is_primary { "primary" } else { "backup" },
+            ),
+            None => {
+                warn!("No healthy CL node available");
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "No healthy CL node available",
+                )
+                    .into_response();
+            }
+        }
     };
 
-    Json(StatusResponse {
-        el_chain_head,
-        cl_chain_head,
-        el_failover_active,
-        el_nodes,
-        cl_nodes,
-    })
+    let full_url = build_cl_url(&target_url, request.uri());
+    if let Some(record) = request.extensions().get::<AccessRecord>() {
+        record.set_upstream(&node_name, 1);
+    }
+
+    debug!(full_url, node_name, "Proxying CL request");
+
+    // Forward the request to the constructed URL
+    let span = info_span!(
+        "cl_upstream",
+        otel.kind = "client",
+        node = %node_name,
+        tier,
+        http.status_code = field::Empty,
+    );
+    let response = forward_request_to_url(&state.http_client, request, &full_url)
+        .instrument(span.clone())
+        .await;
+    span.record("http.status_code", response.status().as_u16());
+
+    if let Some(detector) = &state.outlier_detector {
+        let is_error = response.status().is_server_error();
+        record_cl_outcome(&state, detector, &node_name, tier, is_error).await;
+    }
+
+    // Record metrics
+    let duration = start.elapsed().as_secs_f64();
+    VixyMetrics::inc_cl_requests(&node_name, tier);
+    VixyMetrics::observe_cl_duration(&node_name, tier, duration);
+
+    response
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::state::{ClNodeState, ElNodeState};
-    use axum::Router;
-    use axum::body::Body;
-    use axum::http::Request;
-    use std::sync::Arc;
-    use tokio::sync::RwLock;
-    use tower::util::ServiceExt;
-    use wiremock::matchers::{body_string, method, path};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
+/// Record the outcome of a request to a CL node, ejecting the node if it became an outlier
+///
+/// The last healthy CL node is never ejected.
+async fn record_cl_outcome(
+    state: &AppState,
+    detector: &OutlierDetector,
+    node_name: &str,
+    tier: &str,
+    is_error: bool,
+) {
+    if !detector.record(node_name, is_error) {
+        return;
+    }
 
-    // Helper to create minimal AppState for testing
-    fn create_test_state(el_nodes: Vec<ElNodeState>, cl_nodes: Vec<ClNodeState>) -> Arc<AppState> {
-        Arc::new(AppState {
-            el_nodes: Arc::new(RwLock::new(el_nodes)),
-            cl_nodes: Arc::new(RwLock::new(cl_nodes)),
-            el_chain_head: std::sync::atomic::AtomicU64::new(0),
-            cl_chain_head: std::sync::atomic::AtomicU64::new(0),
-            el_failover_active: std::sync::atomic::AtomicBool::new(false),
-            max_el_lag: 5,
-            max_cl_lag: 3,
-            proxy_timeout_ms: 30000,
-            max_retries: 2,
-            health_check_max_failures: 3,
+    let mut cl_nodes = state.cl_nodes.write().await;
+    let failover_active = state.cl_failover_active.load(Ordering::SeqCst);
+    let selectable = selection::healthy_cl_nodes(&cl_nodes, failover_active);
+    let last_selectable = selectable.len() == 1 && selectable[0].name == node_name;
+    if let Some(node) = cl_nodes.iter_mut().find(|n| n.name == node_name)
+        && node.is_healthy
+        && !last_selectable
+    {
+        warn!(
+            node = node_name,
+            "Ejecting CL node after too many failed requests"
+        );
+        node.is_healthy = false;
+        node.ejected_until = Some(Instant::now() + detector.ejection());
+        node.flaps = node.flaps.saturating_add(1);
+        VixyMetrics::inc_cl_node_ejections(node_name, tier);
+        VixyMetrics::inc_cl_node_flaps(node_name, tier);
+        state.events.record(EventKind::NodeHealth {
+            layer: Layer::Cl,
+            node: node.name.clone(),
+            tier: if node.is_primary { "primary" } else { "backup" },
+            old_state: HealthState::Healthy,
+            new_state: HealthState::Unhealthy,
+            reason: "ejected",
+            lag: node.lag,
+            block: node.slot,
+        });
+    }
+}
+
+/// Extract the beacon API path from a proxied request URI (strip /cl prefix)
+fn cl_path(uri: &Uri) -> &str {
+    let path = uri.path();
+    path.strip_prefix("/cl").unwrap_or(path)
+}
+
+/// Build the upstream URL for a CL request, preserving path and query
+fn build_cl_url(base_url: &str, uri: &Uri) -> String {
+    let cl_path = cl_path(uri);
+    // Ensure path starts with / for proper URL construction
+    let cl_path = if cl_path == "/" { "" } else { cl_path };
+    let query = uri.query().map(|q| format!("?{q}")).unwrap_or_default();
+
+    let base_url = base_url.trim_end_matches('/');
+    format!("{base_url}{cl_path}{query}")
+}
+
+/// Broadcast a CL publish request to every healthy CL node
+///
+/// Backup nodes are only included while CL failover is active. Responses are
+/// combined according to the configured broadcast policy.
+async fn broadcast_cl_request(state: &AppState, request: Request<Body>) -> Response {
+    // Collect targets: (name, full url, tier)
+    let targets: Vec<(String, String, &'static str)> = {
+        let failover_active = state.cl_failover_active.load(Ordering::SeqCst);
+        let cl_nodes = state.cl_nodes.read().await;
+        selection::healthy_cl_nodes(&cl_nodes, failover_active)
+            .into_iter()
+            .map(|n| {
+                let tier = if n.is_primary { "primary" } else { "backup" };
+                (n.name.clone(), build_cl_url(&n.url, request.uri()), tier)
+            })
+            .collect()
+    };
+
+    if targets.is_empty() {
+        warn!("No healthy CL node available");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No healthy CL node available",
+        )
+            .into_response();
+    }
+
+    // Forward the headers beacon nodes need to decode the published object
+    let headers: Vec<(String, String)> = ["content-type", "eth-consensus-version"]
+        .iter()
+        .filter_map(|name| {
+            let value = request.headers().get(*name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
         })
+        .collect();
+
+    let body = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!(error = %e, "Failed to read request body");
+            return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+        }
+    };
+
+    debug!(targets = targets.len(), "Broadcasting CL request");
+
+    let timeout = Duration::from_millis(state.cl_broadcast.timeout_ms);
+    let (tx, mut rx) = mpsc::channel(targets.len());
+    for (name, url, tier) in targets {
+        let client = state.http_client.clone();
+        let body = body.clone();
+        let headers = headers.clone();
+        let tx = tx.clone();
+        tokio::spawn(telemetry::propagate(async move {
+            let start = Instant::now();
+            let mut forward_request = client
+                .post(&url)
+                .timeout(timeout)
+                .headers(telemetry::upstream_headers())
+                .body(body);
+            for (name, value) in headers {
+                forward_request = forward_request.header(name, value);
+            }
+            let result = match forward_request.send().await {
+                Ok(response) => {
+                    let status = StatusCode::from_u16(response.status().as_u16())
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                    response.bytes().await.map(|bytes| (status, bytes))
+                }
+                Err(e) => Err(e),
+            };
+            let outcome = match &result {
+                Ok((status, _)) if status.is_success() => "accepted",
+                Ok(_) => "rejected",
+                Err(e) => {
+                    warn!(node = %name, error = %e, "Broadcast request failed");
+                    "failed"
+                }
+            };
+            VixyMetrics::inc_cl_broadcast_result(&name, outcome);
+            VixyMetrics::inc_cl_requests(&name, tier);
+            VixyMetrics::observe_cl_duration(&name, tier, start.elapsed().as_secs_f64());
+            debug!(node = %name, outcome, "Broadcast result");
+            let _ = tx.send(result).await;
+        }));
+    }
+    drop(tx);
+
+    let mut first_success = None;
+    let mut first_failure = None;
+    while let Some(result) = rx.recv().await {
+        match result {
+            Ok((status, bytes)) if status.is_success() => {
+                if state.cl_broadcast.policy == BroadcastPolicy::FirstSuccess {
+                    return (status, bytes).into_response();
+                }
+                first_success.get_or_insert((status, bytes));
+            }
+            Ok((status, bytes)) => {
+                first_failure.get_or_insert((status, bytes));
+            }
+            Err(_) => {
+                first_failure.get_or_insert((
+                    StatusCode::BAD_GATEWAY,
+                    Bytes::from_static(b"Upstream request failed"),
+                ));
+            }
+        }
     }
 
-    fn make_el_node(name: &str, url: &str, is_healthy: bool) -> ElNodeState {
-        ElNodeState {
-            name: name.to_string(),
-            http_url: url.to_string(),
-            ws_url: url.to_string(),
-            is_primary: true,
-            block_number: 1000,
-            check_ok: is_healthy,
-            is_healthy,
-            lag: 0,
-            consecutive_failures: 0,
+    // FirstSuccess only gets here if no node accepted; AllSuccess fails on any rejection
+    match first_failure.or(first_success) {
+        Some((status, bytes)) => (status, bytes).into_response(),
+        None => (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response(),
+    }
+}
+
+/// Forward a request to a target URL
+async fn forward_request(
+    client: &reqwest::Client,
+    request: Request<Body>,
+    target_url: &str,
+) -> Response {
+    // Extract method, headers, and body
+    let method = request.method().clone();
+    let content_type = request
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    let body_bytes = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!(error = %e, "Failed to read request body");
+            return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+        }
+    };
+
+    // Build the forwarded request with Content-Type header
+    let mut forward_request = client
+        .request(method, target_url)
+        .headers(telemetry::upstream_headers());
+    if let Some(ct) = content_type {
+        forward_request = forward_request.header("content-type", ct);
+    }
+    forward_request = forward_request.body(body_bytes);
+
+    // Send the request
+    match forward_request.send().await {
+        Ok(response) => convert_response(response).await,
+        Err(e) => {
+            if e.is_timeout() {
+                warn!(error = %e, "Proxy request timed out");
+                return (StatusCode::GATEWAY_TIMEOUT, "Request timed out").into_response();
+            }
+            warn!(error = %e, "Proxy request failed");
+            (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response()
+        }
+    }
+}
+
+/// Forward a request to a specific URL (used for CL with path construction)
+async fn forward_request_to_url(
+    client: &reqwest::Client,
+    request: Request<Body>,
+    target_url: &str,
+) -> Response {
+    // Extract method, headers, and body
+    let method = request.method().clone();
+    let content_type = request
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    let body_bytes = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!(error = %e, "Failed to read request body");
+            return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
+        }
+    };
+
+    // Build the forwarded request with Content-Type header
+    let mut forward_request = client
+        .request(method, target_url)
+        .headers(telemetry::upstream_headers());
+    if let Some(ct) = content_type {
+        forward_request = forward_request.header("content-type", ct);
+    }
+    if !body_bytes.is_empty() {
+        forward_request = forward_request.body(body_bytes);
+    }
+
+    // Send the request
+    match forward_request.send().await {
+        Ok(response) => convert_response(response).await,
+        Err(e) => {
+            if e.is_timeout() {
+                warn!(error = %e, "Proxy request timed out");
+                return (StatusCode::GATEWAY_TIMEOUT, "Request timed out").into_response();
+            }
+            warn!(error = %e, "Proxy request failed");
+            (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response()
+        }
+    }
+}
+
+/// Convert a reqwest response to an axum response
+pub(crate) async fn convert_response(response: reqwest::Response) -> Response {
+    let status = StatusCode::from_u16(response.status().as_u16())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+    match response.bytes().await {
+        Ok(bytes) => (status, bytes.to_vec()).into_response(),
+        Err(e) => {
+            warn!(error = %e, "Failed to read response body");
+            (StatusCode::BAD_GATEWAY, "Failed to read upstream response").into_response()
+        }
+    }
+}
+
+// ============================================================================
+// Status endpoint
+// ============================================================================
+
+/// EL node status for JSON response
+#[derive(Debug, Serialize)]
+pub struct ElNodeStatus {
+    pub name: String,
+    pub http_url: String,
+    pub is_primary: bool,
+    pub block_number: u64,
+    pub lag: u64,
+    pub check_ok: bool,
+    pub is_healthy: bool,
+    pub is_ejected: bool,
+    pub check_latency_ms: u64,
+    pub flaps: u32,
+}
+
+/// CL node status for JSON response
+#[derive(Debug, Serialize)]
+pub struct ClNodeStatus {
+    pub name: String,
+    pub url: String,
+    pub is_primary: bool,
+    pub slot: u64,
+    pub lag: u64,
+    pub health_ok: bool,
+    pub is_healthy: bool,
+    pub is_ejected: bool,
+    pub check_latency_ms: u64,
+    pub flaps: u32,
+}
+
+/// Full status response
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    pub el_chain_head: u64,
+    pub cl_chain_head: u64,
+    pub el_failover_active: bool,
+    pub cl_failover_active: bool,
+    pub el_nodes: Vec<ElNodeStatus>,
+    pub cl_nodes: Vec<ClNodeStatus>,
+}
+
+/// Handle status requests (GET /status)
+///
+/// Returns JSON with all node health states
+pub async fn status_handler(State(state): State<Arc<AppState>>) -> Json<StatusResponse> {
+    let el_chain_head = state.el_chain_head.load(Ordering::SeqCst);
+    let cl_chain_head = state.cl_chain_head.load(Ordering::SeqCst);
+    let el_failover_active = state.el_failover_active.load(Ordering::SeqCst);
+    let cl_failover_active = state.cl_failover_active.load(Ordering::SeqCst);
+
+    // Collect EL node statuses
+    let el_nodes = {
+        let nodes = state.el_nodes.read().await;
+        nodes
+            .iter()
+            .map(|n| ElNodeStatus {
+                name: n.name.clone(),
+                http_url: n.http_url.clone(),
+                is_primary: n.is_primary,
+                block_number: n.block_number,
+                lag: n.lag,
+                check_ok: n.check_ok,
+                is_healthy: n.is_healthy,
+                is_ejected: n.is_ejected(),
+                check_latency_ms: n.check_latency_ms,
+                flaps: n.flaps,
+            })
+            .collect()
+    };
+
+    // Collect CL node statuses
+    let cl_nodes = {
+        let nodes = state.cl_nodes.read().await;
+        nodes
+            .iter()
+            .map(|n| ClNodeStatus {
+                name: n.name.clone(),
+                url: n.url.clone(),
+                is_primary: n.is_primary,
+                slot: n.slot,
+                lag: n.lag,
+                health_ok: n.health_ok,
+                is_healthy: n.is_healthy,
+                is_ejected: n.is_ejected(),
+                check_latency_ms: n.check_latency_ms,
+                flaps: n.flaps,
+            })
+            .collect()
+    };
+
+    Json(StatusResponse {
+        el_chain_head,
+        cl_chain_head,
+        el_failover_active,
+        cl_failover_active,
+        el_nodes,
+        cl_nodes,
+    })
+}
+
+/// Handle health event requests (GET /status/events)
+///
+/// Returns recent health events as JSON, or streams them as server-sent events when
+/// the client accepts `text/event-stream` (resuming after `Last-Event-ID` on reconnect)
+pub async fn events_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    let wants_stream = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/event-stream"));
+    if !wants_stream {
+        return Json(state.events.recent()).into_response();
+    }
+
+    let last_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+    let stream = state.events.stream(last_id).map(|event| {
+        SseEvent::default()
+            .id(event.id.to_string())
+            .json_data(&event)
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ClNodeState, ElNodeState};
+    use axum::Router;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+    use tower::util::ServiceExt;
+    use wiremock::matchers::{body_string, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    // Helper to create minimal AppState for testing
+    fn create_test_state(el_nodes: Vec<ElNodeState>, cl_nodes: Vec<ClNodeState>) -> Arc<AppState> {
+        Arc::new(AppState {
+            el_nodes: Arc::new(RwLock::new(el_nodes)),
+            cl_nodes: Arc::new(RwLock::new(cl_nodes)),
+            el_chain_head: std::sync::atomic::AtomicU64::new(0),
+            cl_chain_head: std::sync::atomic::AtomicU64::new(0),
+            el_failover_active: std::sync::atomic::AtomicBool::new(false),
+            cl_failover_active: std::sync::atomic::AtomicBool::new(false),
+            el_failover_since: std::sync::Mutex::new(None),
+            cl_failover_since: std::sync::Mutex::new(None),
+            el_all_down: std::sync::atomic::AtomicBool::new(false),
+            cl_all_down: std::sync::atomic::AtomicBool::new(false),
+            el_head_progress: std::sync::Mutex::new(crate::state::HeadProgress::new(0)),
+            cl_head_progress: std::sync::Mutex::new(crate::state::HeadProgress::new(0)),
+            chain_stall_timeout: Duration::ZERO,
+            el_routes: vec![],
+            el_method_filter: crate::config::MethodFilter::default(),
+            el_broadcast: crate::config::Broadcast::default(),
+            cl_broadcast: crate::config::ClBroadcast::default(),
+            cl_routes: vec![],
+            el_cache: None,
+            coalescer: None,
+            el_hedger: None,
+            el_retry_rules: crate::config::Retry::default().rules,
+            auth: None,
+            events: crate::events::EventLog::new(100),
+            outlier_detector: None,
+            el_metric_methods: crate::config::Metrics::default()
+                .el_methods
+                .into_iter()
+                .collect(),
+            max_el_lag: 5,
+            max_cl_lag: 3,
+            proxy_timeout_ms: 30000,
+            http_client: reqwest::Client::new(),
+            stream_client: reqwest::Client::new(),
+            max_retries: 2,
+            health_check_timeout: Duration::from_secs(1),
+            health_check_max_failures: 3,
+            health_check_recovery_successes: 1,
+            failover_min_dwell: Duration::ZERO,
+            max_batch_size: 1000,
+            split_batches: false,
+        })
+    }
+
+    fn make_el_node(name: &str, url: &str, is_healthy: bool) -> ElNodeState {
+        ElNodeState {
+            name: name.to_string(),
+            http_url: url.to_string(),
+            ws_url: url.to_string(),
+            is_primary: true,
+            tags: vec![],
+            block_number: 1000,
+            check_ok: is_healthy,
+            is_healthy,
+            lag: 0,
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
     fn make_cl_node(name: &str, url: &str, is_healthy: bool) -> ClNodeState {
         ClNodeState {
             name: name.to_string(),
+            is_primary: true,
+            tags: vec![],
             url: url.to_string(),
             slot: 5000,
             health_ok: is_healthy,
             is_healthy,
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
-    // =========================================================================
-    // EL proxy tests
-    // =========================================================================
+    // =========================================================================
+    // EL proxy tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_el_proxy_forwards_request() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(body_string(
+                r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#,
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": "0x10d4f",
+                "id": 1
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let app = Router::new()
+            .route("/el", axum::routing::post(el_proxy_handler))
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#,
+            ))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["result"], "0x10d4f");
+    }
+
+    #[tokio::test]
+    async fn test_el_request_id_is_forwarded_upstream() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(header("x-request-id", "client-req-7"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": "0x10d4f",
+                "id": 1
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let app = Router::new()
+            .route("/el", axum::routing::post(el_proxy_handler))
+            .layer(axum::middleware::from_fn(crate::request_id::propagate))
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .header("content-type", "application/json")
+            .header("x-request-id", "client-req-7")
+            .body(Body::from(
+                r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#,
+            ))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-request-id"], "client-req-7");
+    }
+
+    #[tokio::test]
+    async fn test_el_proxy_returns_503_no_healthy_nodes() {
+        let el_nodes = vec![make_el_node("geth-1", "http://localhost:8545", false)]; // unhealthy
+        let state = create_test_state(el_nodes, vec![]);
+
+        let app = Router::new()
+            .route("/el", axum::routing::post(el_proxy_handler))
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .body(Body::from("{}"))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    // =========================================================================
+    // EL batch tests
+    // =========================================================================
+
+    async fn post_el(state: Arc<AppState>, body: &str) -> (StatusCode, serde_json::Value) {
+        let app = Router::new()
+            .route("/el", axum::routing::post(el_proxy_handler))
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_el_batch_exceeding_limit_is_rejected() {
+        let el_nodes = vec![make_el_node("geth-1", "http://localhost:8545", true)];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().max_batch_size = 2;
+
+        let (status, json) = post_el(
+            state,
+            r#"[{"jsonrpc":"2.0","method":"eth_chainId","id":1},
+                {"jsonrpc":"2.0","method":"eth_chainId","id":2},
+                {"jsonrpc":"2.0","method":"eth_chainId","id":3}]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["error"]["code"], jsonrpc::INVALID_REQUEST);
+        assert_eq!(json["id"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_el_empty_batch_is_rejected() {
+        let el_nodes = vec![make_el_node("geth-1", "http://localhost:8545", true)];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let (status, json) = post_el(state, "[]").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["error"]["code"], jsonrpc::INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_el_batch_split_across_nodes_keeps_order() {
+        let mock1 = MockServer::start().await;
+        let mock2 = MockServer::start().await;
+
+        // Each node answers its sub-batch in reverse order to exercise id matching
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"jsonrpc": "2.0", "id": 3, "result": "node1-c"},
+                {"jsonrpc": "2.0", "id": 1, "result": "node1-a"}
+            ])))
+            .expect(1)
+            .mount(&mock1)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"jsonrpc": "2.0", "id": 2, "result": "node2-b"}
+            ])))
+            .expect(1)
+            .mount(&mock2)
+            .await;
+
+        let el_nodes = vec![
+            make_el_node("geth-1", &mock1.uri(), true),
+            make_el_node("geth-2", &mock2.uri(), true),
+        ];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().split_batches = true;
+
+        let (status, json) = post_el(
+            state,
+            r#"[{"jsonrpc":"2.0","method":"eth_chainId","id":1},
+                {"jsonrpc":"2.0","method":"eth_chainId","id":2},
+                {"jsonrpc":"2.0","method":"eth_chainId","id":3}]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let responses = json.as_array().expect("Should return a batch response");
+        let results: Vec<&str> = responses
+            .iter()
+            .map(|r| r["result"].as_str().unwrap())
+            .collect();
+        assert_eq!(results, vec!["node1-a", "node2-b", "node1-c"]);
+    }
+
+    #[tokio::test]
+    async fn test_el_batch_split_reports_missing_responses() {
+        let mock1 = MockServer::start().await;
+
+        // Node answers with an empty array, dropping the call
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .mount(&mock1)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock1.uri(), true)];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().split_batches = true;
+
+        let (status, json) = post_el(
+            state,
+            r#"[{"jsonrpc":"2.0","method":"eth_chainId","id":"a"}]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json[0]["id"], "a");
+        assert_eq!(json[0]["error"]["code"], jsonrpc::INTERNAL_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_el_batch_split_retries_calls_on_another_node() {
+        let behind_mock = mount_el_response(serde_json::json!([header_not_found()]), 1).await;
+        // Answers the call assigned to it, then the call retried from the behind node
+        let synced_mock = mount_el_response(
+            serde_json::json!([
+                {"jsonrpc": "2.0", "id": 1, "result": "0x1"},
+                {"jsonrpc": "2.0", "id": 2, "result": "0x2"}
+            ]),
+            2,
+        )
+        .await;
+
+        let el_nodes = vec![
+            make_el_node("behind-1", &behind_mock.uri(), true),
+            make_el_node("synced-1", &synced_mock.uri(), true),
+        ];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().split_batches = true;
+
+        let (status, json) = post_el(
+            state,
+            r#"[{"jsonrpc":"2.0","method":"eth_getBalance","params":["0x0","latest"],"id":1},
+                {"jsonrpc":"2.0","method":"eth_getBalance","params":["0x1","latest"],"id":2}]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json[0]["result"], "0x1");
+        assert_eq!(json[1]["result"], "0x2");
+    }
+
+    #[tokio::test]
+    async fn test_el_method_routed_to_tagged_node() {
+        let full_mock = MockServer::start().await;
+        let archive_mock = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "full"
+            })))
+            .mount(&full_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "archive"
+            })))
+            .mount(&archive_mock)
+            .await;
+
+        let mut archive = make_el_node("archive-1", &archive_mock.uri(), true);
+        archive.tags = vec!["archive".to_string()];
+        let el_nodes = vec![make_el_node("full-1", &full_mock.uri(), true), archive];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().el_routes = vec![MethodRoute {
+            methods: vec!["debug_*".to_string()],
+            tag: "archive".to_string(),
+        }];
+
+        let (_, json) = post_el(
+            state.clone(),
+            r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x1"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"], "archive");
+
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"], "full");
+    }
+
+    #[tokio::test]
+    async fn test_only_selectable_primary_is_not_ejected() {
+        let mut el_backup = make_el_node("geth-backup", "http://127.0.0.1:1", true);
+        el_backup.is_primary = false;
+        let mut cl_backup = make_cl_node("lighthouse-backup", "http://127.0.0.1:1", true);
+        cl_backup.is_primary = false;
+        let mut state = create_test_state(
+            vec![
+                make_el_node("geth-1", "http://127.0.0.1:1", true),
+                el_backup,
+            ],
+            vec![
+                make_cl_node("lighthouse-1", "http://127.0.0.1:1", true),
+                cl_backup,
+            ],
+        );
+        Arc::get_mut(&mut state).unwrap().outlier_detector = Some(
+            crate::proxy::outlier::OutlierDetector::new(&crate::config::OutlierDetection {
+                enabled: true,
+                min_requests: 2,
+                ..Default::default()
+            }),
+        );
+        let detector = state.outlier_detector.as_ref().unwrap();
+
+        // Failover is not active, so the backups can't take over from the primaries
+        for _ in 0..3 {
+            record_el_outcome(&state, detector, "geth-1", "primary", true).await;
+            record_cl_outcome(&state, detector, "lighthouse-1", "primary", true).await;
+        }
+
+        assert!(state.el_nodes.read().await[0].is_healthy);
+        assert!(state.cl_nodes.read().await[0].is_healthy);
+    }
+
+    #[tokio::test]
+    async fn test_el_failing_node_is_ejected() {
+        let failing_mock = MockServer::start().await;
+        let good_mock = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "error": {"code": -32603, "message": "internal error"}
+            })))
+            .expect(2)
+            .mount(&failing_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x1"
+            })))
+            .mount(&good_mock)
+            .await;
+
+        let el_nodes = vec![
+            make_el_node("failing-1", &failing_mock.uri(), true),
+            make_el_node("good-1", &good_mock.uri(), true),
+        ];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().outlier_detector = Some(
+            crate::proxy::outlier::OutlierDetector::new(&crate::config::OutlierDetection {
+                enabled: true,
+                min_requests: 2,
+                ..Default::default()
+            }),
+        );
+
+        let body = r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#;
+        for _ in 0..2 {
+            let (_, json) = post_el(state.clone(), body).await;
+            assert_eq!(json["error"]["code"], -32603);
+        }
+
+        // The failing node is out of rotation, traffic moves to the next node
+        let (_, json) = post_el(state.clone(), body).await;
+        assert_eq!(json["result"], "0x1");
+
+        let nodes = state.el_nodes.read().await;
+        assert!(!nodes[0].is_healthy);
+        assert!(nodes[0].is_ejected());
+        assert_eq!(nodes[0].flaps, 1);
+
+        // The ejection shows up as a health transition
+        let events = state.events.recent();
+        assert!(matches!(
+            &events[..],
+            [crate::events::Event {
+                kind: EventKind::NodeHealth {
+                    node,
+                    new_state: HealthState::Unhealthy,
+                    reason: "ejected",
+                    ..
+                },
+                ..
+            }] if node == "failing-1"
+        ));
+
+        // The last healthy node is never ejected
+        drop(nodes);
+        for _ in 0..3 {
+            record_el_outcome(
+                &state,
+                state.outlier_detector.as_ref().unwrap(),
+                "good-1",
+                "primary",
+                true,
+            )
+            .await;
+        }
+        assert!(state.el_nodes.read().await[1].is_healthy);
+    }
+
+    fn with_hedging(mut state: Arc<AppState>) -> Arc<AppState> {
+        Arc::get_mut(&mut state).unwrap().el_hedger = Some(Hedger::new(&crate::config::Hedging {
+            enabled: true,
+            min_delay_ms: 50,
+            max_delay_ms: 50,
+            ..Default::default()
+        }));
+        state
+    }
+
+    async fn mount_el_result(result: &str, delay: Duration, expected: u64) -> MockServer {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0", "id": 1, "result": result
+                    }))
+                    .set_delay(delay),
+            )
+            .expect(expected)
+            .mount(&mock_server)
+            .await;
+        mock_server
+    }
+
+    #[tokio::test]
+    async fn test_el_slow_call_is_hedged() {
+        let slow_mock = mount_el_result("slow", Duration::from_secs(5), 1).await;
+        let fast_mock = mount_el_result("fast", Duration::ZERO, 1).await;
+
+        let el_nodes = vec![
+            make_el_node("slow-1", &slow_mock.uri(), true),
+            make_el_node("fast-1", &fast_mock.uri(), true),
+        ];
+        let state = with_hedging(create_test_state(el_nodes, vec![]));
+
+        let started = Instant::now();
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_call","params":[{},"latest"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"], "fast");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_el_hedge_only_sent_for_slow_hedgeable_calls() {
+        let first_mock = mount_el_result("first", Duration::ZERO, 2).await;
+        let second_mock = mount_el_result("second", Duration::ZERO, 0).await;
+
+        let el_nodes = vec![
+            make_el_node("first-1", &first_mock.uri(), true),
+            make_el_node("second-1", &second_mock.uri(), true),
+        ];
+        let state = with_hedging(create_test_state(el_nodes, vec![]));
+
+        // Answered before the hedge delay
+        let (_, json) = post_el(
+            state.clone(),
+            r#"{"jsonrpc":"2.0","method":"eth_call","params":[{},"latest"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"], "first");
+
+        // Not a hedged method
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_sendRawTransaction","params":["0x1"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"], "first");
+    }
+
+    async fn mount_el_response(response: serde_json::Value, expected: u64) -> MockServer {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .expect(expected)
+            .mount(&mock_server)
+            .await;
+        mock_server
+    }
+
+    fn header_not_found() -> serde_json::Value {
+        serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "header not found"}
+        })
+    }
+
+    #[tokio::test]
+    async fn test_el_header_not_found_is_retried_on_another_node() {
+        let behind_mock = mount_el_response(header_not_found(), 1).await;
+        let synced_mock = mount_el_response(
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}),
+            1,
+        )
+        .await;
+
+        let el_nodes = vec![
+            make_el_node("behind-1", &behind_mock.uri(), true),
+            make_el_node("synced-1", &synced_mock.uri(), true),
+        ];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_getBalance","params":["0x0","0x3e8"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"], "0x1");
+    }
+
+    #[tokio::test]
+    async fn test_el_null_block_is_retried_on_another_node() {
+        let behind_mock = mount_el_response(
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": null}),
+            1,
+        )
+        .await;
+        let synced_mock = mount_el_response(
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"number": "0x3e8"}}),
+            1,
+        )
+        .await;
+
+        let el_nodes = vec![
+            make_el_node("behind-1", &behind_mock.uri(), true),
+            make_el_node("synced-1", &synced_mock.uri(), true),
+        ];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["0x3e8",false],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["result"]["number"], "0x3e8");
+    }
+
+    #[tokio::test]
+    async fn test_el_non_idempotent_call_is_not_retried() {
+        let first_mock = mount_el_response(header_not_found(), 1).await;
+        let second_mock = mount_el_response(header_not_found(), 0).await;
+
+        let el_nodes = vec![
+            make_el_node("first-1", &first_mock.uri(), true),
+            make_el_node("second-1", &second_mock.uri(), true),
+        ];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_sendRawTransaction","params":["0x1"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["error"]["message"], "header not found");
+    }
+
+    #[tokio::test]
+    async fn test_el_retries_stop_at_max_retries() {
+        let mocks = [
+            mount_el_response(header_not_found(), 1).await,
+            mount_el_response(header_not_found(), 1).await,
+            mount_el_response(header_not_found(), 1).await,
+            mount_el_response(header_not_found(), 0).await,
+        ];
+
+        let el_nodes = mocks
+            .iter()
+            .enumerate()
+            .map(|(i, mock)| make_el_node(&format!("geth-{i}"), &mock.uri(), true))
+            .collect();
+        let state = create_test_state(el_nodes, vec![]);
+
+        // max_retries = 2: the first node plus two retries
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_call","params":[{},"latest"],"id":1}"#,
+        )
+        .await;
+        assert_eq!(json["error"]["code"], -32000);
+    }
+
+    #[tokio::test]
+    async fn test_el_outcome() {
+        let outcome = |status: StatusCode, body: &'static str| async move {
+            el_outcome((status, body).into_response()).await.1
+        };
+
+        assert_eq!(
+            outcome(StatusCode::OK, r#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#).await,
+            "ok"
+        );
+        assert_eq!(
+            outcome(
+                StatusCode::OK,
+                r#"[{"id":1,"result":"0x1"},{"id":2,"error":{"code":-32000,"message":"x"}}]"#
+            )
+            .await,
+            "jsonrpc_error"
+        );
+        assert_eq!(
+            outcome(StatusCode::BAD_GATEWAY, "Upstream request failed").await,
+            "http_error"
+        );
+        assert_eq!(
+            outcome(StatusCode::GATEWAY_TIMEOUT, "Request timed out").await,
+            "timeout"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_el_block_request_skips_lagging_node() {
+        let lagging_mock = MockServer::start().await;
+        let synced_mock = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": null
+            })))
+            .expect(0)
+            .mount(&lagging_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": {"number": "0x3e9"}
+            })))
+            .mount(&synced_mock)
+            .await;
+
+        // First node is at 1000, second at 1001; request asks for 1001 (0x3e9)
+        let mut synced = make_el_node("geth-2", &synced_mock.uri(), true);
+        synced.block_number = 1001;
+        let el_nodes = vec![make_el_node("geth-1", &lagging_mock.uri(), true), synced];
+        let state = create_test_state(el_nodes, vec![]);
+
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["0x3e9",false],"id":1}"#,
+        )
+        .await;
+
+        assert_eq!(json["result"]["number"], "0x3e9");
+    }
+
+    fn with_cache(mut state: Arc<AppState>) -> Arc<AppState> {
+        Arc::get_mut(&mut state).unwrap().el_cache = Some(crate::proxy::cache::ResponseCache::new(
+            &crate::config::Cache {
+                enabled: true,
+                ..Default::default()
+            },
+        ));
+        state
+    }
+
+    #[tokio::test]
+    async fn test_el_cache_serves_repeated_call() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x1"
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = with_cache(create_test_state(el_nodes, vec![]));
+
+        post_el(
+            state.clone(),
+            r#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":1}"#,
+        )
+        .await;
+        let (status, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":"second"}"#,
+        )
+        .await;
+
+        // Cached result is returned with the client's own id
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["id"], "second");
+        assert_eq!(json["result"], "0x1");
+    }
+
+    #[tokio::test]
+    async fn test_el_cache_bypass_header() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x1"
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = with_cache(create_test_state(el_nodes, vec![]));
+        let body = r#"{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":1}"#;
+
+        post_el(state.clone(), body).await;
+
+        let app = Router::new()
+            .route("/el", axum::routing::post(el_proxy_handler))
+            .with_state(state);
+        let request = Request::builder()
+            .method("POST")
+            .uri("/el")
+            .header("content-type", "application/json")
+            .header("cache-control", "no-cache")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_el_cache_skips_head_result_from_lagging_node() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x3de"
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut lagging = make_el_node("geth-1", &mock_server.uri(), true);
+        lagging.block_number = 990;
+        lagging.lag = 10;
+        let state = with_cache(create_test_state(vec![lagging], vec![]));
+        state.el_chain_head.store(1000, Ordering::SeqCst);
+        let body = r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#;
+
+        // Both calls go upstream: the first answer is behind the chain head
+        post_el(state.clone(), body).await;
+        let (status, json) = post_el(state, body).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["result"], "0x3de");
+    }
+
+    #[tokio::test]
+    async fn test_el_concurrent_identical_calls_are_coalesced() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0", "id": 1, "result": {"number": "0x3e8"}
+                    }))
+                    .set_delay(std::time::Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().coalescer =
+            Some(crate::proxy::coalesce::Coalescer::new());
+
+        let ((_, first), (_, second)) = tokio::join!(
+            post_el(
+                state.clone(),
+                r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["latest",false],"id":1}"#,
+            ),
+            post_el(
+                state.clone(),
+                r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["latest",false],"id":2}"#,
+            ),
+        );
+
+        // Each caller gets the shared result under its own id
+        let mut ids = [first["id"].as_i64(), second["id"].as_i64()];
+        ids.sort();
+        assert_eq!(ids, [Some(1), Some(2)]);
+        assert_eq!(first["result"], second["result"]);
+    }
 
     #[tokio::test]
-    async fn test_el_proxy_forwards_request() {
+    async fn test_el_no_cache_call_is_not_coalesced() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("POST"))
-            .and(body_string(
-                r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#,
-            ))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "jsonrpc": "2.0",
-                "result": "0x10d4f",
-                "id": 1
-            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0", "id": 1, "result": {"number": "0x3e8"}
+                    }))
+                    .set_delay(std::time::Duration::from_millis(200)),
+            )
+            .expect(2)
             .mount(&mock_server)
             .await;
 
         let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
-        let state = create_test_state(el_nodes, vec![]);
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().coalescer =
+            Some(crate::proxy::coalesce::Coalescer::new());
 
+        let body =
+            r#"{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["latest",false],"id":1}"#;
         let app = Router::new()
             .route("/el", axum::routing::post(el_proxy_handler))
-            .with_state(state);
-
+            .with_state(state.clone());
         let request = Request::builder()
             .method("POST")
             .uri("/el")
             .header("content-type", "application/json")
-            .body(Body::from(
-                r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#,
-            ))
+            .header("cache-control", "no-cache")
+            .body(Body::from(body))
             .unwrap();
 
-        let response = app.oneshot(request).await.unwrap();
+        let (fresh, _) = tokio::join!(app.oneshot(request), post_el(state, body));
+        assert_eq!(fresh.unwrap().status(), StatusCode::OK);
+    }
 
-        assert_eq!(response.status(), StatusCode::OK);
+    fn with_denied_methods(mut state: Arc<AppState>, deny: &[&str]) -> Arc<AppState> {
+        Arc::get_mut(&mut state).unwrap().el_method_filter = crate::config::MethodFilter {
+            allow: vec![],
+            deny: deny.iter().map(|m| m.to_string()).collect(),
+        };
+        state
+    }
 
-        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["result"], "0x10d4f");
+    #[tokio::test]
+    async fn test_el_denied_method_is_rejected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = with_denied_methods(create_test_state(el_nodes, vec![]), &["admin_*"]);
+
+        let (status, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"admin_peers","params":[],"id":7}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["error"]["code"], jsonrpc::METHOD_NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_el_proxy_returns_503_no_healthy_nodes() {
-        let el_nodes = vec![make_el_node("geth-1", "http://localhost:8545", false)]; // unhealthy
-        let state = create_test_state(el_nodes, vec![]);
+    async fn test_el_batch_with_denied_method_forwards_allowed_calls() {
+        let mock_server = MockServer::start().await;
 
-        let app = Router::new()
-            .route("/el", axum::routing::post(el_proxy_handler))
-            .with_state(state);
+        Mock::given(method("POST"))
+            .and(body_string(
+                r#"[{"id":1,"jsonrpc":"2.0","method":"eth_chainId","params":[]}]"#,
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"jsonrpc": "2.0", "id": 1, "result": "0x1"}
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
 
-        let request = Request::builder()
-            .method("POST")
-            .uri("/el")
-            .body(Body::from("{}"))
-            .unwrap();
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = with_denied_methods(create_test_state(el_nodes, vec![]), &["admin_*"]);
+
+        let (status, json) = post_el(
+            state,
+            r#"[{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":1},{"jsonrpc":"2.0","method":"admin_peers","params":[],"id":2}]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let responses = json.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["result"], "0x1");
+        assert_eq!(responses[1]["id"], 2);
+        assert_eq!(responses[1]["error"]["code"], jsonrpc::METHOD_NOT_FOUND);
+    }
 
-        let response = app.oneshot(request).await.unwrap();
+    #[tokio::test]
+    async fn test_el_batch_rejections_keep_call_order() {
+        let mock_server = MockServer::start().await;
 
-        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        // Upstream answers the forwarded calls out of order
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {"jsonrpc": "2.0", "id": 3, "result": "0x3e8"},
+                {"jsonrpc": "2.0", "id": 2, "result": "0x1"}
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let state = with_denied_methods(create_test_state(el_nodes, vec![]), &["admin_*"]);
+
+        let (status, json) = post_el(
+            state,
+            r#"[{"jsonrpc":"2.0","method":"admin_peers","params":[],"id":1},{"jsonrpc":"2.0","method":"eth_chainId","params":[],"id":2},{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":3}]"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let ids: Vec<&Value> = json.as_array().unwrap().iter().map(|r| &r["id"]).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(json[0]["error"]["code"], jsonrpc::METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_el_broadcast_returns_first_success() {
+        let rejecting_mock = MockServer::start().await;
+        let accepting_mock = MockServer::start().await;
+        let extra_mock = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "error": {"code": -32000, "message": "already known"}
+            })))
+            .expect(1)
+            .mount(&rejecting_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0xhash"
+            })))
+            .expect(1)
+            .mount(&accepting_mock)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0xhash"
+            })))
+            .expect(1)
+            .mount(&extra_mock)
+            .await;
+
+        let el_nodes = vec![
+            make_el_node("geth-1", &rejecting_mock.uri(), true),
+            make_el_node("geth-2", &accepting_mock.uri(), true),
+        ];
+        let mut state = create_test_state(el_nodes, vec![]);
+        let broadcast = &mut Arc::get_mut(&mut state).unwrap().el_broadcast;
+        broadcast.enabled = true;
+        broadcast.extra_endpoints = vec![crate::config::BroadcastEndpoint {
+            name: "relay".to_string(),
+            http_url: extra_mock.uri(),
+        }];
+
+        let (status, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_sendRawTransaction","params":["0x02"],"id":1}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["result"], "0xhash");
+
+        // Give the background broadcasts time to reach every endpoint
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    #[tokio::test]
+    async fn test_el_broadcast_returns_rejection_when_none_accept() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "error": {"code": -32000, "message": "nonce too low"}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let el_nodes = vec![make_el_node("geth-1", &mock_server.uri(), true)];
+        let mut state = create_test_state(el_nodes, vec![]);
+        Arc::get_mut(&mut state).unwrap().el_broadcast.enabled = true;
+
+        let (_, json) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_sendRawTransaction","params":["0x02"],"id":1}"#,
+        )
+        .await;
+
+        assert_eq!(json["error"]["message"], "nonce too low");
+    }
+
+    #[tokio::test]
+    async fn test_el_broadcast_gives_up_after_timeout() {
+        let slow_mock = mount_el_result("0xhash", Duration::from_secs(5), 1).await;
+
+        let el_nodes = vec![make_el_node("geth-1", &slow_mock.uri(), true)];
+        let mut state = create_test_state(el_nodes, vec![]);
+        let broadcast = &mut Arc::get_mut(&mut state).unwrap().el_broadcast;
+        broadcast.enabled = true;
+        broadcast.timeout_ms = 50;
+
+        let started = Instant::now();
+        let (status, _) = post_el(
+            state,
+            r#"{"jsonrpc":"2.0","method":"eth_sendRawTransaction","params":["0x02"],"id":1}"#,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_batch_routing_method_requires_uniform_tag() {
+        let routes = vec![MethodRoute {
+            methods: vec!["trace_*".to_string()],
+            tag: "archive".to_string(),
+        }];
+        let uniform = vec![
+            serde_json::json!({"method": "trace_block", "id": 1}),
+            serde_json::json!({"method": "trace_call", "id": 2}),
+        ];
+        let mixed = vec![
+            serde_json::json!({"method": "trace_block", "id": 1}),
+            serde_json::json!({"method": "eth_call", "id": 2}),
+        ];
+
+        assert_eq!(
+            batch_routing_method(&routes, &uniform),
+            Some("trace_block".to_string())
+        );
+        assert_eq!(batch_routing_method(&routes, &mixed), None);
     }
 
     // =========================================================================
@@ -476,6 +2953,50 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_cl_reads_are_coalesced_per_representation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v2/debug/beacon/states/head"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"data": {}}))
+                    .set_delay(std::time::Duration::from_millis(200)),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let cl_nodes = vec![make_cl_node("lighthouse-1", &mock_server.uri(), true)];
+        let mut state = create_test_state(vec![], cl_nodes);
+        Arc::get_mut(&mut state).unwrap().coalescer =
+            Some(crate::proxy::coalesce::Coalescer::new());
+        let app = Router::new()
+            .route("/cl/{*path}", axum::routing::get(cl_proxy_handler))
+            .with_state(state);
+
+        let get = |accept: &'static str| {
+            let request = Request::builder()
+                .method("GET")
+                .uri("/cl/eth/v2/debug/beacon/states/head")
+                .header("accept", accept)
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // The two JSON reads share one upstream call, the SSZ read gets its own
+        let (json, ssz, json_again) = tokio::join!(
+            get("application/json"),
+            get("application/octet-stream"),
+            get("application/json"),
+        );
+        assert_eq!(json.unwrap().status(), StatusCode::OK);
+        assert_eq!(ssz.unwrap().status(), StatusCode::OK);
+        assert_eq!(json_again.unwrap().status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_cl_proxy_preserves_path() {
         let mock_server = MockServer::start().await;
@@ -535,6 +3056,233 @@ mod tests {
         assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
 
+    #[tokio::test]
+    async fn test_cl_route_deny_returns_403() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let cl_nodes = vec![make_cl_node("lighthouse-1", &mock_server.uri(), true)];
+        let mut state = create_test_state(vec![], cl_nodes);
+        Arc::get_mut(&mut state).unwrap().cl_routes = vec![crate::config::ClRoute {
+            paths: vec!["/eth/v1/node/peers".to_string()],
+            methods: vec![],
+            action: ClRouteAction::Deny,
+            tag: None,
+        }];
+
+        let app = Router::new()
+            .route("/cl/{*path}", axum::routing::any(cl_proxy_handler))
+            .with_state(state);
+        let request = Request::builder()
+            .method("GET")
+            .uri("/cl/eth/v1/node/peers")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], 403);
+    }
+
+    #[tokio::test]
+    async fn test_cl_route_sends_request_to_tagged_node() {
+        let default_mock = MockServer::start().await;
+        let debug_mock = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&default_mock)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v2/debug/beacon/states/head"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&debug_mock)
+            .await;
+
+        let mut debug_node = make_cl_node("lighthouse-debug", &debug_mock.uri(), true);
+        debug_node.tags = vec!["debug".to_string()];
+        let cl_nodes = vec![
+            make_cl_node("lighthouse-1", &default_mock.uri(), true),
+            debug_node,
+        ];
+        let mut state = create_test_state(vec![], cl_nodes);
+        Arc::get_mut(&mut state).unwrap().cl_routes = vec![crate::config::ClRoute {
+            paths: vec!["/eth/v*/debug/**".to_string()],
+            methods: vec![],
+            action: ClRouteAction::Route,
+            tag: Some("debug".to_string()),
+        }];
+
+        let app = Router::new()
+            .route("/cl/{*path}", axum::routing::any(cl_proxy_handler))
+            .with_state(state);
+        let request = Request::builder()
+            .method("GET")
+            .uri("/cl/eth/v2/debug/beacon/states/head")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    async fn mount_event_stream(events: &[u64]) -> MockServer {
+        let mock_server = MockServer::start().await;
+        let body: String = events
+            .iter()
+            .map(|slot| {
+                format!("event: head\ndata: {{\"slot\":\"{slot}\",\"block\":\"0x{slot:02x}\"}}\n\n")
+            })
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/events"))
+            .and(wiremock::matchers::query_param("topics", "head"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(body.into_bytes(), "text/event-stream"),
+            )
+            .mount(&mock_server)
+            .await;
+        mock_server
+    }
+
+    #[tokio::test]
+    async fn test_cl_event_stream_fails_over_without_duplicates() {
+        use futures_util::StreamExt;
+
+        // The first node drops the stream after slot 2; the second replays slot 2
+        let first = mount_event_stream(&[1, 2]).await;
+        let second = mount_event_stream(&[2, 3]).await;
+
+        let cl_nodes = vec![
+            make_cl_node("lighthouse-1", &first.uri(), true),
+            make_cl_node("prysm-1", &second.uri(), true),
+        ];
+        let state = create_test_state(vec![], cl_nodes);
+
+        let app = Router::new()
+            .route("/cl/{*path}", axum::routing::any(cl_proxy_handler))
+            .with_state(state);
+        let request = Request::builder()
+            .method("GET")
+            .uri("/cl/eth/v1/events?topics=head")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        let mut body = response.into_body().into_data_stream();
+        let mut received = String::new();
+        while received.matches("event: head").count() < 3 {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), body.next())
+                .await
+                .expect("Timed out waiting for events")
+                .expect("Stream ended early")
+                .unwrap();
+            received.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+
+        let slots: Vec<&str> = received
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                r#"{"slot":"1","block":"0x01"}"#,
+                r#"{"slot":"2","block":"0x02"}"#,
+                r#"{"slot":"3","block":"0x03"}"#,
+            ]
+        );
+    }
+
+    async fn post_cl_block(state: Arc<AppState>) -> StatusCode {
+        let app = Router::new()
+            .route("/cl/{*path}", axum::routing::any(cl_proxy_handler))
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/cl/eth/v2/beacon/blocks")
+            .header("content-type", "application/json")
+            .header("eth-consensus-version", "deneb")
+            .body(Body::from(r#"{"message":{}}"#))
+            .unwrap();
+
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    async fn mount_block_publish(status: u16) -> MockServer {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/eth/v2/beacon/blocks"))
+            .and(wiremock::matchers::header("eth-consensus-version", "deneb"))
+            .respond_with(ResponseTemplate::new(status))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        mock_server
+    }
+
+    #[tokio::test]
+    async fn test_cl_broadcast_first_success() {
+        let failing = mount_block_publish(503).await;
+        let accepting = mount_block_publish(200).await;
+
+        let cl_nodes = vec![
+            make_cl_node("lighthouse-1", &failing.uri(), true),
+            make_cl_node("prysm-1", &accepting.uri(), true),
+        ];
+        let mut state = create_test_state(vec![], cl_nodes);
+        Arc::get_mut(&mut state).unwrap().cl_broadcast.enabled = true;
+
+        assert_eq!(post_cl_block(state).await, StatusCode::OK);
+
+        // Give the background broadcasts time to reach every node
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    #[tokio::test]
+    async fn test_cl_broadcast_all_success_reports_failure() {
+        let failing = mount_block_publish(400).await;
+        let accepting = mount_block_publish(200).await;
+
+        let cl_nodes = vec![
+            make_cl_node("lighthouse-1", &failing.uri(), true),
+            make_cl_node("prysm-1", &accepting.uri(), true),
+        ];
+        let mut state = create_test_state(vec![], cl_nodes);
+        let broadcast = &mut Arc::get_mut(&mut state).unwrap().cl_broadcast;
+        broadcast.enabled = true;
+        broadcast.policy = BroadcastPolicy::AllSuccess;
+
+        assert_eq!(post_cl_block(state).await, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_build_cl_url() {
+        let uri: Uri = "/cl/eth/v1/node/health?x=1".parse().unwrap();
+        assert_eq!(
+            build_cl_url("http://node:5052/", &uri),
+            "http://node:5052/eth/v1/node/health?x=1"
+        );
+
+        let uri: Uri = "/cl/".parse().unwrap();
+        assert_eq!(build_cl_url("http://node:5052", &uri), "http://node:5052");
+    }
+
     // Note: timeout test is hard to implement without mocking the client
     // We've verified the timeout handling code is in place
 
@@ -648,4 +3396,54 @@ mod tests {
         // Should either succeed or return 404 from upstream (not 503)
         assert_ne!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
+
+    // =========================================================================
+    // Event log tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_events_served_as_json_and_stream() {
+        let state = create_test_state(vec![], vec![]);
+        state.events.record(crate::events::EventKind::Failover {
+            layer: crate::events::Layer::El,
+            active: true,
+        });
+
+        let app = Router::new()
+            .route("/status/events", axum::routing::get(events_handler))
+            .with_state(state);
+
+        let request = Request::builder()
+            .uri("/status/events")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json[0]["type"], "failover");
+        assert_eq!(json[0]["layer"], "el");
+        assert_eq!(json[0]["active"], true);
+
+        // Resuming the stream replays events after the last one seen
+        let request = Request::builder()
+            .uri("/status/events")
+            .header("accept", "text/event-stream")
+            .header("last-event-id", "0")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let frame = response
+            .into_body()
+            .into_data_stream()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(frame.contains("id: 1\n"), "{frame}");
+        assert!(frame.contains(r#""type":"failover""#), "{frame}");
+    }
 }
@@ -0,0 +1,518 @@
+//! JSON-RPC payload parsing and error construction for the EL proxy
+
+use std::collections::{HashMap, VecDeque};
+
+use serde_json::{Value, json};
+
+/// Invalid Request error code (malformed call or empty/oversized batch)
+pub const INVALID_REQUEST: i64 = -32600;
+
+/// Internal error code (used when an upstream node fails to answer a call)
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Method not found error code (also used for methods rejected by the method filter)
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Server error code used when a request lacks a valid API key
+pub const UNAUTHORIZED: i64 = -32000;
+
+/// Limit exceeded error code (EIP-1474), used when a client is rate limited
+pub const LIMIT_EXCEEDED: i64 = -32005;
+
+/// A parsed JSON-RPC request body
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonRpcPayload {
+    /// A single JSON-RPC call object
+    Single(Value),
+    /// A batch of JSON-RPC call objects
+    Batch(Vec<Value>),
+}
+
+/// Parse a request body into a single call or a batch
+///
+/// Returns None if the body is not valid JSON or is neither an object nor an array.
+pub fn parse_payload(body: &[u8]) -> Option<JsonRpcPayload> {
+    match serde_json::from_slice::<Value>(body).ok()? {
+        Value::Array(calls) => Some(JsonRpcPayload::Batch(calls)),
+        call @ Value::Object(_) => Some(JsonRpcPayload::Single(call)),
+        _ => None,
+    }
+}
+
+/// Get the method name of a JSON-RPC call
+pub fn method_of(call: &Value) -> Option<&str> {
+    call.get("method").and_then(|m| m.as_str())
+}
+
+/// Get the id of a JSON-RPC call, or None if the call is a notification
+pub fn id_of(call: &Value) -> Option<&Value> {
+    call.get("id")
+}
+
+/// Check whether a JSON-RPC method can safely be sent to a node more than once
+///
+/// Only known read methods qualify: anything else, such as transaction or bundle
+/// submission under any namespace, may have side effects and must not be repeated.
+pub fn is_idempotent(method: &str) -> bool {
+    const READ_PREFIXES: &[&str] = &[
+        "eth_get",
+        "net_",
+        "web3_",
+        "debug_trace",
+        "debug_getRaw",
+        "trace_",
+    ];
+    const READ_METHODS: &[&str] = &[
+        "eth_blockNumber",
+        "eth_blobBaseFee",
+        "eth_call",
+        "eth_chainId",
+        "eth_createAccessList",
+        "eth_estimateGas",
+        "eth_feeHistory",
+        "eth_gasPrice",
+        "eth_maxPriorityFeePerGas",
+        "eth_newBlockFilter",
+        "eth_newFilter",
+        "eth_newPendingTransactionFilter",
+        "eth_protocolVersion",
+        "eth_simulateV1",
+        "eth_subscribe",
+        "eth_syncing",
+    ];
+
+    READ_METHODS.contains(&method) || READ_PREFIXES.iter().any(|p| method.starts_with(p))
+}
+
+/// Position of the block parameter for methods that address a specific block
+fn block_param_index(method: &str) -> Option<usize> {
+    match method {
+        "eth_getBlockByNumber"
+        | "eth_getBlockReceipts"
+        | "eth_getBlockTransactionCountByNumber"
+        | "eth_getTransactionByBlockNumberAndIndex"
+        | "eth_getUncleByBlockNumberAndIndex"
+        | "eth_getUncleCountByBlockNumber"
+        | "debug_traceBlockByNumber"
+        | "trace_block" => Some(0),
+        "eth_getBalance"
+        | "eth_getCode"
+        | "eth_getTransactionCount"
+        | "eth_call"
+        | "eth_estimateGas"
+        | "eth_feeHistory" => Some(1),
+        "eth_getStorageAt" | "eth_getProof" => Some(2),
+        _ => None,
+    }
+}
+
+/// Parse an explicit block number from a block parameter
+///
+/// Accepts hex quantities and EIP-1898 objects with a `blockNumber` field.
+/// Tags like "latest" or "finalized" carry no explicit height and return None.
+fn parse_block_param(param: &Value) -> Option<u64> {
+    let hex = match param {
+        Value::String(s) => s.as_str(),
+        Value::Object(obj) => obj.get("blockNumber")?.as_str()?,
+        _ => return None,
+    };
+    let digits = hex.strip_prefix("0x")?;
+    u64::from_str_radix(digits, 16).ok()
+}
+
+/// The block a JSON-RPC call is evaluated against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockRef {
+    /// A block tag such as "latest", "safe" or "pending" (also used when the parameter is omitted)
+    Tag(String),
+    /// An explicit block number
+    Number(u64),
+    /// A block hash, which always refers to the same block
+    Hash,
+}
+
+/// Parse a block parameter into a block reference, defaulting to "latest" when omitted
+fn parse_block_ref(param: Option<&Value>) -> Option<BlockRef> {
+    match param {
+        None | Some(Value::Null) => Some(BlockRef::Tag("latest".to_string())),
+        Some(Value::Object(obj)) if obj.contains_key("blockHash") => Some(BlockRef::Hash),
+        Some(Value::String(s)) if s.len() == 66 && s.starts_with("0x") => Some(BlockRef::Hash),
+        Some(Value::String(s)) if !s.starts_with("0x") => Some(BlockRef::Tag(s.clone())),
+        Some(param) => parse_block_param(param).map(BlockRef::Number),
+    }
+}
+
+/// Get the block a block-addressed JSON-RPC call refers to
+///
+/// Returns None for methods without a block parameter. For eth_getLogs a range that
+/// involves any tag is reported as that tag (preferring "pending"), otherwise as
+/// the highest block in the range.
+pub fn block_reference(call: &Value) -> Option<BlockRef> {
+    let method = method_of(call)?;
+    let params = call.get("params").and_then(|p| p.as_array());
+
+    if method == "eth_getLogs" {
+        let filter = params.and_then(|p| p.first())?;
+        if filter.get("blockHash").is_some() {
+            return Some(BlockRef::Hash);
+        }
+        let refs = ["fromBlock", "toBlock"]
+            .iter()
+            .map(|key| parse_block_ref(filter.get(*key)))
+            .collect::<Option<Vec<_>>>()?;
+        let tag = refs
+            .iter()
+            .filter(|r| matches!(r, BlockRef::Tag(_)))
+            .max_by_key(|r| **r == BlockRef::Tag("pending".to_string()));
+        if let Some(tag) = tag {
+            return Some(tag.clone());
+        }
+        return refs
+            .into_iter()
+            .filter_map(|r| match r {
+                BlockRef::Number(n) => Some(n),
+                _ => None,
+            })
+            .max()
+            .map(BlockRef::Number);
+    }
+
+    let index = block_param_index(method)?;
+    parse_block_ref(params.and_then(|p| p.get(index)))
+}
+
+/// Get the block height a JSON-RPC call needs a node to have reached, if any
+///
+/// Inspects the block number/tag parameter of common block-addressed methods and
+/// the `fromBlock`/`toBlock` range of eth_getLogs.
+pub fn requested_block(call: &Value) -> Option<u64> {
+    let method = method_of(call)?;
+    let params = call.get("params")?.as_array()?;
+
+    if method == "eth_getLogs" {
+        let filter = params.first()?;
+        return ["fromBlock", "toBlock"]
+            .iter()
+            .filter_map(|key| filter.get(*key).and_then(parse_block_param))
+            .max();
+    }
+
+    parse_block_param(params.get(block_param_index(method)?)?)
+}
+
+/// Check whether a response body is a JSON-RPC error response
+pub fn is_error_response(body: &[u8]) -> bool {
+    serde_json::from_slice::<Value>(body)
+        .map(|v| v.get("error").is_some_and(|e| !e.is_null()))
+        .unwrap_or(false)
+}
+
+/// Get the error code of a JSON-RPC response, if it is an error response
+pub fn error_code(response: &Value) -> Option<i64> {
+    response.get("error")?.get("code")?.as_i64()
+}
+
+/// Replace the id of a JSON-RPC response body
+///
+/// Returns None if the body is not a JSON-RPC response object.
+pub fn replace_id(body: &[u8], id: &Value) -> Option<Vec<u8>> {
+    let mut response = serde_json::from_slice::<Value>(body).ok()?;
+    *response.as_object_mut()?.get_mut("id")? = id.clone();
+    serde_json::to_vec(&response).ok()
+}
+
+/// Group responses by their serialized id, keeping responses that share an id in order
+pub fn responses_by_id(responses: Vec<Value>) -> HashMap<String, VecDeque<Value>> {
+    let mut by_id: HashMap<String, VecDeque<Value>> = HashMap::new();
+    for response in responses {
+        let key = response.get("id").unwrap_or(&Value::Null).to_string();
+        by_id.entry(key).or_default().push_back(response);
+    }
+    by_id
+}
+
+/// Build the responses to a batch, in the order of its calls, from the responses to
+/// calls answered locally and the upstream responses to the rest
+///
+/// `rejected` holds the position in `calls` and the response of each call answered
+/// locally, in order; upstream responses are matched to the other calls by id.
+/// Upstream responses matching no call (e.g. errors with a null id) go last.
+pub fn merge_batch_responses(
+    calls: &[Value],
+    rejected: Vec<(usize, Value)>,
+    upstream: Vec<Value>,
+) -> Vec<Value> {
+    let mut by_id = responses_by_id(upstream);
+    let mut rejected = rejected.into_iter().peekable();
+    let mut responses = Vec::with_capacity(calls.len());
+
+    for (position, call) in calls.iter().enumerate() {
+        if let Some((_, response)) = rejected.next_if(|(p, _)| *p == position) {
+            responses.push(response);
+        } else if let Some(response) = id_of(call)
+            .and_then(|id| by_id.get_mut(&id.to_string()))
+            .and_then(VecDeque::pop_front)
+        {
+            responses.push(response);
+        }
+    }
+    responses.extend(by_id.into_values().flatten());
+    responses
+}
+
+/// Build the error response for a call whose method is not allowed
+pub fn method_not_allowed(call: &Value) -> Value {
+    let message = match method_of(call) {
+        Some(method) => format!("method {method} is not allowed"),
+        None => "method is not allowed".to_string(),
+    };
+    error_response(
+        id_of(call).cloned().unwrap_or(Value::Null),
+        METHOD_NOT_FOUND,
+        &message,
+    )
+}
+
+/// Build a JSON-RPC success response object
+pub fn success_response(id: Value, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": result,
+    })
+}
+
+/// Build a JSON-RPC error response object
+pub fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": code,
+            "message": message,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_call() {
+        let body = br#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#;
+
+        let payload = parse_payload(body).expect("Should parse single call");
+
+        match payload {
+            JsonRpcPayload::Single(call) => {
+                assert_eq!(method_of(&call), Some("eth_blockNumber"));
+                assert_eq!(id_of(&call), Some(&json!(1)));
+            }
+            JsonRpcPayload::Batch(_) => panic!("Expected single call"),
+        }
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let body = br#"[{"jsonrpc":"2.0","method":"eth_blockNumber","id":1},{"jsonrpc":"2.0","method":"eth_chainId","id":2}]"#;
+
+        let payload = parse_payload(body).expect("Should parse batch");
+
+        match payload {
+            JsonRpcPayload::Batch(calls) => {
+                assert_eq!(calls.len(), 2);
+                assert_eq!(method_of(&calls[1]), Some("eth_chainId"));
+            }
+            JsonRpcPayload::Single(_) => panic!("Expected batch"),
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_body() {
+        assert!(parse_payload(b"not json").is_none());
+        assert!(parse_payload(b"42").is_none());
+    }
+
+    #[test]
+    fn test_notification_has_no_id() {
+        let call = json!({"jsonrpc": "2.0", "method": "eth_subscribe"});
+        assert!(id_of(&call).is_none());
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        assert!(is_idempotent("eth_call"));
+        assert!(is_idempotent("eth_getBlockByNumber"));
+        assert!(is_idempotent("eth_subscribe"));
+        assert!(!is_idempotent("eth_sendRawTransaction"));
+        assert!(!is_idempotent("eth_sendBundle"));
+        assert!(!is_idempotent("eth_unsubscribe"));
+        assert!(!is_idempotent("admin_addPeer"));
+        assert!(!is_idempotent("mev_sendBundle"));
+        assert!(!is_idempotent("flashbots_cancelBundle"));
+        assert!(!is_idempotent("txpool_content"));
+        assert!(!is_idempotent("debug_setHead"));
+        assert!(is_idempotent("debug_traceTransaction"));
+        assert!(is_idempotent("net_version"));
+    }
+
+    #[test]
+    fn test_requested_block_from_block_number() {
+        let call = json!({"method": "eth_getBlockByNumber", "params": ["0x3e8", false]});
+        assert_eq!(requested_block(&call), Some(1000));
+    }
+
+    #[test]
+    fn test_requested_block_from_trailing_param() {
+        let call = json!({"method": "eth_call", "params": [{"to": "0x0"}, "0x10"]});
+        assert_eq!(requested_block(&call), Some(16));
+
+        let call = json!({"method": "eth_getStorageAt", "params": ["0x0", "0x0", "0x20"]});
+        assert_eq!(requested_block(&call), Some(32));
+    }
+
+    #[test]
+    fn test_requested_block_ignores_tags() {
+        let call = json!({"method": "eth_getBlockByNumber", "params": ["latest", false]});
+        assert_eq!(requested_block(&call), None);
+
+        let call = json!({"method": "eth_getBalance", "params": ["0x0"]});
+        assert_eq!(requested_block(&call), None);
+
+        let call = json!({"method": "eth_chainId", "params": []});
+        assert_eq!(requested_block(&call), None);
+    }
+
+    #[test]
+    fn test_requested_block_from_eip1898_object() {
+        let call = json!({
+            "method": "eth_getCode",
+            "params": ["0x0", {"blockNumber": "0xff"}]
+        });
+        assert_eq!(requested_block(&call), Some(255));
+
+        let call = json!({
+            "method": "eth_getCode",
+            "params": ["0x0", {"blockHash": "0xabc"}]
+        });
+        assert_eq!(requested_block(&call), None);
+    }
+
+    #[test]
+    fn test_requested_block_from_log_range() {
+        let call = json!({
+            "method": "eth_getLogs",
+            "params": [{"fromBlock": "0x10", "toBlock": "0x20"}]
+        });
+        assert_eq!(requested_block(&call), Some(32));
+
+        let call = json!({
+            "method": "eth_getLogs",
+            "params": [{"fromBlock": "0x10", "toBlock": "latest"}]
+        });
+        assert_eq!(requested_block(&call), Some(16));
+    }
+
+    #[test]
+    fn test_block_reference() {
+        let call = json!({"method": "eth_getBalance", "params": ["0x0", "0x10"]});
+        assert_eq!(block_reference(&call), Some(BlockRef::Number(16)));
+
+        let call = json!({"method": "eth_call", "params": [{"to": "0x0"}]});
+        assert_eq!(
+            block_reference(&call),
+            Some(BlockRef::Tag("latest".to_string()))
+        );
+
+        let hash = format!("0x{}", "ab".repeat(32));
+        let call = json!({"method": "eth_getBlockReceipts", "params": [hash]});
+        assert_eq!(block_reference(&call), Some(BlockRef::Hash));
+
+        let call = json!({"method": "eth_chainId", "params": []});
+        assert_eq!(block_reference(&call), None);
+    }
+
+    #[test]
+    fn test_block_reference_for_log_range() {
+        let call = json!({
+            "method": "eth_getLogs",
+            "params": [{"fromBlock": "0x10", "toBlock": "0x20"}]
+        });
+        assert_eq!(block_reference(&call), Some(BlockRef::Number(32)));
+
+        let call = json!({
+            "method": "eth_getLogs",
+            "params": [{"fromBlock": "latest", "toBlock": "pending"}]
+        });
+        assert_eq!(
+            block_reference(&call),
+            Some(BlockRef::Tag("pending".to_string()))
+        );
+
+        // A missing bound defaults to "latest"
+        let call = json!({"method": "eth_getLogs", "params": [{"fromBlock": "0x10"}]});
+        assert_eq!(
+            block_reference(&call),
+            Some(BlockRef::Tag("latest".to_string()))
+        );
+
+        let call = json!({"method": "eth_getLogs", "params": [{"blockHash": "0xabc"}]});
+        assert_eq!(block_reference(&call), Some(BlockRef::Hash));
+    }
+
+    #[test]
+    fn test_is_error_response() {
+        assert!(is_error_response(
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"nonce too low"}}"#
+        ));
+        assert!(!is_error_response(
+            br#"{"jsonrpc":"2.0","id":1,"result":"0xabc"}"#
+        ));
+        assert!(!is_error_response(b"not json"));
+    }
+
+    #[test]
+    fn test_replace_id() {
+        let body = br#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#;
+
+        let rewritten = replace_id(body, &json!("abc")).expect("Should rewrite id");
+        let response: Value = serde_json::from_slice(&rewritten).unwrap();
+
+        assert_eq!(response["id"], "abc");
+        assert_eq!(response["result"], "0x10");
+        assert!(replace_id(b"Bad Gateway", &json!(1)).is_none());
+    }
+
+    #[test]
+    fn test_merge_batch_responses_keeps_call_order() {
+        let calls = vec![
+            json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "admin_peers"}),
+            json!({"jsonrpc": "2.0", "method": "admin_addPeer"}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "eth_blockNumber"}),
+        ];
+        let rejected = vec![(1, method_not_allowed(&calls[1]))];
+        // Upstream may answer in any order
+        let upstream = vec![
+            success_response(json!(3), json!("0x3e8")),
+            success_response(json!(1), json!("0x1")),
+        ];
+
+        let responses = merge_batch_responses(&calls, rejected, upstream);
+
+        let ids: Vec<&Value> = responses.iter().map(|r| &r["id"]).collect();
+        assert_eq!(ids, vec![&json!(1), &json!(2), &json!(3)]);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_error_response_shape() {
+        let response = error_response(json!(7), INVALID_REQUEST, "batch too large");
+
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        assert_eq!(response["error"]["message"], "batch too large");
+    }
+}
@@ -1,5 +1,12 @@
 //! Proxy server implementation for EL and CL requests
 
+pub mod cache;
+pub mod coalesce;
+pub mod hedge;
 pub mod http;
+pub mod jsonrpc;
+pub mod outlier;
+pub mod path_pattern;
 pub mod selection;
+pub mod sse;
 pub mod ws;
@@ -0,0 +1,184 @@
+//! Passive outlier detection from proxied request outcomes
+//!
+//! Every request forwarded to a node is recorded as a success or an error in a
+//! per-node sliding window. A node whose error rate in the window exceeds the
+//! configured threshold is reported as an outlier, so the proxy can eject it from
+//! rotation for a cool-down period without waiting for its health checks to fail.
+
+use axum::http::StatusCode;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::OutlierDetection;
+use crate::proxy::jsonrpc;
+
+/// Sliding windows of request outcomes, keyed by node name
+#[derive(Debug)]
+pub struct OutlierDetector {
+    window: Duration,
+    min_requests: usize,
+    max_error_rate: f64,
+    ejection: Duration,
+    error_codes: Vec<i64>,
+    /// Outcomes per node: (time, is_error)
+    outcomes: Mutex<HashMap<String, VecDeque<(Instant, bool)>>>,
+}
+
+impl OutlierDetector {
+    /// Create a detector from the outlier detection settings
+    pub fn new(config: &OutlierDetection) -> Self {
+        Self {
+            window: Duration::from_millis(config.window_ms),
+            min_requests: config.min_requests as usize,
+            max_error_rate: config.max_error_rate,
+            ejection: Duration::from_millis(config.ejection_ms),
+            error_codes: config.error_codes.clone(),
+            outcomes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long an outlier is kept out of rotation
+    pub fn ejection(&self) -> Duration {
+        self.ejection
+    }
+
+    /// Check whether an EL response counts as a node error
+    ///
+    /// HTTP 5xx responses are errors, as are JSON-RPC responses (or batches containing
+    /// a response) carrying one of the configured error codes.
+    pub fn is_el_error(&self, status: StatusCode, body: &[u8]) -> bool {
+        if status.is_server_error() {
+            return true;
+        }
+
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(responses)) => responses.iter().any(|r| self.is_el_error_response(r)),
+            Ok(response) => self.is_el_error_response(&response),
+            Err(_) => false,
+        }
+    }
+
+    /// Check whether a single JSON-RPC response carries one of the configured error codes
+    pub fn is_el_error_response(&self, response: &Value) -> bool {
+        jsonrpc::error_code(response).is_some_and(|code| self.error_codes.contains(&code))
+    }
+
+    /// Record the outcome of a request to a node
+    ///
+    /// Returns true if the node is now an outlier. Its window is cleared so it starts
+    /// over once it is back in rotation.
+    pub fn record(&self, node: &str, is_error: bool) -> bool {
+        self.record_at(node, is_error, Instant::now())
+    }
+
+    fn record_at(&self, node: &str, is_error: bool, now: Instant) -> bool {
+        let mut outcomes = self.outcomes.lock().expect("outlier lock poisoned");
+        let window = outcomes.entry(node.to_string()).or_default();
+
+        window.push_back((now, is_error));
+        while window
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > self.window)
+        {
+            window.pop_front();
+        }
+
+        if window.len() < self.min_requests.max(1) {
+            return false;
+        }
+
+        let errors = window.iter().filter(|(_, is_error)| *is_error).count();
+        let is_outlier = errors as f64 / window.len() as f64 > self.max_error_rate;
+        if is_outlier {
+            window.clear();
+        }
+        is_outlier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> OutlierDetector {
+        OutlierDetector::new(&OutlierDetection {
+            enabled: true,
+            window_ms: 10000,
+            min_requests: 4,
+            max_error_rate: 0.5,
+            ejection_ms: 30000,
+            error_codes: vec![-32603],
+        })
+    }
+
+    #[test]
+    fn test_outlier_needs_min_requests() {
+        let detector = detector();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(!detector.record_at("node", true, now));
+        }
+        assert!(detector.record_at("node", true, now));
+
+        // The window starts over after an ejection
+        assert!(!detector.record_at("node", true, now));
+    }
+
+    #[test]
+    fn test_outlier_error_rate_threshold() {
+        let detector = detector();
+        let now = Instant::now();
+
+        // 2 errors out of 4 is not above 50%
+        for is_error in [true, false, true, false] {
+            assert!(!detector.record_at("node", is_error, now));
+        }
+        // 3 errors out of 5 is
+        assert!(detector.record_at("node", true, now));
+
+        // Nodes are tracked separately
+        assert!(!detector.record_at("other", true, now));
+    }
+
+    #[test]
+    fn test_outlier_window_drops_old_outcomes() {
+        let detector = detector();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            detector.record_at("node", true, start);
+        }
+
+        // Old errors have left the window by the time the 4th request arrives
+        let later = start + Duration::from_secs(11);
+        assert!(!detector.record_at("node", true, later));
+    }
+
+    #[test]
+    fn test_is_el_error() {
+        let detector = detector();
+
+        assert!(detector.is_el_error(StatusCode::BAD_GATEWAY, b""));
+        assert!(detector.is_el_error(
+            StatusCode::OK,
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32603,"message":"internal"}}"#
+        ));
+        assert!(detector.is_el_error(
+            StatusCode::OK,
+            br#"[{"jsonrpc":"2.0","id":1,"result":"0x1"},{"jsonrpc":"2.0","id":2,"error":{"code":-32603,"message":"internal"}}]"#
+        ));
+
+        // Errors caused by the request itself don't count against the node
+        assert!(!detector.is_el_error(
+            StatusCode::OK,
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted"}}"#
+        ));
+        assert!(!detector.is_el_error(
+            StatusCode::OK,
+            br#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#
+        ));
+    }
+}
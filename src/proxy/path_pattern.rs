@@ -0,0 +1,201 @@
+//! Glob-style matching of beacon API paths
+//!
+//! Patterns are matched segment by segment: `*` inside a segment matches any run of
+//! characters within that segment (e.g. `v*` matches `v1` and `v2`), and a `**`
+//! segment matches any number of segments, including none.
+
+/// Check whether a request path matches a pattern
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = segments(pattern).collect();
+    let path: Vec<&str> = segments(path).collect();
+    match_segments(&pattern, &path)
+}
+
+/// Split a path into its non-empty segments
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                match_segment(first, segment) && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single segment where `*` matches any run of characters
+fn match_segment(pattern: &str, segment: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == segment,
+        Some((prefix, rest)) => {
+            let Some(remaining) = segment.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=remaining.len())
+                .filter(|&i| remaining.is_char_boundary(i))
+                .any(|i| match_segment(rest, &remaining[i..]))
+        }
+    }
+}
+
+/// Standard beacon API endpoints, used to label metrics by route instead of by path
+const BEACON_API_ROUTES: &[&str] = &[
+    "/eth/v1/beacon/genesis",
+    "/eth/v1/beacon/states/{state_id}/root",
+    "/eth/v1/beacon/states/{state_id}/fork",
+    "/eth/v1/beacon/states/{state_id}/finality_checkpoints",
+    "/eth/v1/beacon/states/{state_id}/validators",
+    "/eth/v1/beacon/states/{state_id}/validators/{validator_id}",
+    "/eth/v1/beacon/states/{state_id}/validator_balances",
+    "/eth/v1/beacon/states/{state_id}/committees",
+    "/eth/v1/beacon/states/{state_id}/sync_committees",
+    "/eth/v1/beacon/states/{state_id}/randao",
+    "/eth/v1/beacon/headers",
+    "/eth/v1/beacon/headers/{block_id}",
+    "/eth/v1/beacon/blocks",
+    "/eth/v2/beacon/blocks",
+    "/eth/v2/beacon/blocks/{block_id}",
+    "/eth/v1/beacon/blocks/{block_id}/root",
+    "/eth/v1/beacon/blocks/{block_id}/attestations",
+    "/eth/v2/beacon/blocks/{block_id}/attestations",
+    "/eth/v1/beacon/blinded_blocks",
+    "/eth/v2/beacon/blinded_blocks",
+    "/eth/v1/beacon/blinded_blocks/{block_id}",
+    "/eth/v1/beacon/blob_sidecars/{block_id}",
+    "/eth/v1/beacon/pool/attestations",
+    "/eth/v2/beacon/pool/attestations",
+    "/eth/v1/beacon/pool/attester_slashings",
+    "/eth/v2/beacon/pool/attester_slashings",
+    "/eth/v1/beacon/pool/proposer_slashings",
+    "/eth/v1/beacon/pool/voluntary_exits",
+    "/eth/v1/beacon/pool/bls_to_execution_changes",
+    "/eth/v1/beacon/pool/sync_committees",
+    "/eth/v1/beacon/rewards/blocks/{block_id}",
+    "/eth/v1/beacon/rewards/attestations/{epoch}",
+    "/eth/v1/beacon/rewards/sync_committee/{block_id}",
+    "/eth/v1/config/spec",
+    "/eth/v1/config/fork_schedule",
+    "/eth/v1/config/deposit_contract",
+    "/eth/v2/debug/beacon/states/{state_id}",
+    "/eth/v2/debug/beacon/heads",
+    "/eth/v1/events",
+    "/eth/v1/node/identity",
+    "/eth/v1/node/peers",
+    "/eth/v1/node/peers/{peer_id}",
+    "/eth/v1/node/peer_count",
+    "/eth/v1/node/version",
+    "/eth/v1/node/syncing",
+    "/eth/v1/node/health",
+    "/eth/v1/validator/duties/attester/{epoch}",
+    "/eth/v1/validator/duties/proposer/{epoch}",
+    "/eth/v1/validator/duties/sync/{epoch}",
+    "/eth/v2/validator/blocks/{slot}",
+    "/eth/v3/validator/blocks/{slot}",
+    "/eth/v1/validator/blinded_blocks/{slot}",
+    "/eth/v1/validator/attestation_data",
+    "/eth/v1/validator/aggregate_attestation",
+    "/eth/v2/validator/aggregate_attestation",
+    "/eth/v1/validator/aggregate_and_proofs",
+    "/eth/v2/validator/aggregate_and_proofs",
+    "/eth/v1/validator/beacon_committee_subscriptions",
+    "/eth/v1/validator/sync_committee_subscriptions",
+    "/eth/v1/validator/sync_committee_contribution",
+    "/eth/v1/validator/contribution_and_proofs",
+    "/eth/v1/validator/prepare_beacon_proposer",
+    "/eth/v1/validator/register_validator",
+    "/eth/v1/validator/liveness/{epoch}",
+];
+
+/// Get the beacon API route template a request path belongs to
+///
+/// `{...}` segments match any single segment. Paths that are not a known beacon API
+/// endpoint return `other`, so metric label cardinality stays bounded.
+pub fn route_template(path: &str) -> &'static str {
+    BEACON_API_ROUTES
+        .iter()
+        .find(|template| matches_template(template, path))
+        .copied()
+        .unwrap_or("other")
+}
+
+/// Check whether a path matches a route template
+fn matches_template(template: &str, path: &str) -> bool {
+    let mut template = segments(template);
+    let mut path = segments(path);
+    loop {
+        match (template.next(), path.next()) {
+            (None, None) => return true,
+            (Some(t), Some(p)) if t == p || (t.starts_with('{') && t.ends_with('}')) => {}
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_path() {
+        assert!(matches("/eth/v1/node/health", "/eth/v1/node/health"));
+        assert!(!matches("/eth/v1/node/health", "/eth/v1/node/version"));
+        assert!(!matches("/eth/v1/node", "/eth/v1/node/health"));
+    }
+
+    #[test]
+    fn test_wildcard_within_segment() {
+        assert!(matches("/eth/v*/beacon/blocks", "/eth/v1/beacon/blocks"));
+        assert!(matches("/eth/v*/beacon/blocks", "/eth/v2/beacon/blocks"));
+        assert!(!matches(
+            "/eth/v*/beacon/blocks",
+            "/eth/v2/beacon/blinded_blocks"
+        ));
+    }
+
+    #[test]
+    fn test_wildcard_segment() {
+        assert!(matches(
+            "/eth/v1/beacon/pool/*",
+            "/eth/v1/beacon/pool/attestations"
+        ));
+        assert!(!matches("/eth/v1/beacon/pool/*", "/eth/v1/beacon/pool"));
+        assert!(!matches("/eth/v1/beacon/pool/*", "/eth/v1/beacon/pool/a/b"));
+    }
+
+    #[test]
+    fn test_double_wildcard() {
+        assert!(matches(
+            "/eth/v1/debug/**",
+            "/eth/v1/debug/beacon/states/head"
+        ));
+        assert!(matches("/eth/v1/debug/**", "/eth/v1/debug"));
+        assert!(matches("/**", "/anything/at/all"));
+        assert!(!matches("/eth/v1/debug/**", "/eth/v1/node/health"));
+    }
+
+    #[test]
+    fn test_trailing_slash_ignored() {
+        assert!(matches("/eth/v1/node/health", "/eth/v1/node/health/"));
+    }
+
+    #[test]
+    fn test_route_template() {
+        assert_eq!(
+            route_template("/eth/v1/beacon/states/head/finality_checkpoints"),
+            "/eth/v1/beacon/states/{state_id}/finality_checkpoints"
+        );
+        assert_eq!(
+            route_template("/eth/v2/beacon/blocks/0xabc"),
+            "/eth/v2/beacon/blocks/{block_id}"
+        );
+        assert_eq!(route_template("/eth/v1/node/health"), "/eth/v1/node/health");
+        assert_eq!(route_template("/eth/v1/node/health/extra"), "other");
+        assert_eq!(route_template("/some/random/path"), "other");
+    }
+}
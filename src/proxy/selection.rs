@@ -1,5 +1,10 @@
 //! Node selection logic with health checking and failover support
 
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::config::{ClRoute, ClRouteAction, MethodRoute};
+use crate::proxy::{jsonrpc, path_pattern};
 use crate::state::{ClNodeState, ElNodeState};
 
 /// Select a healthy EL node, preferring primary nodes over backup
@@ -22,11 +27,287 @@ pub fn select_el_node(nodes: &[ElNodeState], failover_active: bool) -> Option<&E
     None
 }
 
-/// Select a healthy CL node
+/// Collect every EL node eligible for traffic, using the same tier rules as select_el_node
+///
+/// Returns healthy primary nodes, or healthy backup nodes if no primary is healthy
+/// and failover is active.
+pub fn healthy_el_nodes(nodes: &[ElNodeState], failover_active: bool) -> Vec<&ElNodeState> {
+    let primaries: Vec<&ElNodeState> = nodes
+        .iter()
+        .filter(|n| n.is_primary && n.is_healthy)
+        .collect();
+
+    if !primaries.is_empty() || !failover_active {
+        return primaries;
+    }
+
+    nodes
+        .iter()
+        .filter(|n| !n.is_primary && n.is_healthy)
+        .collect()
+}
+
+/// Collect the EL nodes a broadcast call is sent to
+///
+/// Includes every node eligible for traffic and, if `include_backups` is set,
+/// every healthy backup node even when failover is not active.
+pub fn broadcast_el_nodes(
+    nodes: &[ElNodeState],
+    failover_active: bool,
+    include_backups: bool,
+) -> Vec<&ElNodeState> {
+    let mut targets = healthy_el_nodes(nodes, failover_active);
+    if include_backups {
+        for node in nodes.iter().filter(|n| !n.is_primary && n.is_healthy) {
+            if !targets.iter().any(|t| std::ptr::eq(*t, node)) {
+                targets.push(node);
+            }
+        }
+    }
+    targets
+}
+
+/// Find the node tag a JSON-RPC method is routed to, using the first matching rule
+pub fn route_tag<'a>(routes: &'a [MethodRoute], method: &str) -> Option<&'a str> {
+    routes
+        .iter()
+        .find(|r| r.matches(method))
+        .map(|r| r.tag.as_str())
+}
+
+/// Collect healthy EL nodes carrying a tag, using the same tier rules as select_el_node
+fn tagged_el_nodes<'a>(
+    nodes: &'a [ElNodeState],
+    failover_active: bool,
+    tag: &str,
+) -> Vec<&'a ElNodeState> {
+    healthy_el_nodes(nodes, failover_active)
+        .into_iter()
+        .filter(|n| n.tags.iter().any(|t| t == tag))
+        .collect()
+}
+
+/// Check whether a node has reached the block a call needs
+fn covers_block(node: &ElNodeState, min_block: Option<u64>) -> bool {
+    min_block.is_none_or(|b| node.block_number >= b)
+}
+
+/// Pick the eligible EL node with the highest block number (the cluster head node)
+fn head_el_node<'a>(nodes: &[&'a ElNodeState]) -> Option<&'a ElNodeState> {
+    nodes.iter().copied().max_by_key(|n| n.block_number)
+}
+
+/// Collect the EL nodes calls that no rule routes to a tag are sent to
+///
+/// Nodes carrying a tag that routing rules target (e.g. archive nodes) are kept for the
+/// routed calls, and only serve other calls when no untagged node is eligible.
+fn default_el_nodes<'a>(
+    nodes: &'a [ElNodeState],
+    failover_active: bool,
+    routes: &[MethodRoute],
+) -> Vec<&'a ElNodeState> {
+    let eligible = healthy_el_nodes(nodes, failover_active);
+    let untagged: Vec<&ElNodeState> = eligible
+        .iter()
+        .copied()
+        .filter(|n| !routes.iter().any(|r| n.tags.contains(&r.tag)))
+        .collect();
+
+    if untagged.is_empty() {
+        eligible
+    } else {
+        untagged
+    }
+}
+
+/// Find the group of EL nodes a call is sent to: the healthy nodes carrying the tag a
+/// rule routes its method to, or the default group when no rule matches or the tagged
+/// group has no healthy member
+///
+/// Returns the group's tag (None for the default group) and its nodes.
+fn call_group<'a, 'r>(
+    nodes: &'a [ElNodeState],
+    failover_active: bool,
+    routes: &'r [MethodRoute],
+    method: Option<&str>,
+) -> (Option<&'r str>, Vec<&'a ElNodeState>) {
+    if let Some(tag) = method.and_then(|m| route_tag(routes, m)) {
+        let group = tagged_el_nodes(nodes, failover_active, tag);
+        if !group.is_empty() {
+            return (Some(tag), group);
+        }
+    }
+    (None, default_el_nodes(nodes, failover_active, routes))
+}
+
+/// Select a healthy EL node for a JSON-RPC call, honouring method routing rules
+/// and the block height the call addresses
+///
+/// If a rule routes the method to a tag, a healthy node carrying that tag is chosen;
+/// when the tagged group has no healthy member, falls through to the default group.
+/// Other calls go to the default group, which leaves out nodes carrying routed tags.
+/// If `min_block` is set, only nodes of that group at or above that block are chosen,
+/// falling back to the group's head node when none covers it yet.
+pub fn select_el_node_for_call<'a>(
+    nodes: &'a [ElNodeState],
+    failover_active: bool,
+    routes: &[MethodRoute],
+    method: Option<&str>,
+    min_block: Option<u64>,
+) -> Option<&'a ElNodeState> {
+    let (_, group) = call_group(nodes, failover_active, routes, method);
+
+    group
+        .iter()
+        .copied()
+        .find(|n| covers_block(n, min_block))
+        .or_else(|| head_el_node(&group))
+}
+
+/// Select another EL node for a call that is hedged or retried
+///
+/// Uses the same routing rules as select_el_node_for_call, but skips the nodes named
+/// in `exclude` and never falls back to a node that hasn't reached `min_block`.
+pub fn select_other_el_node<'a>(
+    nodes: &'a [ElNodeState],
+    failover_active: bool,
+    routes: &[MethodRoute],
+    method: Option<&str>,
+    min_block: Option<u64>,
+    exclude: &[String],
+) -> Option<&'a ElNodeState> {
+    call_group(nodes, failover_active, routes, method)
+        .1
+        .into_iter()
+        .find(|n| !exclude.contains(&n.name) && covers_block(n, min_block))
+}
+
+/// Assign each call of a JSON-RPC batch to an EL node
+///
+/// Returns, for every call, the index into `nodes` it should be sent to, or None if
+/// no healthy node is available. Calls routed to a tag are spread round-robin across
+/// that tag's healthy nodes; all other calls are spread across the default group.
+/// Calls addressing a specific block only go to nodes of their group that have reached
+/// it, or to the group's head node if none has.
+pub fn assign_batch_calls(
+    nodes: &[ElNodeState],
+    failover_active: bool,
+    routes: &[MethodRoute],
+    calls: &[Value],
+) -> Option<Vec<usize>> {
+    // Round-robin counter per routing group (None = default group)
+    let mut counters: HashMap<Option<&str>, usize> = HashMap::new();
+    let mut assignment = Vec::with_capacity(calls.len());
+
+    for call in calls {
+        let (key, group) = call_group(nodes, failover_active, routes, jsonrpc::method_of(call));
+        let head = head_el_node(&group)?;
+
+        let min_block = jsonrpc::requested_block(call);
+        let mut group: Vec<&ElNodeState> = group
+            .into_iter()
+            .filter(|n| covers_block(n, min_block))
+            .collect();
+        if group.is_empty() {
+            group.push(head);
+        }
+
+        let counter = counters.entry(key).or_default();
+        let node = group[*counter % group.len()];
+        *counter += 1;
+
+        // Map back to the node's index in the full list
+        let index = nodes.iter().position(|n| std::ptr::eq(n, node))?;
+        assignment.push(index);
+    }
+
+    Some(assignment)
+}
+
+/// Collect every CL node eligible for traffic
 ///
-/// Returns the first healthy CL node found.
-pub fn select_cl_node(nodes: &[ClNodeState]) -> Option<&ClNodeState> {
-    nodes.iter().find(|n| n.is_healthy)
+/// Returns healthy primary nodes, or healthy backup nodes if no primary is healthy
+/// and failover is active.
+pub fn healthy_cl_nodes(nodes: &[ClNodeState], failover_active: bool) -> Vec<&ClNodeState> {
+    let primaries: Vec<&ClNodeState> = nodes
+        .iter()
+        .filter(|n| n.is_primary && n.is_healthy)
+        .collect();
+
+    if !primaries.is_empty() || !failover_active {
+        return primaries;
+    }
+
+    nodes
+        .iter()
+        .filter(|n| !n.is_primary && n.is_healthy)
+        .collect()
+}
+
+/// Select a healthy CL node, preferring primary nodes over backup
+///
+/// Backup nodes are only considered when failover_active is true.
+pub fn select_cl_node(nodes: &[ClNodeState], failover_active: bool) -> Option<&ClNodeState> {
+    healthy_cl_nodes(nodes, failover_active).into_iter().next()
+}
+
+/// Find the first path rule matching a CL request
+pub fn cl_route<'a>(routes: &'a [ClRoute], method: &str, path: &str) -> Option<&'a ClRoute> {
+    routes.iter().find(|r| {
+        r.matches_method(method) && r.paths.iter().any(|p| path_pattern::matches(p, path))
+    })
+}
+
+/// Collect the CL nodes requests that no rule routes to a tag are sent to
+///
+/// Like default_el_nodes: nodes carrying a tag that `route` rules target are kept for
+/// the routed requests, and only serve other requests when no untagged node is eligible.
+fn default_cl_nodes<'a>(
+    nodes: &'a [ClNodeState],
+    failover_active: bool,
+    routes: &[ClRoute],
+) -> Vec<&'a ClNodeState> {
+    let eligible = healthy_cl_nodes(nodes, failover_active);
+    let untagged: Vec<&ClNodeState> = eligible
+        .iter()
+        .copied()
+        .filter(|n| {
+            !routes
+                .iter()
+                .filter(|r| r.action == ClRouteAction::Route)
+                .filter_map(|r| r.tag.as_ref())
+                .any(|tag| n.tags.contains(tag))
+        })
+        .collect();
+
+    if untagged.is_empty() {
+        eligible
+    } else {
+        untagged
+    }
+}
+
+/// Select a healthy CL node, preferring nodes carrying `tag` when given
+///
+/// Uses the same tier rules as select_cl_node. When the tagged group has no healthy
+/// member, falls through to the default group, like method routing does for EL nodes.
+/// The default group leaves out nodes carrying tags that `routes` route to.
+pub fn select_cl_node_for_tag<'a>(
+    nodes: &'a [ClNodeState],
+    failover_active: bool,
+    routes: &[ClRoute],
+    tag: Option<&str>,
+) -> Option<&'a ClNodeState> {
+    tag.and_then(|tag| {
+        healthy_cl_nodes(nodes, failover_active)
+            .into_iter()
+            .find(|n| n.tags.iter().any(|t| t == tag))
+    })
+    .or_else(|| {
+        default_cl_nodes(nodes, failover_active, routes)
+            .first()
+            .copied()
+    })
 }
 
 #[cfg(test)]
@@ -40,11 +321,16 @@ mod tests {
             http_url: format!("http://{name}.local:8545"),
             ws_url: format!("ws://{name}.local:8546"),
             is_primary,
+            tags: vec![],
             block_number: 1000,
             check_ok: is_healthy,
             is_healthy,
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
@@ -53,11 +339,17 @@ mod tests {
         ClNodeState {
             name: name.to_string(),
             url: format!("http://{name}.local:5052"),
+            is_primary: true,
+            tags: vec![],
             slot: 5000,
             health_ok: is_healthy,
             is_healthy,
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
@@ -158,6 +450,308 @@ mod tests {
         assert!(selected.is_none(), "Empty list should return None");
     }
 
+    #[test]
+    fn test_healthy_el_nodes_returns_all_healthy_primaries() {
+        let nodes = vec![
+            make_el_node("geth-1", true, true),
+            make_el_node("geth-2", true, false),
+            make_el_node("geth-3", true, true),
+            make_el_node("backup-1", false, true),
+        ];
+
+        let healthy = healthy_el_nodes(&nodes, true);
+
+        let names: Vec<&str> = healthy.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["geth-1", "geth-3"]);
+    }
+
+    #[test]
+    fn test_healthy_el_nodes_uses_backups_only_on_failover() {
+        let nodes = vec![
+            make_el_node("geth-1", true, false),
+            make_el_node("backup-1", false, true),
+        ];
+
+        assert!(healthy_el_nodes(&nodes, false).is_empty());
+
+        let healthy = healthy_el_nodes(&nodes, true);
+        assert_eq!(healthy.len(), 1);
+        assert_eq!(healthy[0].name, "backup-1");
+    }
+
+    #[test]
+    fn test_assign_batch_calls_round_robin() {
+        let nodes = [
+            make_el_node("geth-1", true, true),
+            make_el_node("geth-2", true, true),
+        ];
+        let calls = vec![serde_json::json!({"method": "eth_chainId", "id": 1}); 5];
+
+        let assignment = assign_batch_calls(&nodes, false, &[], &calls);
+
+        assert_eq!(assignment, Some(vec![0, 1, 0, 1, 0]));
+    }
+
+    #[test]
+    fn test_broadcast_el_nodes_includes_backups_when_requested() {
+        let nodes = vec![
+            make_el_node("geth-1", true, true),
+            make_el_node("geth-2", true, true),
+            make_el_node("backup-1", false, true),
+            make_el_node("backup-2", false, false),
+        ];
+
+        let names = |targets: Vec<&ElNodeState>| -> Vec<String> {
+            targets.iter().map(|n| n.name.clone()).collect()
+        };
+
+        assert_eq!(
+            names(broadcast_el_nodes(&nodes, false, false)),
+            vec!["geth-1", "geth-2"]
+        );
+        assert_eq!(
+            names(broadcast_el_nodes(&nodes, false, true)),
+            vec!["geth-1", "geth-2", "backup-1"]
+        );
+    }
+
+    // =========================================================================
+    // Method routing tests
+    // =========================================================================
+
+    fn make_tagged_node(name: &str, tag: &str, is_healthy: bool) -> ElNodeState {
+        let mut node = make_el_node(name, true, is_healthy);
+        node.tags = vec![tag.to_string()];
+        node
+    }
+
+    fn archive_routes() -> Vec<MethodRoute> {
+        vec![MethodRoute {
+            methods: vec![
+                "debug_*".to_string(),
+                "trace_*".to_string(),
+                "eth_getLogs".to_string(),
+            ],
+            tag: "archive".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_route_tag_matches_prefix_and_exact() {
+        let routes = archive_routes();
+
+        assert_eq!(
+            route_tag(&routes, "debug_traceTransaction"),
+            Some("archive")
+        );
+        assert_eq!(route_tag(&routes, "trace_block"), Some("archive"));
+        assert_eq!(route_tag(&routes, "eth_getLogs"), Some("archive"));
+        assert_eq!(route_tag(&routes, "eth_getLogsX"), None);
+        assert_eq!(route_tag(&routes, "eth_call"), None);
+    }
+
+    #[test]
+    fn test_select_for_method_uses_tagged_node() {
+        let nodes = vec![
+            make_el_node("full-1", true, true),
+            make_tagged_node("archive-1", "archive", true),
+        ];
+        let routes = archive_routes();
+
+        let selected =
+            select_el_node_for_call(&nodes, false, &routes, Some("debug_traceCall"), None).unwrap();
+        assert_eq!(selected.name, "archive-1");
+
+        let selected =
+            select_el_node_for_call(&nodes, false, &routes, Some("eth_call"), None).unwrap();
+        assert_eq!(selected.name, "full-1");
+    }
+
+    #[test]
+    fn test_select_for_call_keeps_ordinary_calls_off_tagged_nodes() {
+        let nodes = vec![
+            make_tagged_node("archive-1", "archive", true),
+            make_el_node("full-1", true, false),
+            make_el_node("full-2", true, true),
+        ];
+        let routes = archive_routes();
+
+        let selected =
+            select_el_node_for_call(&nodes, false, &routes, Some("eth_call"), None).unwrap();
+        assert_eq!(selected.name, "full-2");
+
+        let first = ["full-2".to_string()];
+        assert!(
+            select_other_el_node(&nodes, false, &routes, Some("eth_call"), None, &first).is_none(),
+            "Ordinary calls are not retried on tagged nodes while a full node is healthy"
+        );
+
+        // Tagged nodes serve ordinary calls when no untagged node is healthy
+        let nodes = vec![
+            make_tagged_node("archive-1", "archive", true),
+            make_el_node("full-1", true, false),
+        ];
+        let selected =
+            select_el_node_for_call(&nodes, false, &routes, Some("eth_call"), None).unwrap();
+        assert_eq!(selected.name, "archive-1");
+
+        // Tags no rule routes to do not take nodes out of the default group
+        let nodes = vec![make_tagged_node("geth-1", "fast", true)];
+        let selected = select_el_node_for_call(&nodes, false, &routes, None, None).unwrap();
+        assert_eq!(selected.name, "geth-1");
+    }
+
+    #[test]
+    fn test_select_for_method_falls_through_when_group_unhealthy() {
+        let nodes = vec![
+            make_el_node("full-1", true, true),
+            make_tagged_node("archive-1", "archive", false),
+        ];
+        let routes = archive_routes();
+
+        let selected =
+            select_el_node_for_call(&nodes, false, &routes, Some("trace_block"), None).unwrap();
+        assert_eq!(
+            selected.name, "full-1",
+            "Should fall through to default tier"
+        );
+    }
+
+    #[test]
+    fn test_assign_batch_calls_respects_routes() {
+        let nodes = [
+            make_el_node("full-1", true, true),
+            make_tagged_node("archive-1", "archive", true),
+            make_el_node("full-2", true, true),
+        ];
+        let calls = vec![
+            serde_json::json!({"method": "eth_call", "id": 1}),
+            serde_json::json!({"method": "debug_traceCall", "id": 2}),
+            serde_json::json!({"method": "eth_call", "id": 3}),
+            serde_json::json!({"method": "trace_block", "id": 4}),
+        ];
+
+        let assignment = assign_batch_calls(&nodes, false, &archive_routes(), &calls);
+
+        // Default calls rotate across the full nodes, routed calls stay on archive-1
+        assert_eq!(assignment, Some(vec![0, 1, 2, 1]));
+    }
+
+    // =========================================================================
+    // Block-height-aware selection tests
+    // =========================================================================
+
+    fn make_node_at_block(name: &str, block_number: u64) -> ElNodeState {
+        let mut node = make_el_node(name, true, true);
+        node.block_number = block_number;
+        node
+    }
+
+    #[test]
+    fn test_select_for_call_skips_nodes_behind_requested_block() {
+        let nodes = vec![
+            make_node_at_block("geth-1", 999),
+            make_node_at_block("geth-2", 1000),
+        ];
+
+        let selected = select_el_node_for_call(&nodes, false, &[], None, Some(1000)).unwrap();
+        assert_eq!(selected.name, "geth-2");
+
+        let selected = select_el_node_for_call(&nodes, false, &[], None, None).unwrap();
+        assert_eq!(selected.name, "geth-1", "Without a height, first node wins");
+    }
+
+    #[test]
+    fn test_select_for_call_falls_back_to_head_node() {
+        let nodes = vec![
+            make_node_at_block("geth-1", 998),
+            make_node_at_block("geth-2", 999),
+        ];
+
+        let selected = select_el_node_for_call(&nodes, false, &[], None, Some(1005)).unwrap();
+        assert_eq!(selected.name, "geth-2", "Should fall back to the head node");
+    }
+
+    #[test]
+    fn test_select_for_call_keeps_routed_calls_on_lagging_tagged_node() {
+        let mut archive = make_tagged_node("archive-1", "archive", true);
+        archive.block_number = 999;
+        let nodes = vec![make_node_at_block("full-1", 1000), archive];
+        let routes = archive_routes();
+
+        let selected =
+            select_el_node_for_call(&nodes, false, &routes, Some("trace_block"), Some(1000))
+                .unwrap();
+        assert_eq!(
+            selected.name, "archive-1",
+            "Should fall back to the tagged group's head node"
+        );
+
+        let first = ["archive-1".to_string()];
+        assert!(
+            select_other_el_node(&nodes, false, &routes, Some("trace_block"), None, &first)
+                .is_none(),
+            "Retries of routed calls must stay in the tagged group"
+        );
+
+        let calls = vec![serde_json::json!({
+            "method": "trace_block",
+            "params": ["0x3e8"],
+            "id": 1
+        })];
+        assert_eq!(
+            assign_batch_calls(&nodes, false, &routes, &calls),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_select_other_node_skips_excluded_nodes() {
+        let first = ["geth-1".to_string()];
+        let nodes = vec![
+            make_node_at_block("geth-1", 1000),
+            make_node_at_block("geth-2", 999),
+            make_node_at_block("geth-3", 1000),
+        ];
+
+        let selected = select_other_el_node(&nodes, false, &[], None, None, &first).unwrap();
+        assert_eq!(selected.name, "geth-2");
+
+        let selected = select_other_el_node(&nodes, false, &[], None, Some(1000), &first).unwrap();
+        assert_eq!(
+            selected.name, "geth-3",
+            "Other node must cover the requested block"
+        );
+
+        let tried = ["geth-1".to_string(), "geth-3".to_string()];
+        assert!(select_other_el_node(&nodes, false, &[], None, Some(1000), &tried).is_none());
+    }
+
+    #[test]
+    fn test_assign_batch_calls_respects_requested_block() {
+        let nodes = [
+            make_node_at_block("geth-1", 999),
+            make_node_at_block("geth-2", 1000),
+        ];
+        let calls = vec![
+            serde_json::json!({"method": "eth_getBlockByNumber", "params": ["0x3e8", false], "id": 1}),
+            serde_json::json!({"method": "eth_getBlockByNumber", "params": ["0x3e8", false], "id": 2}),
+            serde_json::json!({"method": "eth_getBlockByNumber", "params": ["0x3e9", false], "id": 3}),
+        ];
+
+        let assignment = assign_batch_calls(&nodes, false, &[], &calls);
+
+        assert_eq!(assignment, Some(vec![1, 1, 1]));
+    }
+
+    #[test]
+    fn test_assign_batch_calls_none_when_no_healthy_nodes() {
+        let nodes = [make_el_node("full-1", true, false)];
+        let calls = vec![serde_json::json!({"method": "eth_call", "id": 1})];
+
+        assert_eq!(assign_batch_calls(&nodes, false, &[], &calls), None);
+    }
+
     // =========================================================================
     // CL node selection tests
     // =========================================================================
@@ -169,7 +763,7 @@ mod tests {
             make_cl_node("prysm-1", true),
         ];
 
-        let selected = select_cl_node(&nodes);
+        let selected = select_cl_node(&nodes, false);
 
         assert!(selected.is_some(), "Should select a healthy CL node");
         assert_eq!(selected.unwrap().name, "lighthouse-1");
@@ -182,7 +776,7 @@ mod tests {
             make_cl_node("prysm-1", true),       // healthy
         ];
 
-        let selected = select_cl_node(&nodes);
+        let selected = select_cl_node(&nodes, false);
 
         assert!(selected.is_some());
         assert_eq!(
@@ -199,11 +793,76 @@ mod tests {
             make_cl_node("prysm-1", false),
         ];
 
-        let selected = select_cl_node(&nodes);
+        let selected = select_cl_node(&nodes, false);
 
         assert!(
             selected.is_none(),
             "Should return None when all CL nodes unhealthy"
         );
     }
+
+    fn cl_rule(paths: &[&str], methods: &[&str], action: ClRouteAction) -> ClRoute {
+        ClRoute {
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+            action,
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn test_cl_route_first_match_wins() {
+        let routes = vec![
+            cl_rule(&["/eth/v1/node/health"], &[], ClRouteAction::Allow),
+            cl_rule(&["/eth/v1/node/*"], &["GET"], ClRouteAction::Deny),
+        ];
+
+        let health = cl_route(&routes, "GET", "/eth/v1/node/health").unwrap();
+        assert_eq!(health.action, ClRouteAction::Allow);
+
+        let peers = cl_route(&routes, "GET", "/eth/v1/node/peers").unwrap();
+        assert_eq!(peers.action, ClRouteAction::Deny);
+
+        assert!(cl_route(&routes, "POST", "/eth/v1/node/peers").is_none());
+        assert!(cl_route(&routes, "GET", "/eth/v1/beacon/genesis").is_none());
+    }
+
+    #[test]
+    fn test_select_cl_node_for_tag() {
+        let mut debug = make_cl_node("debug-1", true);
+        debug.tags = vec!["debug".to_string()];
+        let nodes = vec![make_cl_node("cl-1", true), debug];
+
+        let selected = select_cl_node_for_tag(&nodes, false, &[], Some("debug")).unwrap();
+        assert_eq!(selected.name, "debug-1");
+
+        let selected = select_cl_node_for_tag(&nodes, false, &[], None).unwrap();
+        assert_eq!(selected.name, "cl-1");
+
+        // Falls through to any healthy node when the tagged group is down
+        let mut nodes = nodes;
+        nodes[1].is_healthy = false;
+        let selected = select_cl_node_for_tag(&nodes, false, &[], Some("debug")).unwrap();
+        assert_eq!(selected.name, "cl-1");
+    }
+
+    #[test]
+    fn test_select_cl_node_keeps_untagged_requests_off_routed_nodes() {
+        let mut debug = make_cl_node("debug-1", true);
+        debug.tags = vec!["debug".to_string()];
+        let nodes = vec![debug, make_cl_node("cl-1", true)];
+        let routes = vec![ClRoute {
+            tag: Some("debug".to_string()),
+            ..cl_rule(&["/eth/v*/debug/**"], &[], ClRouteAction::Route)
+        }];
+
+        let selected = select_cl_node_for_tag(&nodes, false, &routes, None).unwrap();
+        assert_eq!(selected.name, "cl-1");
+
+        // Routed nodes still serve everything when no untagged node is healthy
+        let mut nodes = nodes;
+        nodes[1].is_healthy = false;
+        let selected = select_cl_node_for_tag(&nodes, false, &routes, None).unwrap();
+        assert_eq!(selected.name, "debug-1");
+    }
 }
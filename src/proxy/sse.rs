@@ -0,0 +1,462 @@
+//! Streaming proxy for the beacon API event stream (`/eth/v1/events`)
+//!
+//! Events are forwarded as they arrive instead of being buffered. When the upstream
+//! node becomes unhealthy or drops the stream, the proxy reconnects to another healthy
+//! CL node and skips events the client has already received, so the client keeps one
+//! uninterrupted stream.
+
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderValue, Method, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::metrics::VixyMetrics;
+use crate::proxy::http::convert_response;
+use crate::proxy::selection;
+use crate::state::AppState;
+use crate::telemetry;
+
+/// Beacon API path of the event stream
+const EVENTS_PATH: &str = "/eth/v1/events";
+
+/// Timeout for establishing an upstream event stream
+pub(crate) const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay between reconnection attempts while no CL node is available
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How often the health of the current upstream node is checked
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of recent events remembered to skip duplicates after a reconnect
+const DEDUP_WINDOW: usize = 1024;
+
+/// Event fields identifying the same event across beacon nodes
+///
+/// Other fields (e.g. `execution_optimistic`) may legitimately differ between nodes.
+const ID_FIELDS: &[&str] = &[
+    "slot",
+    "block",
+    "block_root",
+    "root",
+    "state",
+    "epoch",
+    "index",
+    "validator_index",
+];
+
+/// Check whether a CL request subscribes to the event stream
+pub fn is_event_stream(method: &Method, cl_path: &str) -> bool {
+    method == Method::GET && cl_path.trim_end_matches('/') == EVENTS_PATH
+}
+
+/// A complete server-sent event
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SseEvent {
+    /// Event type (`event:` field), e.g. "head"
+    event: Option<String>,
+    /// Event payload (`data:` lines joined by newlines)
+    data: String,
+}
+
+impl SseEvent {
+    /// Serialize the event for the client
+    fn encode(&self) -> Bytes {
+        let mut out = String::new();
+        if let Some(event) = &self.event {
+            out.push_str(&format!("event: {event}\n"));
+        }
+        for line in self.data.split('\n') {
+            out.push_str(&format!("data: {line}\n"));
+        }
+        out.push('\n');
+        Bytes::from(out)
+    }
+
+    /// Key identifying the event regardless of which node emitted it
+    fn dedup_key(&self) -> String {
+        let event = self.event.as_deref().unwrap_or("message");
+        let Ok(Value::Object(data)) = serde_json::from_str::<Value>(&self.data) else {
+            return format!("{event}:{}", self.data);
+        };
+
+        let ids: Vec<String> = ID_FIELDS
+            .iter()
+            .filter_map(|field| data.get(*field).map(|v| format!("{field}={v}")))
+            .collect();
+        if ids.is_empty() {
+            // Maps are ordered, so the serialization is canonical
+            format!("{event}:{}", Value::Object(data))
+        } else {
+            format!("{event}:{}", ids.join(","))
+        }
+    }
+}
+
+/// Incremental parser turning upstream chunks into complete events
+#[derive(Debug, Default)]
+struct EventParser {
+    buffer: Vec<u8>,
+}
+
+impl EventParser {
+    /// Feed a chunk and return the events it completes
+    ///
+    /// Comments and keep-alives (events without data) are dropped.
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend(chunk.iter().filter(|&&b| b != b'\r'));
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            if let Some(event) = parse_event(&String::from_utf8_lossy(&block)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+}
+
+/// Parse one event block (the lines before a blank line)
+fn parse_event(block: &str) -> Option<SseEvent> {
+    let mut event = None;
+    let mut data: Vec<&str> = Vec::new();
+
+    for line in block.lines() {
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = Some(value.to_string()),
+            "data" => data.push(value),
+            // Comments, ids and retry hints are not forwarded
+            _ => {}
+        }
+    }
+
+    (!data.is_empty()).then(|| SseEvent {
+        event,
+        data: data.join("\n"),
+    })
+}
+
+/// Bounded set of recently forwarded events
+#[derive(Debug)]
+struct RecentEvents {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl RecentEvents {
+    fn new(capacity: usize) -> Self {
+        Self {
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Remember an event, returning false if it was already seen
+    fn insert(&mut self, key: String) -> bool {
+        if self.seen.contains(&key) {
+            return false;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.seen.insert(key.clone());
+        self.order.push_back(key);
+        true
+    }
+}
+
+/// Decrements the active stream gauge when a stream ends
+struct StreamGuard;
+
+impl StreamGuard {
+    fn new() -> Self {
+        VixyMetrics::inc_cl_sse_streams();
+        Self
+    }
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        VixyMetrics::dec_cl_sse_streams();
+    }
+}
+
+/// Select a healthy CL node for the stream, preferring nodes other than `avoid`
+async fn select_node(
+    state: &AppState,
+    tag: Option<&str>,
+    avoid: Option<&str>,
+) -> Option<(String, String)> {
+    let failover_active = state.cl_failover_active.load(Ordering::SeqCst);
+    let cl_nodes = state.cl_nodes.read().await;
+    let others: Vec<_> = cl_nodes
+        .iter()
+        .filter(|n| Some(n.name.as_str()) != avoid)
+        .cloned()
+        .collect();
+    let routes = &state.cl_routes;
+    selection::select_cl_node_for_tag(&others, failover_active, routes, tag)
+        .or_else(|| selection::select_cl_node_for_tag(&cl_nodes, failover_active, routes, tag))
+        .map(|n| (n.name.clone(), n.url.clone()))
+}
+
+/// Check whether a CL node is still healthy
+async fn is_node_healthy(state: &AppState, node_name: &str) -> bool {
+    let cl_nodes = state.cl_nodes.read().await;
+    cl_nodes
+        .iter()
+        .find(|n| n.name == node_name)
+        .is_some_and(|n| n.is_healthy)
+}
+
+/// Open the event stream on a CL node
+async fn connect(
+    state: &AppState,
+    base_url: &str,
+    path_and_query: &str,
+) -> reqwest::Result<reqwest::Response> {
+    let url = format!("{}{path_and_query}", base_url.trim_end_matches('/'));
+    state
+        .stream_client
+        .get(url)
+        .headers(telemetry::upstream_headers())
+        .header(header::ACCEPT, "text/event-stream")
+        .send()
+        .await
+}
+
+/// Proxy an event stream subscription
+///
+/// `path_and_query` is the beacon API path with its query (e.g.
+/// `/eth/v1/events?topics=head`). Upstream errors on the initial connection, such as
+/// an invalid topic, are returned to the client as-is.
+pub async fn proxy_event_stream(
+    state: Arc<AppState>,
+    path_and_query: String,
+    tag: Option<String>,
+) -> Response {
+    let Some((node_name, node_url)) = select_node(&state, tag.as_deref(), None).await else {
+        warn!("No healthy CL node available");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "No healthy CL node available",
+        )
+            .into_response();
+    };
+
+    let upstream = match connect(&state, &node_url, &path_and_query).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => return convert_response(response).await,
+        Err(e) => {
+            warn!(node = %node_name, error = %e, "Failed to open beacon event stream");
+            return (StatusCode::BAD_GATEWAY, "Upstream request failed").into_response();
+        }
+    };
+
+    info!(node = %node_name, path = %path_and_query, "Opened beacon event stream");
+
+    let (tx, mut rx) = mpsc::channel::<Bytes>(64);
+    tokio::spawn(telemetry::propagate(stream_events(
+        state,
+        path_and_query,
+        tag,
+        node_name,
+        upstream,
+        tx,
+    )));
+
+    let body = futures_util::stream::poll_fn(move |cx| {
+        rx.poll_recv(cx).map(|chunk| chunk.map(Ok::<_, Infallible>))
+    });
+    let mut response = Body::from_stream(body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/event-stream"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+/// Forward events to the client, switching upstream nodes as needed
+///
+/// Runs until the client disconnects.
+async fn stream_events(
+    state: Arc<AppState>,
+    path_and_query: String,
+    tag: Option<String>,
+    mut node_name: String,
+    mut upstream: reqwest::Response,
+    tx: mpsc::Sender<Bytes>,
+) {
+    let _guard = StreamGuard::new();
+    let mut recent = RecentEvents::new(DEDUP_WINDOW);
+
+    loop {
+        let connected_at = Instant::now();
+        let mut parser = EventParser::default();
+        let mut health_check = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+
+        let reason = loop {
+            tokio::select! {
+                chunk = upstream.chunk() => match chunk {
+                    Ok(Some(chunk)) => {
+                        for event in parser.push(&chunk) {
+                            if !recent.insert(event.dedup_key()) {
+                                VixyMetrics::inc_cl_sse_event("duplicate");
+                                continue;
+                            }
+                            VixyMetrics::inc_cl_sse_event("forwarded");
+                            if tx.send(event.encode()).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Ok(None) => break "closed",
+                    Err(e) => {
+                        debug!(node = %node_name, error = %e, "Beacon event stream failed");
+                        break "error";
+                    }
+                },
+                _ = health_check.tick() => {
+                    if !is_node_healthy(&state, &node_name).await {
+                        break "unhealthy";
+                    }
+                }
+                _ = tx.closed() => return,
+            }
+        };
+
+        warn!(node = %node_name, reason, "Beacon event stream upstream lost, reconnecting");
+
+        // Avoid a tight loop against nodes that close the stream right away
+        if let Some(remaining) = RECONNECT_DELAY.checked_sub(connected_at.elapsed()) {
+            tokio::select! {
+                _ = tokio::time::sleep(remaining) => {}
+                _ = tx.closed() => return,
+            }
+        }
+
+        // Reconnect, preferring another node, until one accepts or the client leaves
+        loop {
+            if tx.is_closed() {
+                return;
+            }
+
+            if let Some((name, url)) = select_node(&state, tag.as_deref(), Some(&node_name)).await {
+                match connect(&state, &url, &path_and_query).await {
+                    Ok(response) if response.status().is_success() => {
+                        info!(old_node = %node_name, new_node = %name, "Switched beacon event stream");
+                        VixyMetrics::inc_cl_sse_reconnection(reason);
+                        node_name = name;
+                        upstream = response;
+                        break;
+                    }
+                    Ok(response) => {
+                        warn!(node = %name, status = %response.status(), "Beacon event stream rejected");
+                    }
+                    Err(e) => {
+                        warn!(node = %name, error = %e, "Failed to reopen beacon event stream");
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                _ = tx.closed() => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_event_stream() {
+        assert!(is_event_stream(&Method::GET, "/eth/v1/events"));
+        assert!(is_event_stream(&Method::GET, "/eth/v1/events/"));
+        assert!(!is_event_stream(&Method::POST, "/eth/v1/events"));
+        assert!(!is_event_stream(&Method::GET, "/eth/v1/node/health"));
+    }
+
+    #[test]
+    fn test_parser_handles_split_chunks() {
+        let mut parser = EventParser::default();
+
+        assert!(parser.push(b"event: head\ndata: {\"slot\":").is_empty());
+        let events = parser.push(b"\"1\"}\n\n: keep-alive\n\nevent: block\r\ndata: {}\r\n\r\n");
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: Some("head".to_string()),
+                    data: r#"{"slot":"1"}"#.to_string(),
+                },
+                SseEvent {
+                    event: Some("block".to_string()),
+                    data: "{}".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_round_trips() {
+        let event = SseEvent {
+            event: Some("head".to_string()),
+            data: "line1\nline2".to_string(),
+        };
+
+        let mut parser = EventParser::default();
+        assert_eq!(parser.push(&event.encode()), vec![event]);
+    }
+
+    #[test]
+    fn test_dedup_key_ignores_node_specific_fields() {
+        let from_a = SseEvent {
+            event: Some("head".to_string()),
+            data: r#"{"slot":"10","block":"0xab","execution_optimistic":false}"#.to_string(),
+        };
+        let from_b = SseEvent {
+            event: Some("head".to_string()),
+            data: r#"{"execution_optimistic":true,"block":"0xab","slot":"10"}"#.to_string(),
+        };
+        let next = SseEvent {
+            event: Some("head".to_string()),
+            data: r#"{"slot":"11","block":"0xcd"}"#.to_string(),
+        };
+
+        assert_eq!(from_a.dedup_key(), from_b.dedup_key());
+        assert_ne!(from_a.dedup_key(), next.dedup_key());
+    }
+
+    #[test]
+    fn test_recent_events_window() {
+        let mut recent = RecentEvents::new(2);
+
+        assert!(recent.insert("a".to_string()));
+        assert!(!recent.insert("a".to_string()));
+        assert!(recent.insert("b".to_string()));
+        assert!(recent.insert("c".to_string()));
+
+        // "a" fell out of the window
+        assert!(recent.insert("a".to_string()));
+    }
+}
@@ -1,22 +1,28 @@
 //! WebSocket proxy for EL subscriptions with health-aware reconnection
 
-use axum::extract::State;
 use axum::extract::WebSocketUpgrade;
 use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{Extension, State};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 use tokio::sync::{Mutex, mpsc};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request as UpstreamRequest;
 use tokio_tungstenite::{connect_async, tungstenite::Message as TungsteniteMessage};
-use tracing::{debug, error, info, warn};
+use tracing::{Instrument, debug, error, info, info_span, warn};
 
+use crate::auth::{ApiKeyState, AuthorizedKey, ProxyRoute};
+use crate::config::MethodFilter;
+use crate::events::{EventKind, EventLog};
 use crate::metrics::VixyMetrics;
-use crate::proxy::selection;
+use crate::proxy::{jsonrpc, selection};
+use crate::request_id::{self, RequestId};
 use crate::state::AppState;
 
 // ============================================================================
@@ -120,6 +126,330 @@ impl SubscriptionTracker {
     }
 }
 
+// ============================================================================
+// In-flight Request Tracking for Failover
+// ============================================================================
+
+/// Error message returned for requests that cannot be safely re-sent after failover
+const INFLIGHT_FAILED_MESSAGE: &str = "upstream connection changed before the request was answered";
+
+/// Subscription calls are never re-sent after failover: confirmed subscriptions are
+/// restored by the subscription tracker, so re-sending could subscribe twice
+const SUBSCRIPTION_METHODS: &[&str] = &["eth_subscribe", "eth_unsubscribe"];
+
+/// A client call forwarded upstream that has not been answered yet
+#[derive(Debug, Clone)]
+struct InFlightCall {
+    /// Sequence number of the batch the call was sent in, if any
+    batch: Option<u64>,
+    /// Serialized JSON-RPC id, which the response carries
+    id: String,
+    /// The original JSON-RPC call object
+    call: Value,
+}
+
+/// Tracks client requests awaiting an upstream response so they can be
+/// recovered when the upstream connection is replaced
+#[derive(Debug, Default)]
+pub struct InFlightTracker {
+    /// Pending calls by the order they were sent in; clients may reuse ids, so calls
+    /// sharing an id are answered oldest first
+    calls: BTreeMap<u64, InFlightCall>,
+    next_seq: u64,
+}
+
+/// What to do with an unanswered request message after switching upstream
+#[derive(Debug, Clone, PartialEq)]
+pub enum InFlightRecovery {
+    /// Re-send the message to the new upstream
+    Resend(Value),
+    /// Answer the client directly with this error response
+    Reply(Value),
+}
+
+impl InFlightTracker {
+    /// Create a new empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a request message (single call or batch) forwarded upstream
+    pub fn track(&mut self, message: &Value) {
+        match message {
+            Value::Array(calls) => {
+                let batch = self.next_seq;
+                self.next_seq += 1;
+                for call in calls {
+                    self.insert(call, Some(batch));
+                }
+            }
+            Value::Object(_) => self.insert(message, None),
+            _ => {}
+        }
+    }
+
+    fn insert(&mut self, call: &Value, batch: Option<u64>) {
+        // Notifications never get a response, so there is nothing to wait for
+        let (Some(id), Some(_)) = (jsonrpc::id_of(call), jsonrpc::method_of(call)) else {
+            return;
+        };
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.calls.insert(
+            seq,
+            InFlightCall {
+                batch,
+                id: id.to_string(),
+                call: call.clone(),
+            },
+        );
+    }
+
+    /// Mark the requests answered by an upstream response message as complete
+    pub fn complete(&mut self, message: &Value) {
+        let responses = match message {
+            Value::Array(responses) => responses.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        for response in responses {
+            // Only responses carry a result or error; requests and notifications don't
+            if response.get("result").is_none() && response.get("error").is_none() {
+                continue;
+            }
+            let Some(id) = jsonrpc::id_of(response).map(Value::to_string) else {
+                continue;
+            };
+            if let Some(seq) = self
+                .calls
+                .iter()
+                .find(|(_, pending)| pending.id == id)
+                .map(|(seq, _)| *seq)
+            {
+                self.calls.remove(&seq);
+            }
+        }
+    }
+
+    /// Number of requests awaiting a response
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Check if no requests are awaiting a response
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Take all unanswered requests, regrouped into the messages they were sent in
+    pub fn take_all(&mut self) -> Vec<Value> {
+        let mut messages: BTreeMap<u64, (bool, Vec<Value>)> = BTreeMap::new();
+        for (seq, pending) in std::mem::take(&mut self.calls) {
+            // Calls are taken in the order they were sent
+            messages
+                .entry(pending.batch.unwrap_or(seq))
+                .or_insert_with(|| (pending.batch.is_some(), Vec::new()))
+                .1
+                .push(pending.call);
+        }
+
+        messages
+            .into_values()
+            .map(|(is_batch, mut calls)| {
+                if is_batch {
+                    Value::Array(calls)
+                } else {
+                    calls.swap_remove(0)
+                }
+            })
+            .collect()
+    }
+}
+
+/// Decide how to recover an unanswered request message after switching upstream
+///
+/// Messages made up only of idempotent calls are re-sent as-is. Anything containing
+/// a call with side effects (e.g. eth_sendRawTransaction) is answered with an error,
+/// since the old node may or may not have executed it, and so are subscription calls.
+pub fn plan_recovery(message: Value) -> InFlightRecovery {
+    let calls = match &message {
+        Value::Array(calls) => calls.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    let idempotent = calls.iter().all(|call| {
+        jsonrpc::method_of(call)
+            .is_some_and(|m| jsonrpc::is_idempotent(m) && !SUBSCRIPTION_METHODS.contains(&m))
+    });
+    if idempotent {
+        return InFlightRecovery::Resend(message);
+    }
+
+    let error_for = |call: &Value| {
+        jsonrpc::error_response(
+            jsonrpc::id_of(call).cloned().unwrap_or(Value::Null),
+            jsonrpc::INTERNAL_ERROR,
+            INFLIGHT_FAILED_MESSAGE,
+        )
+    };
+    match &message {
+        Value::Array(calls) => {
+            InFlightRecovery::Reply(Value::Array(calls.iter().map(error_for).collect()))
+        }
+        call => InFlightRecovery::Reply(error_for(call)),
+    }
+}
+
+// ============================================================================
+// Client Policy
+// ============================================================================
+
+/// Limits applied to messages a client sends over its connection
+#[derive(Debug)]
+struct ClientPolicy {
+    /// API key the connection was authenticated with
+    api_key: Option<Arc<ApiKeyState>>,
+    /// Global EL method filter
+    method_filter: MethodFilter,
+}
+
+impl ClientPolicy {
+    /// Name used in metrics for the client
+    fn key_name(&self) -> &str {
+        self.api_key
+            .as_ref()
+            .map_or("anonymous", |k| k.name.as_str())
+    }
+
+    /// Check a call against the global and per-key method filters
+    fn allows(&self, call: &Value) -> bool {
+        let Some(method) = jsonrpc::method_of(call) else {
+            return true;
+        };
+        self.method_filter.permits(method)
+            && self
+                .api_key
+                .as_ref()
+                .is_none_or(|key| key.method_filter.permits(method))
+    }
+
+    /// Decide how to handle a client message under the method filters
+    ///
+    /// Like HTTP batches, a batch with disallowed methods is forwarded without them;
+    /// their rejections are merged into the upstream reply in the original call order.
+    fn check(&self, message: &Value) -> PolicyDecision {
+        match message {
+            Value::Array(calls) => {
+                let mut forwarded = Vec::with_capacity(calls.len());
+                let mut rejected = Vec::new();
+                for (position, call) in calls.iter().enumerate() {
+                    if self.allows(call) {
+                        forwarded.push(call.clone());
+                        continue;
+                    }
+                    VixyMetrics::inc_el_method_rejection(self.key_name(), "el_ws");
+                    if jsonrpc::id_of(call).is_some() {
+                        rejected.push((position, jsonrpc::method_not_allowed(call)));
+                    }
+                }
+
+                if forwarded.len() == calls.len() {
+                    PolicyDecision::Forward
+                } else if !forwarded.is_empty() {
+                    PolicyDecision::Partial(PartialBatch {
+                        calls: calls.clone(),
+                        forwarded,
+                        rejected,
+                    })
+                } else if rejected.is_empty() {
+                    // Only notifications, which get no response
+                    PolicyDecision::Drop
+                } else {
+                    PolicyDecision::Reply(Value::Array(
+                        rejected.into_iter().map(|(_, r)| r).collect(),
+                    ))
+                }
+            }
+            call if !self.allows(call) => {
+                VixyMetrics::inc_el_method_rejection(self.key_name(), "el_ws");
+                match jsonrpc::id_of(call) {
+                    Some(_) => PolicyDecision::Reply(jsonrpc::method_not_allowed(call)),
+                    None => PolicyDecision::Drop,
+                }
+            }
+            _ => PolicyDecision::Forward,
+        }
+    }
+}
+
+/// How a client message is handled
+#[derive(Debug, PartialEq)]
+enum PolicyDecision {
+    /// Forward the message unchanged
+    Forward,
+    /// Answer the client with this reply instead of forwarding the message
+    Reply(Value),
+    /// Neither forward nor answer the message
+    Drop,
+    /// Forward the allowed calls of a batch and answer the others locally
+    Partial(PartialBatch),
+}
+
+/// A batch forwarded without its disallowed calls
+#[derive(Debug, PartialEq)]
+struct PartialBatch {
+    /// The batch as sent by the client
+    calls: Vec<Value>,
+    /// The calls forwarded upstream
+    forwarded: Vec<Value>,
+    /// Responses to the disallowed calls, with their positions in `calls`
+    rejected: Vec<(usize, Value)>,
+}
+
+impl PartialBatch {
+    /// Serialized ids of the forwarded calls, sorted, which the upstream reply answers
+    fn expected_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .forwarded
+            .iter()
+            .filter_map(jsonrpc::id_of)
+            .map(Value::to_string)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Build the reply to the whole batch from the upstream reply to the forwarded calls
+    fn reply(self, upstream: Vec<Value>) -> Value {
+        Value::Array(jsonrpc::merge_batch_responses(
+            &self.calls,
+            self.rejected,
+            upstream,
+        ))
+    }
+}
+
+/// Partially forwarded batches awaiting their upstream reply
+#[derive(Debug, Default)]
+struct PartialBatches {
+    batches: Vec<PartialBatch>,
+}
+
+impl PartialBatches {
+    /// Complete the reply to a partially forwarded batch with an upstream batch reply
+    ///
+    /// Returns the reply to the whole batch if `responses` answers the forwarded calls
+    /// of a waiting batch, matched by their ids.
+    fn complete(&mut self, responses: &[Value]) -> Option<Value> {
+        let mut ids: Vec<String> = responses
+            .iter()
+            .filter_map(jsonrpc::id_of)
+            .map(Value::to_string)
+            .collect();
+        ids.sort();
+        let index = self.batches.iter().position(|b| b.expected_ids() == ids)?;
+        Some(self.batches.remove(index).reply(responses.to_vec()))
+    }
+}
+
 /// Information about a new upstream connection for reconnection
 #[derive(Debug)]
 struct ReconnectInfo {
@@ -200,7 +530,32 @@ async fn health_monitor(
 // ============================================================================
 
 /// Handle EL WebSocket upgrade requests (GET /el/ws)
-pub async fn el_ws_handler(State(state): State<Arc<AppState>>, ws: WebSocketUpgrade) -> Response {
+pub async fn el_ws_handler(
+    State(state): State<Arc<AppState>>,
+    api_key: Option<Extension<AuthorizedKey>>,
+    request_id: Option<Extension<RequestId>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let request_id = request_id.map_or_else(RequestId::generate, |Extension(id)| id);
+
+    // Enforce the API key's concurrent connection cap
+    let api_key = api_key.map(|Extension(AuthorizedKey(key))| key);
+    let ws_slot = match &api_key {
+        Some(key) => match key.try_acquire_ws() {
+            Some(slot) => Some(slot),
+            None => {
+                warn!(key = %key.name, "API key reached its WebSocket connection limit");
+                VixyMetrics::inc_api_key_request(
+                    &key.name,
+                    ProxyRoute::ElWs.label(),
+                    "rate_limited",
+                );
+                return ProxyRoute::ElWs.rate_limited_response(Value::Null, None);
+            }
+        },
+        None => None,
+    };
+
     // Read the failover flag
     let failover_active = state.el_failover_active.load(Ordering::SeqCst);
 
@@ -228,7 +583,15 @@ pub async fn el_ws_handler(State(state): State<Arc<AppState>>, ws: WebSocketUpgr
     );
 
     // Upgrade the WebSocket connection and handle it with health monitoring
-    ws.on_upgrade(move |socket| handle_websocket(socket, state, node_name, ws_url))
+    let span = info_span!("el_ws_connection", request_id = request_id.as_str());
+    ws.on_upgrade(move |socket| {
+        async move {
+            // Hold the connection slot for the lifetime of the connection
+            let _ws_slot = ws_slot;
+            handle_websocket(socket, state, node_name, ws_url, api_key, request_id).await
+        }
+        .instrument(span)
+    })
 }
 
 /// Handle the WebSocket connection with health-aware reconnection
@@ -237,7 +600,14 @@ async fn handle_websocket(
     state: Arc<AppState>,
     initial_node_name: String,
     initial_ws_url: String,
+    api_key: Option<Arc<ApiKeyState>>,
+    request_id: RequestId,
 ) {
+    let policy = ClientPolicy {
+        api_key,
+        method_filter: state.el_method_filter.clone(),
+    };
+
     // Track connection metrics
     VixyMetrics::inc_ws_connections();
     VixyMetrics::set_ws_upstream_node(&initial_node_name, true);
@@ -254,9 +624,12 @@ async fn handle_websocket(
     // Spawn health monitor
     let health_state = state.clone();
     let health_node_name = current_node_name.clone();
-    let _health_monitor = tokio::spawn(async move {
-        health_monitor(health_state, health_node_name, reconnect_tx).await;
-    });
+    let _health_monitor = tokio::spawn(
+        async move {
+            health_monitor(health_state, health_node_name, reconnect_tx).await;
+        }
+        .in_current_span(),
+    );
 
     // Run the proxy loop with reconnection support
     run_proxy_loop(
@@ -265,6 +638,9 @@ async fn handle_websocket(
         tracker.clone(),
         current_node_name.clone(),
         reconnect_rx,
+        policy,
+        &request_id,
+        &state.events,
     )
     .await;
 
@@ -281,15 +657,22 @@ async fn handle_websocket(
 }
 
 /// Main proxy loop handling message forwarding and reconnection
+#[allow(clippy::too_many_arguments)]
 async fn run_proxy_loop(
     client_socket: WebSocket,
     initial_ws_url: String,
     tracker: Arc<Mutex<SubscriptionTracker>>,
     current_node_name: Arc<Mutex<String>>,
     mut reconnect_rx: mpsc::Receiver<ReconnectInfo>,
+    policy: ClientPolicy,
+    request_id: &RequestId,
+    events: &EventLog,
 ) {
     // Connect to initial upstream
-    let upstream_result = connect_async(&initial_ws_url).await;
+    let upstream_result = match upstream_request(&initial_ws_url, request_id) {
+        Ok(request) => connect_async(request).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
     let (upstream_ws, _) = match upstream_result {
         Ok((ws, resp)) => (ws, resp),
         Err(e) => {
@@ -322,16 +705,33 @@ async fn run_proxy_loop(
     // Track pending subscribe requests: rpc_id -> (params, response_tx)
     let pending_subscribes: Arc<Mutex<PendingSubscribes>> = Arc::new(Mutex::new(HashMap::new()));
 
+    // Track requests awaiting a response so they survive an upstream switch
+    let in_flight = Arc::new(Mutex::new(InFlightTracker::new()));
+
+    // Track batches forwarded without their disallowed calls
+    let partial_batches = Arc::new(Mutex::new(PartialBatches::default()));
+
+    // Client messages are numbered to tell them apart in logs
+    let mut message_count = 0u64;
+
     loop {
         tokio::select! {
             // Handle messages from client
             Some(msg) = client_msg_rx.recv() => {
+                message_count += 1;
+                let message_id = format!("{}-{message_count}", request_id.as_str());
                 if let Err(should_close) = handle_client_message(
                     msg,
                     &upstream_sender,
+                    &client_sender,
                     &tracker,
                     &pending_subscribes,
-                ).await
+                    &in_flight,
+                    &partial_batches,
+                    &policy,
+                )
+                .instrument(info_span!("ws_message", message_id))
+                .await
                     && should_close
                 {
                     break;
@@ -345,6 +745,8 @@ async fn run_proxy_loop(
                     &client_sender,
                     &tracker,
                     &pending_subscribes,
+                    &in_flight,
+                    &partial_batches,
                 ).await
                     && should_close
                 {
@@ -371,6 +773,7 @@ async fn run_proxy_loop(
                     &reconnect_info.ws_url,
                     &tracker,
                     &upstream_sender,
+                    request_id,
                 ).await {
                     Ok((new_receiver, new_sender)) => {
                         // Replace upstream sender
@@ -381,12 +784,20 @@ async fn run_proxy_loop(
                         upstream_msg_rx = new_upstream_rx;
                         tokio::spawn(upstream_receiver_task(new_receiver, new_upstream_tx));
 
+                        // Responses to requests sent to the old upstream will never arrive
+                        recover_in_flight(&in_flight, &partial_batches, &upstream_sender, &client_sender).await;
+
                         // Update metrics for successful reconnection
                         VixyMetrics::inc_ws_reconnections();
                         VixyMetrics::inc_ws_reconnection_attempt("success");
                         VixyMetrics::set_ws_upstream_node(&old_node, false);
                         VixyMetrics::set_ws_upstream_node(&reconnect_info.node_name, true);
 
+                        events.record(EventKind::WsReconnect {
+                            from_node: old_node,
+                            to_node: reconnect_info.node_name,
+                        });
+
                         info!("WebSocket reconnection successful");
                     }
                     Err(e) => {
@@ -418,6 +829,50 @@ async fn run_proxy_loop(
     let _ = client_sender.lock().await.send(Message::Close(None)).await;
 }
 
+/// Re-send or fail requests that were still awaiting a response from the old upstream
+async fn recover_in_flight(
+    in_flight: &Arc<Mutex<InFlightTracker>>,
+    partial_batches: &Arc<Mutex<PartialBatches>>,
+    upstream_sender: &Arc<Mutex<UpstreamSender>>,
+    client_sender: &Arc<Mutex<ClientSender>>,
+) {
+    let messages = in_flight.lock().await.take_all();
+    if messages.is_empty() {
+        return;
+    }
+    info!(
+        count = messages.len(),
+        "Recovering in-flight WebSocket requests"
+    );
+
+    for message in messages {
+        match plan_recovery(message) {
+            InFlightRecovery::Resend(message) => {
+                VixyMetrics::inc_ws_inflight_recovered("resent");
+                in_flight.lock().await.track(&message);
+                let _ = upstream_sender
+                    .lock()
+                    .await
+                    .send(TungsteniteMessage::Text(message.to_string().into()))
+                    .await;
+            }
+            InFlightRecovery::Reply(mut response) => {
+                VixyMetrics::inc_ws_inflight_recovered("failed");
+                if let Value::Array(responses) = &response
+                    && let Some(reply) = partial_batches.lock().await.complete(responses)
+                {
+                    response = reply;
+                }
+                let _ = client_sender
+                    .lock()
+                    .await
+                    .send(Message::Text(response.to_string().into()))
+                    .await;
+            }
+        }
+    }
+}
+
 /// Task that receives messages from the client WebSocket
 async fn client_receiver_task(
     mut receiver: futures_util::stream::SplitStream<WebSocket>,
@@ -454,19 +909,85 @@ async fn upstream_receiver_task(
 
 /// Handle a message from the client, forwarding to upstream
 /// Returns Err(true) if connection should close, Err(false) for recoverable errors
+#[allow(clippy::too_many_arguments)]
 async fn handle_client_message(
     msg: Message,
     upstream_sender: &Arc<Mutex<UpstreamSender>>,
+    client_sender: &Arc<Mutex<ClientSender>>,
     tracker: &Arc<Mutex<SubscriptionTracker>>,
     pending_subscribes: &Arc<Mutex<PendingSubscribes>>,
+    in_flight: &Arc<Mutex<InFlightTracker>>,
+    partial_batches: &Arc<Mutex<PartialBatches>>,
+    policy: &ClientPolicy,
 ) -> Result<(), bool> {
     match msg {
         Message::Text(text) => {
             debug!(direction = "client->upstream", "Forwarding text message");
             VixyMetrics::inc_ws_messages("upstream");
 
+            // Replaces the message when only part of a batch is forwarded
+            let mut outgoing = None;
+
             // Check if this is an eth_subscribe or eth_unsubscribe request
             if let Ok(json) = serde_json::from_str::<Value>(text.as_str()) {
+                // Answer rate-limited or disallowed requests without forwarding them
+                let decision = match &policy.api_key {
+                    Some(key) if key.charge(Some(&json)).is_err() => {
+                        VixyMetrics::inc_api_key_request(
+                            &key.name,
+                            ProxyRoute::ElWs.label(),
+                            "rate_limited",
+                        );
+                        PolicyDecision::Reply(rate_limited_reply(&json))
+                    }
+                    _ => policy.check(&json),
+                };
+                let json = match decision {
+                    PolicyDecision::Forward => json,
+                    PolicyDecision::Reply(response) => {
+                        if client_sender
+                            .lock()
+                            .await
+                            .send(Message::Text(response.to_string().into()))
+                            .await
+                            .is_err()
+                        {
+                            return Err(true);
+                        }
+                        return Ok(());
+                    }
+                    PolicyDecision::Drop => return Ok(()),
+                    PolicyDecision::Partial(batch) => {
+                        let forwarded = Value::Array(batch.forwarded.clone());
+                        outgoing = Some(forwarded.to_string());
+                        if batch.expected_ids().is_empty() {
+                            // The forwarded calls are notifications, so no reply will come
+                            let reply = batch.reply(Vec::new());
+                            if client_sender
+                                .lock()
+                                .await
+                                .send(Message::Text(reply.to_string().into()))
+                                .await
+                                .is_err()
+                            {
+                                return Err(true);
+                            }
+                        } else {
+                            partial_batches.lock().await.batches.push(batch);
+                        }
+                        forwarded
+                    }
+                };
+                if let Some(key) = &policy.api_key {
+                    VixyMetrics::inc_api_key_request(
+                        &key.name,
+                        ProxyRoute::ElWs.label(),
+                        "allowed",
+                    );
+                }
+
+                in_flight.lock().await.track(&json);
+
                 let method = json.get("method").and_then(|m| m.as_str());
                 let rpc_id = json.get("id").cloned();
 
@@ -495,7 +1016,9 @@ async fn handle_client_message(
             if upstream_sender
                 .lock()
                 .await
-                .send(TungsteniteMessage::Text(text.to_string().into()))
+                .send(TungsteniteMessage::Text(
+                    outgoing.unwrap_or_else(|| text.to_string()).into(),
+                ))
                 .await
                 .is_err()
             {
@@ -548,12 +1071,29 @@ async fn handle_client_message(
     Ok(())
 }
 
+/// Build the JSON-RPC error reply for a rate-limited WebSocket message
+fn rate_limited_reply(message: &Value) -> Value {
+    let error_for = |call: &Value| {
+        jsonrpc::error_response(
+            jsonrpc::id_of(call).cloned().unwrap_or(Value::Null),
+            jsonrpc::LIMIT_EXCEEDED,
+            "rate limit exceeded",
+        )
+    };
+    match message {
+        Value::Array(calls) => Value::Array(calls.iter().map(error_for).collect()),
+        call => error_for(call),
+    }
+}
+
 /// Handle a message from upstream, forwarding to client with ID translation
 async fn handle_upstream_message(
     msg: TungsteniteMessage,
     client_sender: &Arc<Mutex<ClientSender>>,
     tracker: &Arc<Mutex<SubscriptionTracker>>,
     pending_subscribes: &Arc<Mutex<PendingSubscribes>>,
+    in_flight: &Arc<Mutex<InFlightTracker>>,
+    partial_batches: &Arc<Mutex<PartialBatches>>,
 ) -> Result<(), bool> {
     match msg {
         TungsteniteMessage::Text(text) => {
@@ -564,6 +1104,15 @@ async fn handle_upstream_message(
 
             // Check if this is a subscription response or notification
             if let Ok(json) = serde_json::from_str::<Value>(&text_to_send) {
+                in_flight.lock().await.complete(&json);
+
+                // Add the rejections of a partially forwarded batch to its reply
+                if let Value::Array(responses) = &json
+                    && let Some(reply) = partial_batches.lock().await.complete(responses)
+                {
+                    text_to_send = reply.to_string();
+                }
+
                 // Check for subscription response (has "result" with subscription ID)
                 if let (Some(id), Some(result)) = (json.get("id"), json.get("result")) {
                     let id_str = id.to_string();
@@ -669,14 +1218,27 @@ async fn handle_upstream_message(
     Ok(())
 }
 
-/// Reconnect to a new upstream and replay subscriptions
+/// Build the handshake request to an upstream node, carrying the connection's request ID
+fn upstream_request(ws_url: &str, request_id: &RequestId) -> Result<UpstreamRequest, String> {
+    let mut request = ws_url
+        .into_client_request()
+        .map_err(|e| format!("Invalid upstream URL: {e}"))?;
+    request
+        .headers_mut()
+        .insert(request_id::HEADER, request_id.header_value());
+    Ok(request)
+}
+
+/// Reconnect to a new upstream, replay subscriptions and close the old upstream
 async fn reconnect_upstream(
     ws_url: &str,
     tracker: &Arc<Mutex<SubscriptionTracker>>,
-    _old_sender: &Arc<Mutex<UpstreamSender>>,
+    old_sender: &Arc<Mutex<UpstreamSender>>,
+    request_id: &RequestId,
 ) -> Result<(UpstreamReceiver, UpstreamSender), String> {
     // Connect to new upstream
-    let (new_ws, _) = connect_async(ws_url)
+    let request = upstream_request(ws_url, request_id)?;
+    let (new_ws, _) = connect_async(request)
         .await
         .map_err(|e| format!("Failed to connect: {e}"))?;
 
@@ -718,6 +1280,16 @@ async fn reconnect_upstream(
     // Note: The subscription responses will be handled by the normal message flow
     // and will update the upstream ID mappings via handle_upstream_message
 
+    // Close the old upstream so it doesn't linger; bounded in case the node is hung
+    let _ = tokio::time::timeout(Duration::from_secs(1), async {
+        old_sender
+            .lock()
+            .await
+            .send(TungsteniteMessage::Close(None))
+            .await
+    })
+    .await;
+
     Ok((new_receiver, new_sender))
 }
 
@@ -736,11 +1308,42 @@ mod tests {
             el_chain_head: std::sync::atomic::AtomicU64::new(0),
             cl_chain_head: std::sync::atomic::AtomicU64::new(0),
             el_failover_active: std::sync::atomic::AtomicBool::new(false),
+            cl_failover_active: std::sync::atomic::AtomicBool::new(false),
+            el_failover_since: std::sync::Mutex::new(None),
+            cl_failover_since: std::sync::Mutex::new(None),
+            el_all_down: std::sync::atomic::AtomicBool::new(false),
+            cl_all_down: std::sync::atomic::AtomicBool::new(false),
+            el_head_progress: std::sync::Mutex::new(crate::state::HeadProgress::new(0)),
+            cl_head_progress: std::sync::Mutex::new(crate::state::HeadProgress::new(0)),
+            chain_stall_timeout: Duration::ZERO,
+            el_routes: vec![],
+            el_method_filter: crate::config::MethodFilter::default(),
+            el_broadcast: crate::config::Broadcast::default(),
+            cl_broadcast: crate::config::ClBroadcast::default(),
+            cl_routes: vec![],
+            el_cache: None,
+            coalescer: None,
+            el_hedger: None,
+            el_retry_rules: crate::config::Retry::default().rules,
+            auth: None,
+            events: crate::events::EventLog::new(100),
+            outlier_detector: None,
+            el_metric_methods: crate::config::Metrics::default()
+                .el_methods
+                .into_iter()
+                .collect(),
             max_el_lag: 5,
             max_cl_lag: 3,
             proxy_timeout_ms: 30000,
+            http_client: reqwest::Client::new(),
+            stream_client: reqwest::Client::new(),
             max_retries: 2,
+            health_check_timeout: Duration::from_secs(1),
             health_check_max_failures: 3,
+            health_check_recovery_successes: 1,
+            failover_min_dwell: Duration::ZERO,
+            max_batch_size: 1000,
+            split_batches: false,
         })
     }
 
@@ -750,11 +1353,16 @@ mod tests {
             http_url: "http://localhost:8545".to_string(),
             ws_url: ws_url.to_string(),
             is_primary: true,
+            tags: vec![],
             block_number: 1000,
             check_ok: is_healthy,
             is_healthy,
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
 
@@ -936,4 +1544,222 @@ mod tests {
         assert!(tracker.has_subscriptions());
         assert_eq!(tracker.get_all_subscriptions().len(), 1);
     }
+
+    // =========================================================================
+    // In-flight Request Tracking Tests
+    // =========================================================================
+
+    #[test]
+    fn test_inflight_tracker_completes_on_response() {
+        let mut tracker = InFlightTracker::new();
+
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"}));
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "eth_chainId"}));
+        assert_eq!(tracker.len(), 2);
+
+        tracker.complete(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x10"}));
+
+        assert_eq!(tracker.len(), 1);
+        assert_eq!(
+            tracker.take_all(),
+            vec![serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "eth_chainId"})]
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_inflight_tracker_ignores_notifications() {
+        let mut tracker = InFlightTracker::new();
+
+        // Client notification without an id
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "method": "eth_blockNumber"}));
+        assert!(tracker.is_empty());
+
+        // Subscription notification from upstream must not complete request id 1
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_call"}));
+        tracker.complete(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "eth_subscription",
+            "params": {"subscription": "0x1", "result": {}}
+        }));
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn test_inflight_tracker_keeps_calls_with_duplicate_ids() {
+        let mut tracker = InFlightTracker::new();
+
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"}));
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"}));
+        assert_eq!(tracker.len(), 2);
+
+        // The oldest call with the id is answered first
+        tracker.complete(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x10"}));
+
+        assert_eq!(
+            tracker.take_all(),
+            vec![serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"})]
+        );
+    }
+
+    #[test]
+    fn test_inflight_tracker_regroups_batches() {
+        let mut tracker = InFlightTracker::new();
+
+        tracker.track(&serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "id": 3, "method": "eth_gasPrice"}
+        ]));
+        tracker.track(&serde_json::json!({"jsonrpc": "2.0", "id": 4, "method": "eth_call"}));
+
+        // Batch response answers only part of the batch
+        tracker.complete(&serde_json::json!([{"jsonrpc": "2.0", "id": 2, "result": "0x1"}]));
+
+        let messages = tracker.take_all();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0],
+            serde_json::json!([
+                {"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"},
+                {"jsonrpc": "2.0", "id": 3, "method": "eth_gasPrice"}
+            ])
+        );
+        assert_eq!(messages[1]["id"], 4);
+    }
+
+    #[test]
+    fn test_plan_recovery_resends_idempotent_requests() {
+        let request = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_call"});
+
+        assert_eq!(
+            plan_recovery(request.clone()),
+            InFlightRecovery::Resend(request)
+        );
+    }
+
+    #[test]
+    fn test_plan_recovery_fails_non_idempotent_requests() {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "eth_sendRawTransaction",
+            "params": ["0x00"]
+        });
+
+        let InFlightRecovery::Reply(response) = plan_recovery(request) else {
+            panic!("Expected an error reply");
+        };
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], jsonrpc::INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn test_plan_recovery_does_not_resend_subscriptions() {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "eth_subscribe",
+            "params": ["newHeads"]
+        });
+
+        let InFlightRecovery::Reply(response) = plan_recovery(request) else {
+            panic!("Expected an error reply");
+        };
+        assert_eq!(response["id"], 3);
+        assert_eq!(response["error"]["code"], jsonrpc::INTERNAL_ERROR);
+    }
+
+    #[test]
+    fn test_plan_recovery_fails_whole_batch_with_side_effects() {
+        let batch = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_blockNumber"},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_sendRawTransaction", "params": ["0x00"]}
+        ]);
+
+        let InFlightRecovery::Reply(response) = plan_recovery(batch) else {
+            panic!("Expected an error reply");
+        };
+        let responses = response.as_array().expect("Batch reply should be an array");
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["id"], 2);
+    }
+
+    fn deny_policy(deny: &[&str]) -> ClientPolicy {
+        ClientPolicy {
+            api_key: None,
+            method_filter: MethodFilter {
+                allow: vec![],
+                deny: deny.iter().map(|m| m.to_string()).collect(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_client_policy_rejects_denied_method() {
+        let policy = deny_policy(&["admin_*"]);
+
+        let allowed = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"});
+        assert_eq!(policy.check(&allowed), PolicyDecision::Forward);
+
+        let denied = serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "admin_peers"});
+        let PolicyDecision::Reply(response) = policy.check(&denied) else {
+            panic!("Expected a rejection");
+        };
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], jsonrpc::METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_client_policy_forwards_allowed_batch_calls() {
+        let policy = deny_policy(&["admin_*"]);
+        let batch = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "admin_peers"},
+            {"jsonrpc": "2.0", "id": 2, "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "id": 3, "method": "eth_blockNumber"}
+        ]);
+
+        let PolicyDecision::Partial(partial) = policy.check(&batch) else {
+            panic!("Expected the allowed calls to be forwarded");
+        };
+        assert_eq!(partial.forwarded, batch.as_array().unwrap()[1..].to_vec());
+
+        let mut waiting = PartialBatches::default();
+        waiting.batches.push(partial);
+        // Replies to other messages are passed through
+        assert!(
+            waiting
+                .complete(&[serde_json::json!({"jsonrpc": "2.0", "id": 9, "result": "0x1"})])
+                .is_none()
+        );
+
+        let reply = waiting
+            .complete(&[
+                serde_json::json!({"jsonrpc": "2.0", "id": 3, "result": "0x3e8"}),
+                serde_json::json!({"jsonrpc": "2.0", "id": 2, "result": "0x1"}),
+            ])
+            .expect("Reply should complete the batch");
+        let responses = reply.as_array().expect("Batch reply should be an array");
+        let ids: Vec<&Value> = responses.iter().map(|r| &r["id"]).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(responses[0]["error"]["code"], jsonrpc::METHOD_NOT_FOUND);
+        assert!(waiting.batches.is_empty());
+    }
+
+    #[test]
+    fn test_client_policy_rejects_fully_denied_batch() {
+        let policy = deny_policy(&["admin_*"]);
+        let batch = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "admin_peers"},
+            {"jsonrpc": "2.0", "method": "admin_addPeer"}
+        ]);
+
+        let PolicyDecision::Reply(response) = policy.check(&batch) else {
+            panic!("Expected a rejection");
+        };
+        let responses = response.as_array().expect("Batch reply should be an array");
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["error"]["code"], jsonrpc::METHOD_NOT_FOUND);
+    }
 }
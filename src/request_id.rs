@@ -0,0 +1,133 @@
+//! Request IDs for correlating client requests with logs
+//!
+//! Every proxied request carries an `X-Request-Id`: the client's own, if it sent a usable
+//! one, or a generated UUID. The ID is attached to the request's spans, forwarded to the
+//! upstream node and returned in the response headers.
+
+use std::future::Future;
+
+use axum::extract::Request;
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Header carrying the request ID
+pub const HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request ID that is accepted
+const MAX_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT: RequestId;
+}
+
+/// ID of a proxied request, found in its extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Generate a new random request ID
+    pub fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Use a client-supplied request ID if it is short printable ASCII
+    pub fn from_header(value: &HeaderValue) -> Option<Self> {
+        let id = value.to_str().ok()?;
+        let valid =
+            !id.is_empty() && id.len() <= MAX_LEN && id.bytes().all(|b| b.is_ascii_graphic());
+        valid.then(|| Self(id.to_string()))
+    }
+
+    /// The ID as a string
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The ID as a header value
+    pub fn header_value(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.0).expect("request IDs are printable ASCII")
+    }
+}
+
+/// ID of the request currently being served, if any
+pub fn current() -> Option<RequestId> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Run `future` as part of serving the request with ID `id`
+pub async fn scope<F: Future>(id: RequestId, future: F) -> F::Output {
+    CURRENT.scope(id, future).await
+}
+
+/// Middleware assigning every request an ID and returning it in the response
+pub async fn propagate(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&HEADER)
+        .and_then(RequestId::from_header)
+        .unwrap_or_else(RequestId::generate);
+    request.headers_mut().insert(HEADER, id.header_value());
+    request.extensions_mut().insert(id.clone());
+
+    let mut response = scope(id.clone(), next.run(request)).await;
+    response.headers_mut().insert(HEADER, id.header_value());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::extract::Extension;
+    use tower::ServiceExt;
+
+    async fn request_id_of(header: Option<&str>) -> (String, String) {
+        let app = Router::new()
+            .route(
+                "/el",
+                axum::routing::post(|Extension(id): Extension<RequestId>| async move {
+                    assert_eq!(current().as_ref(), Some(&id));
+                    id.as_str().to_string()
+                }),
+            )
+            .layer(axum::middleware::from_fn(propagate));
+
+        let mut request = Request::builder().method("POST").uri("/el");
+        if let Some(header) = header {
+            request = request.header("x-request-id", header);
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let returned = response.headers()[&HEADER].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (returned, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_client_request_id_is_kept() {
+        let (returned, seen) = request_id_of(Some("client-req-42")).await;
+        assert_eq!(returned, "client-req-42");
+        assert_eq!(seen, "client-req-42");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_generated() {
+        let (returned, seen) = request_id_of(None).await;
+        assert_eq!(returned, seen);
+        assert!(uuid::Uuid::parse_str(&returned).is_ok());
+
+        // Unusable client IDs are replaced
+        let too_long = "a".repeat(MAX_LEN + 1);
+        for header in ["", "has space", too_long.as_str()] {
+            let (returned, _) = request_id_of(Some(header)).await;
+            assert!(uuid::Uuid::parse_str(&returned).is_ok(), "{header:?}");
+        }
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! Contains the application state including EL/CL node states and chain head tracking.
 
-use std::sync::Arc;
+use std::collections::HashSet;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// State for an EL (Execution Layer) node
@@ -18,6 +20,8 @@ pub struct ElNodeState {
     pub ws_url: String,
     /// Whether this is a primary node (true) or backup (false)
     pub is_primary: bool,
+    /// Tags used by method routing rules
+    pub tags: Vec<String>,
     /// Current block number reported by the node
     pub block_number: u64,
     /// Whether the last health check succeeded (node was reachable)
@@ -28,6 +32,14 @@ pub struct ElNodeState {
     pub lag: u64,
     /// Number of consecutive health check failures
     pub consecutive_failures: u32,
+    /// Number of consecutive health check successes
+    pub consecutive_successes: u32,
+    /// Until when the node is ejected from rotation for failing proxied requests
+    pub ejected_until: Option<Instant>,
+    /// Duration of the last health check in milliseconds
+    pub check_latency_ms: u64,
+    /// Number of times the node went from healthy to unhealthy
+    pub flaps: u32,
 }
 
 impl ElNodeState {
@@ -38,13 +50,24 @@ impl ElNodeState {
             http_url: node.http_url.clone(),
             ws_url: node.ws_url.clone(),
             is_primary,
+            tags: node.tags.clone(),
             block_number: 0,
             check_ok: false,   // Start with check not ok
             is_healthy: false, // Start unhealthy until health check passes
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
         }
     }
+
+    /// Whether the node is currently ejected by outlier detection
+    pub fn is_ejected(&self) -> bool {
+        self.ejected_until
+            .is_some_and(|until| until > Instant::now())
+    }
 }
 
 /// State for a CL (Consensus Layer) node
@@ -54,6 +77,10 @@ pub struct ClNodeState {
     pub name: String,
     /// Base URL for beacon API
     pub url: String,
+    /// Whether this is a primary node (vs backup)
+    pub is_primary: bool,
+    /// Tags used by path routing rules
+    pub tags: Vec<String>,
     /// Current slot reported by the node
     pub slot: u64,
     /// Whether the health endpoint returns 200
@@ -64,19 +91,61 @@ pub struct ClNodeState {
     pub lag: u64,
     /// Number of consecutive health check failures
     pub consecutive_failures: u32,
+    /// Number of consecutive health check successes
+    pub consecutive_successes: u32,
+    /// Until when the node is ejected from rotation for failing proxied requests
+    pub ejected_until: Option<Instant>,
+    /// Duration of the last health check in milliseconds
+    pub check_latency_ms: u64,
+    /// Number of times the node went from healthy to unhealthy
+    pub flaps: u32,
 }
 
 impl ClNodeState {
     /// Create a ClNodeState from a Cl config
-    pub fn from_config(node: &crate::config::Cl) -> Self {
+    pub fn from_config(node: &crate::config::Cl, is_primary: bool) -> Self {
         Self {
             name: node.name.clone(),
             url: node.url.clone(),
+            is_primary,
+            tags: node.tags.clone(),
             slot: 0,
             health_ok: false,  // Start with health not ok
             is_healthy: false, // Start unhealthy until health check passes
             lag: 0,
             consecutive_failures: 0,
+            consecutive_successes: 0,
+            ejected_until: None,
+            check_latency_ms: 0,
+            flaps: 0,
+        }
+    }
+
+    /// Whether the node is currently ejected by outlier detection
+    pub fn is_ejected(&self) -> bool {
+        self.ejected_until
+            .is_some_and(|until| until > Instant::now())
+    }
+}
+
+/// Chain head of a layer and when it was first seen, to detect stalls
+#[derive(Debug, Clone, Copy)]
+pub struct HeadProgress {
+    /// Highest chain head seen
+    pub head: u64,
+    /// When the chain head was first seen
+    pub since: Instant,
+    /// Whether the chain head is reported stalled
+    pub stalled: bool,
+}
+
+impl HeadProgress {
+    /// Progress at a newly seen chain head
+    pub fn new(head: u64) -> Self {
+        Self {
+            head,
+            since: Instant::now(),
+            stalled: false,
         }
     }
 }
@@ -94,16 +163,73 @@ pub struct AppState {
     pub cl_chain_head: AtomicU64,
     /// Whether we're in failover mode (using backup EL nodes)
     pub el_failover_active: AtomicBool,
+    /// Whether we're in failover mode (using backup CL nodes)
+    pub cl_failover_active: AtomicBool,
+    /// When EL failover was last activated (None while inactive)
+    pub el_failover_since: Mutex<Option<Instant>>,
+    /// When CL failover was last activated (None while inactive)
+    pub cl_failover_since: Mutex<Option<Instant>>,
+    /// Whether no EL node was healthy after the last health check
+    pub el_all_down: AtomicBool,
+    /// Whether no CL node was healthy after the last health check
+    pub cl_all_down: AtomicBool,
+    /// EL chain head progress, for stall detection
+    pub el_head_progress: Mutex<HeadProgress>,
+    /// CL chain head progress, for stall detection
+    pub cl_head_progress: Mutex<HeadProgress>,
+    /// Time without a new chain head before the chain is reported stalled (zero = never)
+    pub chain_stall_timeout: Duration,
+    /// Method routing rules for the EL proxy
+    pub el_routes: Vec<crate::config::MethodRoute>,
+    /// Methods clients may call on the EL proxy
+    pub el_method_filter: crate::config::MethodFilter,
+    /// Transaction broadcast settings for the EL proxy
+    pub el_broadcast: crate::config::Broadcast,
+    /// Publish broadcast settings for the CL proxy
+    pub cl_broadcast: crate::config::ClBroadcast,
+    /// Beacon API path rules for the CL proxy
+    pub cl_routes: Vec<crate::config::ClRoute>,
+    /// EL response cache (None when caching is disabled)
+    pub el_cache: Option<crate::proxy::cache::ResponseCache>,
+    /// Coalescing of identical in-flight reads (None when disabled)
+    pub coalescer: Option<crate::proxy::coalesce::Coalescer>,
+    /// Hedging of slow EL reads (None when disabled)
+    pub el_hedger: Option<crate::proxy::hedge::Hedger>,
+    /// Rules for retrying EL calls on another node (empty when retries are disabled)
+    pub el_retry_rules: Vec<crate::config::RetryRule>,
+    /// API key authentication (None when auth is disabled)
+    pub auth: Option<crate::auth::Authenticator>,
+    /// Log of recent health events
+    pub events: crate::events::EventLog,
+    /// Passive outlier detection (None when disabled)
+    pub outlier_detector: Option<crate::proxy::outlier::OutlierDetector>,
+    /// JSON-RPC methods EL metrics are labelled with
+    pub el_metric_methods: HashSet<String>,
     /// Maximum allowed EL lag in blocks
     pub max_el_lag: u64,
     /// Maximum allowed CL lag in slots
     pub max_cl_lag: u64,
     /// Proxy request timeout in milliseconds
     pub proxy_timeout_ms: u64,
+    /// HTTP client shared by proxied upstream requests, bounded by the proxy timeout
+    pub http_client: reqwest::Client,
+    /// HTTP client shared by upstream event streams, which stay open for as long as
+    /// the client listens and so have no overall timeout
+    pub stream_client: reqwest::Client,
     /// Maximum number of retry attempts
     pub max_retries: u32,
+    /// Timeout for a single health check request
+    pub health_check_timeout: Duration,
     /// Number of consecutive health check failures before marking node as unhealthy
     pub health_check_max_failures: u32,
+    /// Number of consecutive health check successes before marking node as healthy again
+    pub health_check_recovery_successes: u32,
+    /// Minimum time failover stays active before traffic returns to primary nodes
+    pub failover_min_dwell: Duration,
+    /// Maximum number of calls accepted in a single JSON-RPC batch (0 = unlimited)
+    pub max_batch_size: usize,
+    /// Whether to split JSON-RPC batches across all healthy EL nodes
+    pub split_batches: bool,
 }
 
 impl AppState {
@@ -122,8 +248,16 @@ impl AppState {
             el_nodes.push(ElNodeState::from_config(node, false));
         }
 
-        // Create CL node states
-        let cl_nodes: Vec<ClNodeState> = config.cl.iter().map(ClNodeState::from_config).collect();
+        // Create CL node states - primary nodes first, then backup
+        let mut cl_nodes = Vec::new();
+
+        for node in &config.cl.primary {
+            cl_nodes.push(ClNodeState::from_config(node, true));
+        }
+
+        for node in &config.cl.backup {
+            cl_nodes.push(ClNodeState::from_config(node, false));
+        }
 
         Self {
             el_nodes: Arc::new(RwLock::new(el_nodes)),
@@ -131,11 +265,66 @@ impl AppState {
             el_chain_head: AtomicU64::new(0),
             cl_chain_head: AtomicU64::new(0),
             el_failover_active: AtomicBool::new(false),
+            cl_failover_active: AtomicBool::new(false),
+            el_failover_since: Mutex::new(None),
+            cl_failover_since: Mutex::new(None),
+            el_all_down: AtomicBool::new(false),
+            cl_all_down: AtomicBool::new(false),
+            el_head_progress: Mutex::new(HeadProgress::new(0)),
+            cl_head_progress: Mutex::new(HeadProgress::new(0)),
+            chain_stall_timeout: Duration::from_millis(config.global.chain_stall_timeout_ms),
+            el_routes: config.el.routes.clone(),
+            el_method_filter: config.el.method_filter.clone(),
+            el_broadcast: config.el.broadcast.clone(),
+            cl_broadcast: config.cl_broadcast.clone(),
+            cl_routes: config.cl_routes.clone(),
+            el_cache: config
+                .el
+                .cache
+                .enabled
+                .then(|| crate::proxy::cache::ResponseCache::new(&config.el.cache)),
+            coalescer: config
+                .global
+                .coalesce_requests
+                .then(crate::proxy::coalesce::Coalescer::new),
+            el_hedger: config
+                .el
+                .hedging
+                .enabled
+                .then(|| crate::proxy::hedge::Hedger::new(&config.el.hedging)),
+            el_retry_rules: if config.el.retry.enabled {
+                config.el.retry.rules.clone()
+            } else {
+                Vec::new()
+            },
+            auth: config
+                .auth
+                .enabled
+                .then(|| crate::auth::Authenticator::new(&config.auth)),
+            events: crate::events::EventLog::new(config.global.event_log_size),
+            outlier_detector: config
+                .outlier_detection
+                .enabled
+                .then(|| crate::proxy::outlier::OutlierDetector::new(&config.outlier_detection)),
+            el_metric_methods: config.metrics.el_methods.iter().cloned().collect(),
             max_el_lag: config.global.max_el_lag_blocks,
             max_cl_lag: config.global.max_cl_lag_slots,
             proxy_timeout_ms: config.global.proxy_timeout_ms,
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_millis(config.global.proxy_timeout_ms))
+                .build()
+                .expect("Failed to build HTTP client"),
+            stream_client: reqwest::Client::builder()
+                .connect_timeout(crate::proxy::sse::CONNECT_TIMEOUT)
+                .build()
+                .expect("Failed to build HTTP client"),
             max_retries: config.global.max_retries,
+            health_check_timeout: Duration::from_millis(config.global.health_check_timeout()),
             health_check_max_failures: config.global.health_check_max_failures,
+            health_check_recovery_successes: config.global.health_check_recovery_successes,
+            failover_min_dwell: Duration::from_millis(config.global.failover_min_dwell_ms),
+            max_batch_size: config.global.max_batch_size,
+            split_batches: config.global.split_batches,
         }
     }
 }
@@ -209,9 +398,9 @@ url = "http://localhost:5053"
     #[test]
     fn test_cl_node_state_from_config() {
         let config = sample_config();
-        let cl_node = &config.cl[0];
+        let cl_node = &config.cl.primary[0];
 
-        let state = ClNodeState::from_config(cl_node);
+        let state = ClNodeState::from_config(cl_node, true);
 
         assert_eq!(state.name, "lighthouse-1");
         assert_eq!(state.url, "http://localhost:5052");
@@ -240,6 +429,7 @@ url = "http://localhost:5053"
 
         // Check failover starts as inactive
         assert!(!state.el_failover_active.load(Ordering::SeqCst));
+        assert!(!state.cl_failover_active.load(Ordering::SeqCst));
 
         // Check max lag values from config
         assert_eq!(state.max_el_lag, 5);
@@ -0,0 +1,339 @@
+//! Logging and distributed tracing
+//!
+//! Installs the `tracing` subscriber: application logs and the access log as text or
+//! JSON lines, and, when tracing is enabled, span export to an OTLP collector. W3C trace
+//! context (`traceparent`) is extracted from client requests and injected into requests
+//! sent to upstream nodes.
+
+use std::future::Future;
+use std::path::Path;
+
+use axum::http::{HeaderMap, HeaderName, HeaderValue};
+use eyre::{Result, WrapErr};
+use opentelemetry::global;
+use opentelemetry::propagation::{Extractor, Injector};
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use tracing::{Instrument, Level, Span};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::{EnvFilter, Targets};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry};
+
+use crate::access_log;
+use crate::config::{self, LogFormat, LogRotation};
+use crate::request_id;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Installed log and span outputs, flushed on shutdown
+pub struct Telemetry {
+    tracer_provider: Option<SdkTracerProvider>,
+    /// Flushes the access log file when dropped
+    _access_log_guard: Option<WorkerGuard>,
+}
+
+impl Telemetry {
+    /// Flush buffered spans and access log lines
+    pub fn shutdown(self) -> Result<()> {
+        if let Some(provider) = &self.tracer_provider {
+            provider.shutdown().wrap_err("failed to flush traces")?;
+        }
+        Ok(())
+    }
+}
+
+/// Install the global `tracing` subscriber
+pub fn init(logging: &config::Logging, tracing: &config::Tracing) -> Result<Telemetry> {
+    let json = logging.format == LogFormat::Json;
+
+    // Application logs; access log events only go to the access log
+    let mut layers = vec![
+        fmt_layer(json, true, std::io::stdout)
+            .with_filter(log_filter())
+            .boxed(),
+    ];
+
+    let mut access_log_guard = None;
+    let access_log = &logging.access_log;
+    if access_log.enabled {
+        let layer = match &access_log.file {
+            Some(file) => {
+                let (writer, guard) = access_log_writer(file, access_log)?;
+                access_log_guard = Some(guard);
+                fmt_layer(json, false, writer)
+            }
+            None => fmt_layer(json, true, std::io::stdout),
+        };
+        layers.push(
+            layer
+                .with_filter(Targets::new().with_target(access_log::TARGET, Level::INFO))
+                .boxed(),
+        );
+    }
+
+    let tracer_provider = tracing
+        .enabled
+        .then(|| tracer_provider(tracing))
+        .transpose()?;
+    if let Some(provider) = &tracer_provider {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        layers.push(
+            tracing_opentelemetry::layer()
+                .with_tracer(provider.tracer("vixy"))
+                .with_filter(log_filter())
+                .boxed(),
+        );
+    }
+
+    tracing_subscriber::registry().with(layers).init();
+
+    Ok(Telemetry {
+        tracer_provider,
+        _access_log_guard: access_log_guard,
+    })
+}
+
+/// Log level filter from `RUST_LOG`, at least INFO, without access log events
+fn log_filter() -> EnvFilter {
+    EnvFilter::from_default_env()
+        .add_directive(Level::INFO.into())
+        .add_directive(
+            format!("{}=off", access_log::TARGET)
+                .parse()
+                .expect("valid directive"),
+        )
+}
+
+/// Log lines in the configured format, written to `writer`; `ansi` colours text lines
+fn fmt_layer<W>(json: bool, ansi: bool, writer: W) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(ansi)
+        .with_writer(writer);
+    if json {
+        layer.json().flatten_event(true).boxed()
+    } else {
+        layer.boxed()
+    }
+}
+
+/// Non-blocking writer to the access log file, rotated as configured
+fn access_log_writer(file: &str, config: &config::AccessLog) -> Result<(NonBlocking, WorkerGuard)> {
+    let path = Path::new(file);
+    let directory = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let rotation = match config.rotation {
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+
+    std::fs::create_dir_all(directory)
+        .wrap_err_with(|| format!("failed to create access log directory for {file}"))?;
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(file_name);
+    if config.max_files > 0 {
+        builder = builder.max_log_files(config.max_files);
+    }
+    let appender = builder
+        .build(directory)
+        .wrap_err_with(|| format!("failed to open access log {file}"))?;
+
+    Ok(tracing_appender::non_blocking(appender))
+}
+
+/// Build a tracer provider exporting spans to the configured OTLP/HTTP endpoint
+pub fn tracer_provider(config: &config::Tracing) -> Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&config.endpoint)
+        .build()
+        .wrap_err("failed to build OTLP span exporter")?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            config.sample_ratio,
+        ))))
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build())
+}
+
+/// Continue the trace a client request belongs to, if it carries trace context
+pub fn set_parent_from(span: &Span, headers: &HeaderMap) {
+    let context = global::get_text_map_propagator(|p| p.extract(&HeaderExtractor(headers)));
+    // Fails only if the span isn't recorded by the OpenTelemetry layer
+    let _ = span.set_parent(context);
+}
+
+/// Headers for requests to upstream nodes: the ID of the request being served and the
+/// trace context of the current span (when span export is enabled)
+pub fn upstream_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(id) = request_id::current() {
+        headers.insert(request_id::HEADER, id.header_value());
+    }
+    let context = Span::current().context();
+    global::get_text_map_propagator(|p| {
+        p.inject_context(&context, &mut HeaderInjector(&mut headers))
+    });
+    headers
+}
+
+/// Carry the current span and request ID into a task spawned while serving a request
+pub fn propagate<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let future = future.in_current_span();
+    let id = request_id::current();
+    async move {
+        match id {
+            Some(id) => request_id::scope(id, future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// Read trace context from request headers
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+/// Write trace context to request headers
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    fn tracing_config(endpoint: String) -> config::Tracing {
+        config::Tracing {
+            enabled: true,
+            endpoint,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_trace_context_is_propagated_upstream() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut client_headers = HeaderMap::new();
+            client_headers.insert("traceparent", HeaderValue::from_static(TRACEPARENT));
+
+            let span = tracing::info_span!("el_request");
+            set_parent_from(&span, &client_headers);
+            let _guard = span.enter();
+
+            let upstream_headers = upstream_headers();
+
+            let traceparent = upstream_headers["traceparent"].to_str().unwrap();
+            let parts: Vec<&str> = traceparent.split('-').collect();
+            // Same trace, but the parent is now our span
+            assert_eq!(parts[1], "4bf92f3577b34da6a3ce929d0e0e4736");
+            assert_ne!(parts[2], "00f067aa0ba902b7");
+            assert_eq!(parts[3], "01");
+        });
+    }
+
+    #[test]
+    fn test_access_log_file_is_written() {
+        let directory = std::env::temp_dir().join(format!("vixy-access-{}", std::process::id()));
+        let file = directory.join("access.log");
+        let config = config::AccessLog {
+            enabled: true,
+            file: Some(file.to_string_lossy().into_owned()),
+            rotation: LogRotation::Never,
+            max_files: 0,
+        };
+
+        let (writer, guard) =
+            access_log_writer(config.file.as_deref().unwrap(), &config).expect("Should open");
+        let subscriber = tracing_subscriber::registry().with(fmt_layer(true, false, writer));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: access_log::TARGET, status = 200, "request");
+        });
+        drop(guard);
+
+        let contents = std::fs::read_to_string(&file).expect("Should write access log");
+        let line: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+        assert_eq!(line["status"], 200);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spans_are_exported_to_collector() {
+        let collector = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/traces"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1..)
+            .mount(&collector)
+            .await;
+
+        let config = tracing_config(format!("{}/v1/traces", collector.uri()));
+        let provider = tokio::task::spawn_blocking(move || tracer_provider(&config))
+            .await
+            .unwrap()
+            .expect("Should build tracer provider");
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("health_check_cycle").entered();
+        });
+
+        tokio::task::spawn_blocking(move || provider.shutdown())
+            .await
+            .unwrap()
+            .expect("Should flush spans");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
@@ -18,11 +18,17 @@ fn make_cl_node(name: &str, slot: u64, health_ok: bool) -> ClNodeState {
     ClNodeState {
         name: name.to_string(),
         url: "http://localhost:5052".to_string(),
+        is_primary: true,
+        tags: vec![],
         slot,
         health_ok,
         is_healthy: false,
         lag: 0,
         consecutive_failures: 0,
+        consecutive_successes: 0,
+        ejected_until: None,
+        check_latency_ms: 0,
+        flaps: 0,
     }
 }
 
@@ -102,7 +108,7 @@ fn when_health_check_runs(world: &mut VixyWorld) {
 
         // Calculate health for each EL node
         for node in world.el_nodes.iter_mut() {
-            calculate_el_health(node, world.el_chain_head, world.max_el_lag, 3);
+            calculate_el_health(node, world.el_chain_head, world.max_el_lag, 3, 1);
         }
     }
 
@@ -115,7 +121,7 @@ fn when_health_check_runs(world: &mut VixyWorld) {
 
         // Calculate health for each CL node
         for node in world.cl_nodes.iter_mut() {
-            calculate_cl_health(node, world.cl_chain_head, world.max_cl_lag, 3);
+            calculate_cl_health(node, world.cl_chain_head, world.max_cl_lag, 3, 1);
         }
     }
 }
@@ -15,11 +15,16 @@ fn make_el_node(name: &str, block_number: u64, check_ok: bool) -> ElNodeState {
         http_url: "http://localhost:8545".to_string(),
         ws_url: "ws://localhost:8546".to_string(),
         is_primary: true,
+        tags: vec![],
         block_number,
         check_ok,
         is_healthy: false,
         lag: 0,
         consecutive_failures: 0,
+        consecutive_successes: 0,
+        ejected_until: None,
+        check_latency_ms: 0,
+        flaps: 0,
     }
 }
 
@@ -634,29 +634,29 @@ async fn ensure_all_services_running(world: &mut IntegrationWorld) {
     for attempt in 1..=max_attempts {
         tokio::time::sleep(Duration::from_secs(5)).await;
 
-        if let Ok(resp) = client.get(&url).send().await {
-            if let Ok(status) = resp.json::<serde_json::Value>().await {
-                let el_healthy = status["el_nodes"]
-                    .as_array()
-                    .map(|nodes| {
-                        nodes
-                            .iter()
-                            .all(|n| n["is_healthy"].as_bool().unwrap_or(false))
-                    })
-                    .unwrap_or(false);
-                let cl_healthy = status["cl_nodes"]
-                    .as_array()
-                    .map(|nodes| {
-                        nodes
-                            .iter()
-                            .all(|n| n["is_healthy"].as_bool().unwrap_or(false))
-                    })
-                    .unwrap_or(false);
-
-                if el_healthy && cl_healthy {
-                    eprintln!("All nodes healthy after {attempt} attempts");
-                    return;
-                }
+        if let Ok(resp) = client.get(&url).send().await
+            && let Ok(status) = resp.json::<serde_json::Value>().await
+        {
+            let el_healthy = status["el_nodes"]
+                .as_array()
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .all(|n| n["is_healthy"].as_bool().unwrap_or(false))
+                })
+                .unwrap_or(false);
+            let cl_healthy = status["cl_nodes"]
+                .as_array()
+                .map(|nodes| {
+                    nodes
+                        .iter()
+                        .all(|n| n["is_healthy"].as_bool().unwrap_or(false))
+                })
+                .unwrap_or(false);
+
+            if el_healthy && cl_healthy {
+                eprintln!("All nodes healthy after {attempt} attempts");
+                return;
             }
         }
     }
@@ -983,13 +983,13 @@ async fn verify_continue_receiving_headers(world: &mut IntegrationWorld) {
     while start.elapsed() < timeout && !received_header {
         match tokio::time::timeout(Duration::from_secs(5), conn.receiver.next()).await {
             Ok(Some(Ok(WsMessage::Text(text)))) => {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                    if json.get("params").is_some() {
-                        // This is a subscription notification
-                        received_header = true;
-                        world.last_subscription_event = Some(json);
-                        eprintln!("Received block header after reconnection");
-                    }
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text)
+                    && json.get("params").is_some()
+                {
+                    // This is a subscription notification
+                    received_header = true;
+                    world.last_subscription_event = Some(json);
+                    eprintln!("Received block header after reconnection");
                 }
             }
             Ok(Some(Ok(WsMessage::Pong(_)))) => {
@@ -1049,17 +1049,16 @@ async fn verify_same_subscription_id(world: &mut IntegrationWorld) {
     while start.elapsed() < timeout {
         match tokio::time::timeout(Duration::from_secs(5), conn.receiver.next()).await {
             Ok(Some(Ok(WsMessage::Text(text)))) => {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                    if let Some(params) = json.get("params") {
-                        if let Some(sub_id) = params.get("subscription").and_then(|s| s.as_str()) {
-                            assert_eq!(
-                                sub_id, original_sub_id,
-                                "Subscription ID changed after reconnection! Original: {original_sub_id}, Got: {sub_id}"
-                            );
-                            eprintln!("Verified subscription ID preserved: {sub_id}");
-                            return;
-                        }
-                    }
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text)
+                    && let Some(params) = json.get("params")
+                    && let Some(sub_id) = params.get("subscription").and_then(|s| s.as_str())
+                {
+                    assert_eq!(
+                        sub_id, original_sub_id,
+                        "Subscription ID changed after reconnection! Original: {original_sub_id}, Got: {sub_id}"
+                    );
+                    eprintln!("Verified subscription ID preserved: {sub_id}");
+                    return;
                 }
             }
             Ok(Some(Ok(WsMessage::Ping(data)))) => {
@@ -1258,12 +1257,12 @@ async fn client_subscribes_to(world: &mut IntegrationWorld, subscription_type: S
     // Try to receive subscription response
     match tokio::time::timeout(Duration::from_secs(5), conn.receiver.next()).await {
         Ok(Some(Ok(WsMessage::Text(text)))) => {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
-                if let Some(result) = json.get("result") {
-                    world.subscription_id = result.as_str().map(String::from);
-                    world.last_response_body = Some(text.to_string());
-                    eprintln!("✓ Received subscription ID: {:?}", world.subscription_id);
-                }
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text)
+                && let Some(result) = json.get("result")
+            {
+                world.subscription_id = result.as_str().map(String::from);
+                world.last_response_body = Some(text.to_string());
+                eprintln!("✓ Received subscription ID: {:?}", world.subscription_id);
             }
         }
         _ => {